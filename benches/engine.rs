@@ -0,0 +1,137 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::board::{BitBoard, Board};
+use termsweeper::config::Config;
+use termsweeper::game::{Field, Game};
+use termsweeper::generator::{BoardGenerator, NoGuess, Uniform};
+use termsweeper::solver;
+
+/// Board sizes as (rows, columns), from Expert up to the largest boards people play over SSH.
+const SIZES: [(usize, usize); 3] = [(16, 30), (50, 100), (100, 300)];
+
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    for density in [0.1, 0.2, 0.5] {
+        for size in SIZES {
+            let mines = (size.0 * size.1) as f64 * density;
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}x{}", size.1, size.0), density),
+                &mines,
+                |b, &mines| b.iter(|| Field::new(size, mines as usize).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// Play a board out from the middle the way the no-guess generator checks one: clearing whatever the solver finds safe,
+/// until it finds nothing more.
+fn play_out(board: &mut impl Board) {
+    let (rows, cols) = board.size();
+    board.clear_cell((rows / 2, cols / 2));
+    loop {
+        let analysis = solver::analyze(board);
+        if analysis.safe().is_empty() {
+            return;
+        }
+        for &pos in analysis.safe() {
+            board.clear_cell(pos);
+        }
+    }
+}
+
+/// No-guess boards, from the same seed each time so that the same layouts are tried.
+fn no_guess(c: &mut Criterion) {
+    let mut group = c.benchmark_group("no_guess");
+    for (size, mines) in [((9, 9), 10), ((16, 16), 40), ((16, 30), 80)] {
+        group.bench_function(format!("{}x{}", size.1, size.0), |b| b.iter(|| {
+            NoGuess.generate(size, mines, &mut StdRng::seed_from_u64(0)).unwrap()
+        }));
+    }
+    group.finish();
+}
+
+fn play_outs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("play_out");
+    for size in SIZES {
+        // Beginner density, with the middle kept clear, so that there's a lot to clear
+        let center = (size.0 / 2, size.1 / 2);
+        let mines = Uniform.place_mines(size, size.0 * size.1 / 8, &mut StdRng::seed_from_u64(0)).unwrap()
+            .into_iter()
+            .filter(|pos| pos.0.abs_diff(center.0) > 1 || pos.1.abs_diff(center.1) > 1)
+            .collect::<Vec<_>>();
+        group.bench_function(format!("{}x{}", size.1, size.0), |b| b.iter_batched(
+            || Field::from_mines(size, &mines).unwrap(),
+            |mut field| play_out(&mut field),
+            BatchSize::LargeInput,
+        ));
+        group.bench_function(format!("{}x{}/bitboard", size.1, size.0), |b| b.iter_batched(
+            || BitBoard::from_mines(size, &mines).unwrap(),
+            |mut board| play_out(&mut board),
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+fn flood_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flood_fill");
+    for size in SIZES {
+        group.bench_function(format!("{}x{}", size.1, size.0), |b| b.iter_batched(
+            || Field::new(size, 0).unwrap(),
+            |mut field| field.clear_cell((size.0 / 2, size.1 / 2)),
+            BatchSize::LargeInput,
+        ));
+        group.bench_function(format!("{}x{}/bitboard", size.1, size.0), |b| b.iter_batched(
+            || BitBoard::from_mines(size, &[]).unwrap(),
+            |mut board| board.clear_cell((size.0 / 2, size.1 / 2)),
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+/// A field at Expert density with an opening revealed in the middle, so the solver has a frontier to work on.
+fn opened_field(size: (usize, usize)) -> Field {
+    loop {
+        let mut field = Field::new(size, size.0 * size.1 / 5).unwrap();
+        if field.clear_cell((size.0 / 2, size.1 / 2)).is_some_and(|reveal| reveal.cells.len() > 1) {
+            return field;
+        }
+    }
+}
+
+fn solver(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solver");
+    for size in SIZES {
+        group.bench_function(format!("{}x{}", size.1, size.0), |b| b.iter_batched_ref(
+            || opened_field(size),
+            |field| solver::analyze(field),
+            BatchSize::LargeInput,
+        ));
+        group.bench_function(format!("{}x{}/bitboard", size.1, size.0), |b| b.iter_batched_ref(
+            || BitBoard::from(&opened_field(size)),
+            |board| solver::analyze(board),
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+fn render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for size in SIZES {
+        let terminal_size = (size.1 as u16 + 40, size.0 as u16 + 20);
+        group.bench_function(format!("{}x{}", size.1, size.0), |b| b.iter_batched_ref(
+            || Game::headless(opened_field(size), Config::default(), terminal_size, std::io::sink()),
+            |game| game.render().unwrap(),
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation, no_guess, play_outs, flood_fill, solver, render);
+criterion_main!(benches);
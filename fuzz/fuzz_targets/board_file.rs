@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use termsweeper::board_file;
+
+fuzz_target!(|data: &str| {
+    if let Ok(field) = board_file::parse(data) {
+        // Anything that parses has to survive being written out and read back in
+        let written = board_file::write(&field);
+        assert_eq!(board_file::write(&board_file::parse(&written).unwrap()), written);
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use termsweeper::config::Config;
+
+fuzz_target!(|text: &str| {
+    let _ = Config::parse(text);
+});
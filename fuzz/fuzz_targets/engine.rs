@@ -0,0 +1,48 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use termsweeper::board::BitBoard;
+use termsweeper::engine::Command;
+use termsweeper::game::{Direction, Field};
+
+#[path = "../../tests/reference/mod.rs"]
+mod reference;
+
+const DIRECTIONS: [Direction; 10] = [
+    Direction::Left,
+    Direction::Right,
+    Direction::Up,
+    Direction::Down,
+    Direction::UpLeft,
+    Direction::UpRight,
+    Direction::DownLeft,
+    Direction::DownRight,
+    Direction::PrevLayer,
+    Direction::NextLayer,
+];
+
+// The board's size in the first two bytes, then a bit for each cell saying whether it's a mine, then a pair of bytes
+// for each command: which command, and the cell (or direction) it's for.
+fuzz_target!(|data: &[u8]| {
+    let [rows, cols, data @ ..] = data else { return };
+    let size = (*rows as usize % 16 + 1, *cols as usize % 16 + 1);
+    let cells = size.0 * size.1;
+    let (mines, commands) = data.split_at(data.len().min(cells.div_ceil(8)));
+    let mines = (0..cells)
+        .filter(|i| mines.get(i / 8).is_some_and(|byte| byte & (1 << (i % 8)) != 0))
+        .map(|i| (i / size.1, i % size.1))
+        .collect::<Vec<_>>();
+    let field = Field::from_mines(size, &mines).unwrap();
+    let commands = commands.chunks_exact(2).map(|command| {
+        let pos = (command[1] as usize % cells / size.1, command[1] as usize % cells % size.1);
+        match command[0] % 4 {
+            0 => Command::Reveal(pos),
+            1 => Command::Flag(pos),
+            2 => Command::Chord(pos),
+            _ => Command::Move(DIRECTIONS[command[1] as usize % DIRECTIONS.len()]),
+        }
+    }).collect::<Vec<_>>();
+    reference::play_board(&mut BitBoard::from(&field), commands.iter().copied());
+    reference::play(field, commands);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use termsweeper::replay::Replay;
+
+fuzz_target!(|text: &str| {
+    // Anything that reads back has to play back, if not always to the same penalties
+    if let Ok(replay) = Replay::parse(text) {
+        let _ = replay.export_cast(&mut std::io::sink());
+    }
+});
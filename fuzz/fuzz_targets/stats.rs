@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use termsweeper::stats::Stats;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Stats::parse(data);
+});
@@ -0,0 +1,198 @@
+# German
+
+## Controls
+action-move = bewegen
+action-reveal = aufdecken
+action-flag = markieren
+action-note = notiz
+action-ping = zeigen
+action-quit = beenden
+action-frontier = fokus
+control-hint = tipp
+control-layer = ebene
+control-board = feld
+control-export = export
+control-menu = menü
+control-help = hilfe
+title-controls = Steuerung
+control-save = speichern
+
+## Help
+help-goal = Decke jedes Feld auf, das keine Mine ist, ohne eine Mine aufzudecken.
+help-numbers = Eine Zahl gibt an, wie viele der Felder um sie herum Minen sind.
+help-flags = Markiere ein Feld mit einer Flagge als Mine, damit es nicht versehentlich aufgedeckt wird.
+help-chords = Eine Zahl mit ebenso vielen Flaggen um sich herum aufzudecken, deckt ihre übrigen Nachbarn auf.
+help-bookmarks = Strg+B merkt sich das Spielfeld, um es später mit `termsweeper bookmarks` noch einmal zu versuchen.
+help-tabs = Strg+T öffnet ein weiteres Spiel in einem neuen Tab, Strg+Tab und Strg+Umschalt+Tab wechseln zwischen ihnen, und Strg+W (oder Beenden) schließt das aktuelle.
+help-commands = Mit : gibst du einen Befehl ein: new (oder new expert, oder new 16x30 99) fängt neu an, seed 42 fängt auf dem Spielfeld mit diesem Seed neu an, reveal 3 4 oder reveal C7 (oder flag, oder chord) macht einen Zug, goto C7 setzt den Cursor dorthin, glyphs ascii oder borders double ändert das Aussehen, save NAME speichert das Spielfeld, und stats zeigt die Statistik.
+help-presentation = Strg+P schaltet den Präsentationsmodus um, zum Streamen oder Unterrichten am Beamer: ein hellerer Cursor mit hervorgehobener Zeile und Spalte und eine große Uhr.
+help-header = Hilfe (Pfeile und Bild auf/ab zum Blättern, Esc zum Schließen)
+
+## Settings
+settings-hint = Enter zum Wählen, Esc zum Schließen
+preset-arrows = Pfeiltasten
+preset-wasd = WASD
+preset-vim = Vim
+preset-numpad = Ziffernblock
+preset-left-handed = Linkshändig
+
+## Playing
+paused = PAUSE
+clock-time = Zeit { $time }
+clock-time-left = Restzeit { $time }
+clock-cleared = Gelöst { $cleared }
+clock-clicks = Klicks { $clicks }/{ $max }
+clock-ladder = Leiter { $rung }/{ $rungs }: { $total }
+clock-latency = Eingabeverzögerung { $p50 } p50, { $p99 } p99
+window-title = termsweeper — { $board } — { $time } — noch { $left } Minen
+mistake-reveal = ({ $row }, { $col }) muss eine Mine sein. Aufdecken? (y/n)
+mistake-chord = ({ $row }, { $col }) muss eine Mine sein. Eine Markierung ist falsch. Aufdecken? (y/n)
+session-game = Spiel { $number } von { $count }
+versus-player = Spieler { $number }
+versus-computer = Computer
+
+## Messages
+toast-pasted = Eingefügter Text ignoriert
+toast-picture-saved = { $name }.svg gespeichert
+toast-pictures-saved = { $name }.svg und .png gespeichert
+toast-picture-failed = Bild konnte nicht gespeichert werden: { $error }
+toast-mine-moved = Eine Mine hat sich bewegt!
+toast-puzzle-solved = Rätsel gelöst!
+toast-replay-saved = Wiederholung in { $path } gespeichert
+toast-bookmarked = Spielfeld gemerkt
+toast-already-bookmarked = Das Spielfeld ist schon gemerkt
+toast-bookmark-failed = Spielfeld konnte nicht gemerkt werden: { $error }
+toast-bookmark-update-failed = Das Lesezeichen des Spielfelds konnte nicht aktualisiert werden: { $error }
+toast-bookmark-topology = Nur quadratische Spielfelder können gemerkt werden
+toast-command-failed = { $error }
+toast-cant-start-over = Nur ein einfaches Einzelspiel kann neu anfangen
+toast-board-invalid = Dieses Spielfeld kann nicht angelegt werden
+toast-board-size-clamped = Das Spielfeld ist jetzt { $rows }x{ $columns }, die nächste spielbare Größe
+toast-off-the-board = Dieses Feld liegt außerhalb des Spielfelds
+toast-board-saved = Spielfeld in { $path } gespeichert
+toast-board-save-failed = Spielfeld konnte nicht gespeichert werden: { $error }
+toast-share-saved = Text zum Teilen in { $path } gespeichert
+toast-share-failed = Text zum Teilen konnte nicht gespeichert werden: { $error }
+toast-mistakes = { $count } Fehler rot markiert
+toast-no-mistakes = Keine Fehler zum Ansehen
+toast-presenting = Präsentationsmodus an
+toast-not-presenting = Präsentationsmodus aus
+toast-achievement = Erfolg freigeschaltet: { $name }
+toast-engine-unreachable = Die Engine ist nicht erreichbar: { $error }
+toast-config-reloaded = Konfiguration neu geladen
+toast-config-failed = Konfiguration konnte nicht neu geladen werden: { $error }
+toast-stats-damaged = { $count } beschädigte Einträge aus der Statistik entfernt (in einer Kopie der Datei erhalten)
+generating-board = Spielfeld wird erstellt...
+ladder-total = Leiter geschafft in { $total }
+notify-board-ready = Dein Spielfeld ist fertig
+engine-bad-move = Die Engine hat einen ungültigen Zug gesendet: { $error }
+engine-quit = Die Engine wurde beendet
+
+## The end screen
+end-wins = { $name } gewinnt
+end-draw = Unentschieden
+end-won = Gewonnen
+end-cleared = { $cleared } gelöst
+end-time-up = Zeit um
+end-lost = Verloren
+end-flags = Markierungen
+end-flags-counts = { $right } richtig, { $wrong } falsch
+end-retry = Dieses Feld nochmal
+end-new-board = Neues Feld
+end-review = Fehler ansehen
+end-share = Text zum Teilen exportieren
+end-back = Zurück zum Menü
+share-header = termsweeper { $board }, { $mines } Minen
+share-won = Gewonnen in { $time } mit { $clicks } Klicks (3BV { $bbbv })
+share-lost = Verloren mit { $progress } % aufgedeckt nach { $time } und { $clicks } Klicks
+end-missed = Übersehen
+end-missed-mine = 1 Mine
+end-missed-mines = { $count } Minen
+
+## Presets and stats
+preset-beginner = Anfänger
+preset-intermediate = Fortgeschritten
+preset-expert = Experte
+preset-custom = Eigenes
+stats-rating = Wertung: { $rating } ({ $games } gewertete Spiele)
+stats-adaptive = Adaptive Stufe: { $level } %
+stats-damaged = { $count } beschädigte Einträge übersprungen
+stats-played = Gespielt
+stats-won = Gewonnen
+stats-win-rate = Quote
+stats-best = Bestzeit
+stats-win-rates = Gewinnquote
+stats-recent-times = Letzte Gewinnzeiten
+stats-times = Gewinnzeiten: { $preset }
+stats-deaths = Wo Spiele verloren wurden
+stats-death-spots = Ecken { $corners } %, Ränder { $edges } %, innen { $inside } %
+stats-ladder = Leiter: { $runs } Läufe, Bestzeit { $best }
+stats-imported = { $imported } Spiele importiert ({ $skipped } übersprungen)
+
+## Achievements
+achievement-first-win = Erster Sieg
+achievement-first-win-description = Gewinne ein Spiel
+achievement-fast-expert = Blitzschnell
+achievement-fast-expert-description = Gewinne ein Expertenspiel in unter 100 Sekunden
+achievement-win-streak = Siegesserie
+achievement-win-streak-description = Gewinne 10 Spiele in Folge
+achievement-no-flags = Ohne Markierung
+achievement-no-flags-description = Gewinne ein Spiel, ohne eine Markierung zu setzen
+achievement-half-density = Minenfeld
+achievement-half-density-description = Gewinne ein Feld, das mindestens zur Hälfte aus Minen besteht
+
+## Puzzle packs
+pack-title = { $name } ({ $done }/{ $total } gelöst)
+pack-help = Enter zum Spielen, q zum Beenden
+pack-locked = [gesperrt]
+pack-no-flags = keine Markierungen
+pack-clicks = { $max } Klicks
+bookmarks-title = Lesezeichen ({ $total })
+bookmarks-help = Enter zum Spielen, d zum Löschen, q zum Beenden
+bookmarks-empty = Noch keine Lesezeichen. Drücke ctrl+b während eines Spiels, um sein Spielfeld zu merken.
+bookmarks-line = { $cols }x{ $rows }, { $mines } Minen: { $attempts } Versuche, Bestwert { $best }
+
+## The board editor
+editor-mine = mine
+editor-void = lücke
+editor-size = Größe
+editor-mines = Minen
+editor-3bv = 3BV
+editor-no-mines = Setze mindestens eine Mine
+editor-no-safe-cells = Lass mindestens ein sicheres Feld frei
+editor-saved = In { $path } gespeichert
+
+## The custom game menu
+custom-title = Eigenes Spiel
+custom-help = hoch/runter zum Auswählen, links/rechts zum Ändern (mit Shift um 10), Enter zum Spielen, r zum Ziehen der Größe, s zum Speichern, q zum Beenden
+custom-suggestion = Du gewinnst { $rate } % der Spiele auf { $played }. { $next } versuchen? (t zum Versuchen, x zum Ausblenden)
+suggestion-offer = Du gewinnst { $rate } % der Spiele auf { $played }. { $next } versuchen? (t zum Versuchen, x zum Ausblenden, andere Taste zum Weiterspielen)
+daily-banner = Tägliche Herausforderung { $date }: { $status }. Die nächste in { $next } (spielen mit termsweeper daily)
+daily-not-attempted = noch nicht gespielt
+daily-in-progress = begonnen
+daily-lost = verloren
+daily-won = gewonnen in { $time }
+daily-done = Die heutige Herausforderung ist vorbei: { $status }. Tage in Folge gewonnen: { $streak }. Die nächste kommt in { $next }.
+custom-rows = Zeilen
+custom-columns = Spalten
+custom-mines = Minen
+custom-no-guess = Ohne Raten
+custom-hardcore = Hardcore
+custom-moving-mines = Wandernde Minen
+custom-on = an
+custom-off = aus
+custom-density = { $density } % Minen
+custom-too-many-mines = Zu viele Minen für diese Größe (höchstens { $max })
+custom-save-as = Speichern als: { $name }
+custom-saved = Als { $name } gespeichert, für --preset
+custom-name-taken = { $name } ist eine Standardvorgabe
+custom-saved-games = Gespeicherte Spiele
+custom-summary = { $rows }x{ $columns }, { $mines } Minen
+custom-resizing = Ecke mit den Pfeiltasten ziehen (mit Shift um 10), Enter zum Beenden
+
+## Profiles
+profile-title = Profile
+profile-help = hoch/runter zum Auswählen, Enter, um ab jetzt damit zu spielen, n für ein neues Profil, q zum Beenden
+profile-default = Standard
+profile-name = Neues Profil: { $name }
+profile-bad-name = { $name } kann kein Profilname sein
@@ -0,0 +1,201 @@
+# English, which every other catalog falls back to for anything it's missing.
+#
+# Each line is a message: an ID, then `=`, then the text. `{ $name }` is replaced with an argument. Labels (like
+# action names) are lined up in columns by the game, so they don't need padding here.
+
+## Controls
+action-move = move
+action-reveal = reveal
+action-flag = flag
+action-note = note
+action-ping = ping
+action-quit = quit
+action-frontier = focus
+control-hint = hint
+control-layer = layer
+control-board = board
+control-export = export
+control-menu = menu
+control-help = help
+title-controls = Controls
+control-save = save
+
+## Help
+help-goal = Reveal every cell that isn't a mine, without revealing a mine.
+help-numbers = A number is how many of the cells around it are mines.
+help-flags = Flag a cell to mark it as a mine, so it can't be revealed by accident.
+help-chords = Revealing a number with as many flags around it reveals the rest of its neighbors.
+help-bookmarks = Ctrl+B bookmarks the board, to try it again later from `termsweeper bookmarks`.
+help-tabs = Ctrl+T opens another game in a new tab, Ctrl+Tab and Ctrl+Shift+Tab switch between them, and Ctrl+W (or quitting) closes the current one.
+help-commands = Type : to give a command: new (or new expert, or new 16x30 99) to start over, seed 42 to start over on the board with that seed, reveal 3 4 or reveal C7 (or flag, or chord) to move, goto C7 to move the cursor there, glyphs ascii or borders double to change the look, save NAME to save the board, and stats to see the stats.
+help-presentation = Ctrl+P switches presentation mode, for streaming or teaching on a projector: a brighter cursor with its row and column highlighted, and a big clock.
+help-header = Help (arrows and page up/down to scroll, esc to close)
+
+## Settings
+settings-hint = enter to choose, esc to close
+preset-arrows = Arrows
+preset-wasd = WASD
+preset-vim = Vim
+preset-numpad = Numpad
+preset-left-handed = Left-handed
+
+## Playing
+paused = PAUSED
+clock-time = Time { $time }
+clock-time-left = Time left { $time }
+clock-cleared = Cleared { $cleared }
+clock-clicks = Clicks { $clicks }/{ $max }
+clock-ladder = Ladder { $rung }/{ $rungs }: { $total }
+clock-latency = Input lag { $p50 } p50, { $p99 } p99
+window-title = termsweeper — { $board } — { $time } — { $left } mines left
+mistake-reveal = ({ $row }, { $col }) must be a mine. Reveal? (y/n)
+mistake-chord = ({ $row }, { $col }) must be a mine. A flag is wrong. Chord? (y/n)
+session-game = Game { $number } of { $count }
+versus-player = Player { $number }
+versus-computer = Computer
+
+## Messages
+toast-pasted = Ignored pasted text
+toast-picture-saved = Saved { $name }.svg
+toast-pictures-saved = Saved { $name }.svg and .png
+toast-picture-failed = Couldn't save the picture: { $error }
+toast-mine-moved = A mine moved!
+toast-puzzle-solved = Puzzle solved!
+toast-replay-saved = Saved the replay to { $path }
+toast-bookmarked = Bookmarked the board
+toast-already-bookmarked = The board is already bookmarked
+toast-bookmark-failed = Couldn't bookmark the board: { $error }
+toast-bookmark-update-failed = Couldn't update the board's bookmark: { $error }
+toast-bookmark-topology = Only square boards can be bookmarked
+toast-command-failed = { $error }
+toast-cant-start-over = Only a plain single-player game can start over
+toast-board-invalid = That board can't be laid out
+toast-board-size-clamped = Made the board { $rows }x{ $columns }, the nearest size that can be played
+toast-off-the-board = That cell is off the board
+toast-board-saved = Saved the board to { $path }
+toast-board-save-failed = Couldn't save the board: { $error }
+toast-share-saved = Saved the share text to { $path }
+toast-share-failed = Couldn't save the share text: { $error }
+toast-mistakes = { $count } mistakes marked in red
+toast-no-mistakes = No mistakes to review
+toast-presenting = Presentation mode on
+toast-not-presenting = Presentation mode off
+toast-achievement = Achievement unlocked: { $name }
+toast-engine-unreachable = Couldn't reach the engine: { $error }
+toast-config-reloaded = Reloaded the config
+toast-config-failed = Couldn't reload the config: { $error }
+toast-stats-damaged = Dropped { $count } damaged records from the stats (kept in a copy of the file)
+generating-board = Laying out the board...
+ladder-total = Ladder cleared in { $total }
+notify-board-ready = Your board is ready
+engine-bad-move = The engine sent a bad move: { $error }
+engine-quit = The engine quit
+
+## The end screen
+end-wins = { $name } wins
+end-draw = Draw
+end-won = You Win
+end-cleared = { $cleared } cleared
+end-time-up = Time Up
+end-lost = Game Over
+end-flags = Flags
+end-flags-counts = { $right } right, { $wrong } wrong
+end-retry = Retry this board
+end-new-board = New board
+end-review = Review mistakes
+end-share = Export share text
+end-back = Back to menu
+share-header = termsweeper { $board }, { $mines } mines
+share-won = Won in { $time } with { $clicks } clicks (3BV { $bbbv })
+share-lost = Lost with { $progress }% cleared after { $time } and { $clicks } clicks
+end-missed = Missed
+end-missed-mine = 1 mine
+end-missed-mines = { $count } mines
+
+## Presets and stats
+preset-beginner = Beginner
+preset-intermediate = Intermediate
+preset-expert = Expert
+preset-custom = Custom
+stats-rating = Rating: { $rating } ({ $games } ranked games)
+stats-adaptive = Adaptive level: { $level }%
+stats-damaged = Skipped { $count } damaged records
+stats-played = Played
+stats-won = Won
+stats-win-rate = Win %
+stats-best = Best
+stats-win-rates = Win rate
+stats-recent-times = Recent winning times
+stats-times = { $preset } winning times
+stats-deaths = Where games were lost
+stats-death-spots = Corners { $corners }%, edges { $edges }%, inside { $inside }%
+stats-ladder = Ladder: { $runs } runs, best { $best }
+stats-imported = Imported { $imported } games (skipped { $skipped })
+
+## Achievements
+achievement-first-win = First Win
+achievement-first-win-description = Win a game
+achievement-fast-expert = Speed Demon
+achievement-fast-expert-description = Win an Expert game in under 100 seconds
+achievement-win-streak = On a Roll
+achievement-win-streak-description = Win 10 games in a row
+achievement-no-flags = No Flags Needed
+achievement-no-flags-description = Win a game without placing a flag
+achievement-half-density = Minefield
+achievement-half-density-description = Win a board that is at least half mines
+
+## Puzzle packs
+pack-title = { $name } ({ $done }/{ $total } solved)
+pack-help = enter to play, q to quit
+pack-locked = [locked]
+pack-no-flags = no flags
+pack-clicks = { $max } clicks
+bookmarks-title = Bookmarks ({ $total })
+bookmarks-help = enter to play, d to delete, q to quit
+bookmarks-empty = Nothing bookmarked yet. Press ctrl+b during a game to bookmark its board.
+bookmarks-line = { $cols }x{ $rows }, { $mines } mines: { $attempts } attempts, best { $best }
+
+## The board editor
+editor-mine = mine
+editor-void = void
+editor-size = Size
+editor-mines = Mines
+editor-3bv = 3BV
+editor-no-mines = Place at least one mine
+editor-no-safe-cells = Leave at least one safe cell
+editor-saved = Saved to { $path }
+
+## The custom game menu
+custom-title = Custom game
+custom-help = up/down to pick, left/right to change (by 10 with shift), enter to play, r to drag the size, s to save, q to quit
+custom-suggestion = You win { $rate }% of { $played } games. Try { $next }? (t to try, x to dismiss)
+suggestion-offer = You win { $rate }% of { $played } games. Try { $next }? (t to try, x to dismiss, any other key to go on)
+daily-banner = Daily challenge { $date }: { $status }. Next one in { $next } (play it with termsweeper daily)
+daily-not-attempted = not played yet
+daily-in-progress = in progress
+daily-lost = lost
+daily-won = won in { $time }
+daily-done = Today's daily challenge is over: { $status }. Days won in a row: { $streak }. Come back in { $next }.
+custom-rows = Rows
+custom-columns = Columns
+custom-mines = Mines
+custom-no-guess = No guessing
+custom-hardcore = Hardcore
+custom-moving-mines = Moving mines
+custom-on = on
+custom-off = off
+custom-density = { $density }% mines
+custom-too-many-mines = Too many mines for this size (at most { $max })
+custom-save-as = Save as: { $name }
+custom-saved = Saved as { $name }, for --preset
+custom-name-taken = { $name } is a standard preset
+custom-saved-games = Saved games
+custom-summary = { $rows }x{ $columns }, { $mines } mines
+custom-resizing = Drag the corner with the arrow keys (by 10 with shift), enter when done
+
+## Profiles
+profile-title = Profiles
+profile-help = up/down to pick, enter to play as it from now on, n for a new profile, q to quit
+profile-default = Default
+profile-name = New profile: { $name }
+profile-bad-name = { $name } can't be a profile's name
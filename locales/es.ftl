@@ -0,0 +1,198 @@
+# Spanish
+
+## Controls
+action-move = mover
+action-reveal = descubrir
+action-flag = bandera
+action-note = nota
+action-ping = señalar
+action-quit = salir
+action-frontier = enfoque
+control-hint = pista
+control-layer = capa
+control-board = tablero
+control-export = exportar
+control-menu = menú
+control-help = ayuda
+title-controls = Controles
+control-save = guardar
+
+## Help
+help-goal = Descubre todas las casillas que no sean minas, sin descubrir ninguna mina.
+help-numbers = Un número indica cuántas de las casillas a su alrededor son minas.
+help-flags = Marca una casilla con una bandera como mina, para no descubrirla por accidente.
+help-chords = Descubrir un número con tantas banderas alrededor descubre el resto de sus vecinas.
+help-bookmarks = Ctrl+B guarda el tablero en marcadores, para volver a intentarlo luego con `termsweeper bookmarks`.
+help-tabs = Ctrl+T abre otra partida en una pestaña nueva, Ctrl+Tab y Ctrl+Mayús+Tab cambian entre ellas, y Ctrl+W (o salir) cierra la actual.
+help-commands = Escribe : para dar una orden: new (o new expert, o new 16x30 99) para empezar de nuevo, seed 42 para empezar de nuevo en el tablero de esa semilla, reveal 3 4 o reveal C7 (o flag, o chord) para jugar, goto C7 para llevar el cursor allí, glyphs ascii o borders double para cambiar el aspecto, save NOMBRE para guardar el tablero, y stats para ver las estadísticas.
+help-presentation = Ctrl+P cambia el modo presentación, para retransmitir o enseñar con un proyector: un cursor más brillante con su fila y columna resaltadas, y un reloj grande.
+help-header = Ayuda (flechas y re pág/av pág para desplazarse, esc para cerrar)
+
+## Settings
+settings-hint = enter para elegir, esc para cerrar
+preset-arrows = Flechas
+preset-wasd = WASD
+preset-vim = Vim
+preset-numpad = Teclado numérico
+preset-left-handed = Zurdo
+
+## Playing
+paused = EN PAUSA
+clock-time = Tiempo { $time }
+clock-time-left = Quedan { $time }
+clock-cleared = Despejados { $cleared }
+clock-clicks = Clics { $clicks }/{ $max }
+clock-ladder = Escalera { $rung }/{ $rungs }: { $total }
+clock-latency = Retraso de entrada { $p50 } p50, { $p99 } p99
+window-title = termsweeper — { $board } — { $time } — quedan { $left } minas
+mistake-reveal = ({ $row }, { $col }) tiene que ser una mina. ¿Descubrir? (y/n)
+mistake-chord = ({ $row }, { $col }) tiene que ser una mina. Hay una bandera mal puesta. ¿Descubrir? (y/n)
+session-game = Partida { $number } de { $count }
+versus-player = Jugador { $number }
+versus-computer = Ordenador
+
+## Messages
+toast-pasted = Se ha ignorado el texto pegado
+toast-picture-saved = Guardado { $name }.svg
+toast-pictures-saved = Guardados { $name }.svg y .png
+toast-picture-failed = No se pudo guardar la imagen: { $error }
+toast-mine-moved = ¡Una mina se ha movido!
+toast-puzzle-solved = ¡Puzle resuelto!
+toast-replay-saved = Repetición guardada en { $path }
+toast-bookmarked = Tablero guardado en marcadores
+toast-already-bookmarked = El tablero ya está en marcadores
+toast-bookmark-failed = No se pudo guardar el tablero en marcadores: { $error }
+toast-bookmark-update-failed = No se pudo actualizar el marcador del tablero: { $error }
+toast-bookmark-topology = Solo los tableros cuadrados pueden guardarse en marcadores
+toast-command-failed = { $error }
+toast-cant-start-over = Solo una partida normal de un jugador puede empezar de nuevo
+toast-board-invalid = Ese tablero no se puede crear
+toast-board-size-clamped = El tablero es de { $rows }x{ $columns }, el tamaño jugable más cercano
+toast-off-the-board = Esa casilla está fuera del tablero
+toast-board-saved = Tablero guardado en { $path }
+toast-board-save-failed = No se pudo guardar el tablero: { $error }
+toast-share-saved = Texto para compartir guardado en { $path }
+toast-share-failed = No se pudo guardar el texto para compartir: { $error }
+toast-mistakes = { $count } errores marcados en rojo
+toast-no-mistakes = No hay errores que revisar
+toast-presenting = Modo presentación activado
+toast-not-presenting = Modo presentación desactivado
+toast-achievement = Logro desbloqueado: { $name }
+toast-engine-unreachable = No se pudo contactar con el motor: { $error }
+toast-config-reloaded = Configuración recargada
+toast-config-failed = No se pudo recargar la configuración: { $error }
+toast-stats-damaged = Se quitaron { $count } registros dañados de las estadísticas (se guardan en una copia del archivo)
+generating-board = Generando el tablero...
+ladder-total = Escalera completada en { $total }
+notify-board-ready = Tu tablero está listo
+engine-bad-move = El motor envió una jugada no válida: { $error }
+engine-quit = El motor se ha cerrado
+
+## The end screen
+end-wins = Gana { $name }
+end-draw = Empate
+end-won = Has ganado
+end-cleared = { $cleared } despejados
+end-time-up = Tiempo agotado
+end-lost = Fin de la partida
+end-flags = Banderas
+end-flags-counts = { $right } bien, { $wrong } mal
+end-retry = Reintentar este tablero
+end-new-board = Tablero nuevo
+end-review = Revisar errores
+end-share = Exportar texto para compartir
+end-back = Volver al menú
+share-header = termsweeper { $board }, { $mines } minas
+share-won = Ganado en { $time } con { $clicks } clics (3BV { $bbbv })
+share-lost = Perdido con el { $progress } % despejado tras { $time } y { $clicks } clics
+end-missed = Sin marcar
+end-missed-mine = 1 mina
+end-missed-mines = { $count } minas
+
+## Presets and stats
+preset-beginner = Principiante
+preset-intermediate = Intermedio
+preset-expert = Experto
+preset-custom = Personalizado
+stats-rating = Puntuación: { $rating } ({ $games } partidas puntuables)
+stats-adaptive = Nivel adaptativo: { $level } %
+stats-damaged = Se omitieron { $count } registros dañados
+stats-played = Jugadas
+stats-won = Ganadas
+stats-win-rate = % ganadas
+stats-best = Mejor
+stats-win-rates = Porcentaje de victorias
+stats-recent-times = Tiempos recientes de victorias
+stats-times = Tiempos de victorias: { $preset }
+stats-deaths = Dónde se perdieron las partidas
+stats-death-spots = Esquinas { $corners } %, bordes { $edges } %, interior { $inside } %
+stats-ladder = Escalera: { $runs } intentos, mejor { $best }
+stats-imported = Se importaron { $imported } partidas (omitidas: { $skipped })
+
+## Achievements
+achievement-first-win = Primera victoria
+achievement-first-win-description = Gana una partida
+achievement-fast-expert = Rayo
+achievement-fast-expert-description = Gana una partida de experto en menos de 100 segundos
+achievement-win-streak = En racha
+achievement-win-streak-description = Gana 10 partidas seguidas
+achievement-no-flags = Sin banderas
+achievement-no-flags-description = Gana una partida sin poner ninguna bandera
+achievement-half-density = Campo minado
+achievement-half-density-description = Gana un tablero con al menos la mitad de minas
+
+## Puzzle packs
+pack-title = { $name } ({ $done }/{ $total } resueltos)
+pack-help = enter para jugar, q para salir
+pack-locked = [bloqueado]
+pack-no-flags = sin banderas
+pack-clicks = { $max } clics
+bookmarks-title = Marcadores ({ $total })
+bookmarks-help = enter para jugar, d para borrar, q para salir
+bookmarks-empty = Aún no hay marcadores. Pulsa ctrl+b durante una partida para guardar su tablero.
+bookmarks-line = { $cols }x{ $rows }, { $mines } minas: { $attempts } intentos, mejor { $best }
+
+## The board editor
+editor-mine = mina
+editor-void = hueco
+editor-size = Tamaño
+editor-mines = Minas
+editor-3bv = 3BV
+editor-no-mines = Pon al menos una mina
+editor-no-safe-cells = Deja al menos una casilla segura
+editor-saved = Guardado en { $path }
+
+## The custom game menu
+custom-title = Partida personalizada
+custom-help = arriba/abajo para elegir, izquierda/derecha para cambiar (de 10 en 10 con shift), enter para jugar, r para arrastrar el tamaño, s para guardar, q para salir
+custom-suggestion = Ganas el { $rate } % de las partidas en { $played }. ¿Probar { $next }? (t para probar, x para descartar)
+suggestion-offer = Ganas el { $rate } % de las partidas en { $played }. ¿Probar { $next }? (t para probar, x para descartar, otra tecla para seguir)
+daily-banner = Desafío diario { $date }: { $status }. El próximo en { $next } (juégalo con termsweeper daily)
+daily-not-attempted = sin jugar
+daily-in-progress = en curso
+daily-lost = perdido
+daily-won = ganado en { $time }
+daily-done = El desafío de hoy ha terminado: { $status }. Días seguidos ganados: { $streak }. Vuelve en { $next }.
+custom-rows = Filas
+custom-columns = Columnas
+custom-mines = Minas
+custom-no-guess = Sin adivinar
+custom-hardcore = Extremo
+custom-moving-mines = Minas móviles
+custom-on = sí
+custom-off = no
+custom-density = { $density } % de minas
+custom-too-many-mines = Demasiadas minas para este tamaño (como mucho { $max })
+custom-save-as = Guardar como: { $name }
+custom-saved = Guardado como { $name }, para --preset
+custom-name-taken = { $name } es un nivel estándar
+custom-saved-games = Partidas guardadas
+custom-summary = { $rows }x{ $columns }, { $mines } minas
+custom-resizing = Arrastra la esquina con las flechas (de 10 en 10 con shift), enter para terminar
+
+## Profiles
+profile-title = Perfiles
+profile-help = arriba/abajo para elegir, intro para jugar con él desde ahora, n para un perfil nuevo, q para salir
+profile-default = Predeterminado
+profile-name = Perfil nuevo: { $name }
+profile-bad-name = { $name } no puede ser el nombre de un perfil
@@ -0,0 +1,82 @@
+use crate::locale;
+use crate::preset::Preset;
+use crate::stats::GameRecord;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Achievement {
+    FirstWin,
+    FastExpert,
+    WinStreak,
+    NoFlags,
+    HalfDensity,
+}
+
+impl Achievement {
+    pub(crate) const ALL: [Achievement; 5] = [
+        Achievement::FirstWin,
+        Achievement::FastExpert,
+        Achievement::WinStreak,
+        Achievement::NoFlags,
+        Achievement::HalfDensity,
+    ];
+
+    const STREAK: usize = 10;
+
+    pub(crate) fn name(self) -> &'static str {
+        locale::tr(match self {
+            Achievement::FirstWin => "achievement-first-win",
+            Achievement::FastExpert => "achievement-fast-expert",
+            Achievement::WinStreak => "achievement-win-streak",
+            Achievement::NoFlags => "achievement-no-flags",
+            Achievement::HalfDensity => "achievement-half-density",
+        })
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        locale::tr(match self {
+            Achievement::FirstWin => "achievement-first-win-description",
+            Achievement::FastExpert => "achievement-fast-expert-description",
+            Achievement::WinStreak => "achievement-win-streak-description",
+            Achievement::NoFlags => "achievement-no-flags-description",
+            Achievement::HalfDensity => "achievement-half-density-description",
+        })
+    }
+
+    /// Whether the game earns this achievement, given the number of games in a row that have been won up to and
+    /// including it.
+    fn earned_by(self, game: &GameRecord, streak: usize) -> bool {
+        game.won && match self {
+            Achievement::FirstWin => true,
+            Achievement::FastExpert => game.preset == Some(Preset::Expert) && game.time_ms < 100_000,
+            Achievement::WinStreak => streak >= Self::STREAK,
+            Achievement::NoFlags => game.flags == Some(0),
+            Achievement::HalfDensity => game.mines * 2 >= game.size.0 * game.size.1,
+        }
+    }
+}
+
+/// Every achievement earned over the history, with the timestamp of the game that first earned it, in the order they
+/// were earned.
+pub(crate) fn unlocked(games: &[GameRecord]) -> Vec<(Achievement, u64)> {
+    let mut unlocked = Vec::<(Achievement, u64)>::new();
+    let mut streak = 0;
+    for game in games {
+        streak = if game.won { streak + 1 } else { 0 };
+        for achievement in Achievement::ALL {
+            if achievement.earned_by(game, streak) && unlocked.iter().all(|(earned, _)| *earned != achievement) {
+                unlocked.push((achievement, game.timestamp));
+            }
+        }
+    }
+    unlocked
+}
+
+/// The achievements the last game in the history earned for the first time.
+pub(crate) fn newly_unlocked(games: &[GameRecord]) -> Vec<Achievement> {
+    let Some((_, before)) = games.split_last() else { return Vec::new() };
+    let before = unlocked(before);
+    unlocked(games).into_iter()
+        .map(|(achievement, _)| achievement)
+        .filter(|achievement| before.iter().all(|(earned, _)| earned != achievement))
+        .collect()
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::io::{stdout, ErrorKind, Write};
+use std::path::PathBuf;
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::locale;
+use crate::paths;
+use crate::preset::Preset;
+use crate::stats::{GameRecord, Stats};
+use crate::tui::{BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The share of adaptive games the player should win.
+const TARGET_WIN_RATE: f64 = 0.5;
+/// How many of the most recent adaptive games the win rate is taken over.
+const WINDOW: usize = 10;
+/// How far the level moves after each game, at the most (when every recent game was won, or every one lost).
+const STEP: f64 = 0.2;
+/// How many games on a preset it takes to suggest another one, so a lucky (or unlucky) start doesn't.
+const SUGGEST_AFTER: usize = 10;
+/// The win rates on a preset above which the next harder one is suggested, and below which the next easier one is.
+const SUGGEST_WIN_RATES: (f64, f64) = (0.75, 0.2);
+
+/// The level of the next adaptive game, from 0 (a small, sparse board) to 1 (Expert size and density). Each adaptive
+/// game in the history nudges the level of the one after it towards keeping the recent win rate on target.
+pub(crate) fn next_level(games: &[GameRecord]) -> f64 {
+    let adaptive = games.iter()
+        .filter_map(|game| Some((game.adaptive_level?, game.won)))
+        .collect::<Vec<_>>();
+    let Some(&(level, _)) = adaptive.last() else { return 0.0 };
+
+    let recent = &adaptive[adaptive.len().saturating_sub(WINDOW)..];
+    let win_rate = recent.iter().filter(|(_, won)| *won).count() as f64 / recent.len() as f64;
+    let nudge = (win_rate - TARGET_WIN_RATE) / TARGET_WIN_RATE.max(1.0 - TARGET_WIN_RATE);
+    (level + STEP * nudge).clamp(0.0, 1.0)
+}
+
+/// The board for a level, as ((rows, columns), mines): from a Beginner-sized board at 10% mines, up to an Expert one.
+pub(crate) fn board(level: f64) -> ((usize, usize), usize) {
+    let lerp = |from: f64, to: f64| from + (to - from) * level;
+    let size = (lerp(9.0, 16.0).round() as usize, lerp(9.0, 30.0).round() as usize);
+    let density = lerp(0.1, 99.0 / (16.0 * 30.0));
+    (size, ((size.0 * size.1) as f64 * density).round() as usize)
+}
+
+/// A preset to try next, from how the player does on one they've played plenty of.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// The preset it's based on.
+    pub played: Preset,
+    /// The share of the games on it the player won, from 0 to 1.
+    pub win_rate: f64,
+    /// The preset to try.
+    pub next: Preset,
+}
+
+impl Suggestion {
+    /// The suggestion, put to the player in the message with the given id, which says which keys answer it.
+    pub(crate) fn question(&self, id: &'static str) -> String {
+        let rate = format!("{:.0}", self.win_rate * 100.0);
+        let (played, next) = (self.played.label(), self.next.label());
+        locale::tr_with(id, &[("rate", &rate), ("played", &played), ("next", &next)])
+    }
+}
+
+/// A preset to try next, going by how the player does on the hardest one they've played enough of: the one after it if
+/// they win most of those games, or the one before it if they hardly ever do.
+pub fn suggest(stats: &Stats) -> Option<Suggestion> {
+    let (i, played, win_rate) = Preset::ALL.into_iter()
+        .enumerate()
+        .rev()
+        .find_map(|(i, preset)| {
+            let games = stats.games.iter().filter(|game| game.preset == Some(preset)).collect::<Vec<_>>();
+            let won = games.iter().filter(|game| game.won).count();
+            (games.len() >= SUGGEST_AFTER).then(|| (i, preset, won as f64 / games.len() as f64))
+        })?;
+    let next = if win_rate >= SUGGEST_WIN_RATES.0 {
+        Preset::ALL.get(i + 1)?
+    } else if win_rate < SUGGEST_WIN_RATES.1 {
+        Preset::ALL.get(i.checked_sub(1)?)?
+    } else {
+        return None;
+    };
+    Some(Suggestion { played, win_rate, next: *next })
+}
+
+/// The suggestions the player has dismissed, as the presets they were from and to, stored as TOML in the platform data
+/// directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Dismissed {
+    #[serde(default)]
+    suggestions: Vec<(Preset, Preset)>,
+}
+
+impl Dismissed {
+    fn path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("suggestions.toml"))
+    }
+
+    /// Load the dismissals file. A missing file (or no known data directory) means nothing has been dismissed yet.
+    fn load() -> IoResult<Self> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The suggestion to make from the stats, unless the config turns suggestions off or the player has dismissed it
+/// before. A dismissals file that can't be read is no reason to stop a game from starting, so it's taken as having
+/// none.
+pub fn pending(stats: &Stats, config: &Config) -> Option<Suggestion> {
+    let suggestion = suggest(stats).filter(|_| config.suggestions)?;
+    let dismissed = Dismissed::load().unwrap_or_default().suggestions;
+    (!dismissed.contains(&(suggestion.played, suggestion.next))).then_some(suggestion)
+}
+
+/// Remember that the player dismissed the suggestion, so it isn't made again. Another one can still be, once how they
+/// do changes.
+pub fn dismiss(suggestion: Suggestion) -> IoResult<()> {
+    let mut dismissed = Dismissed::load()?;
+    dismissed.suggestions.push((suggestion.played, suggestion.next));
+    let Some(path) = Dismissed::path() else { return Ok(()) };
+    let text = toml::to_string(&dismissed).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    paths::write_safely(&path, text.as_bytes())
+}
+
+/// Put the pending suggestion, if there is one, to the player on a screen of its own before the game starts. Returns
+/// the preset to play if they try it. They can dismiss it instead, or press any other key to play as they started to.
+pub(crate) fn offer(config: &Config) -> IoResult<Option<Preset>> {
+    // Not having any stats to go by is no reason to stop the game from starting
+    let Some(suggestion) = pending(&Stats::load().unwrap_or_default(), config) else { return Ok(None) };
+    execute!(stdout(), EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    let answer = ask(suggestion);
+    terminal::disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen)?;
+    match answer? {
+        KeyCode::Char('t') => Ok(Some(suggestion.next)),
+        KeyCode::Char('x') => dismiss(suggestion).map(|_| None),
+        _ => Ok(None),
+    }
+}
+
+/// Show the suggestion until a key is pressed, and return it.
+fn ask(suggestion: Suggestion) -> IoResult<KeyCode> {
+    loop {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+        BoxedComponent::new(&Text::new(vec![suggestion.question("suggestion-offer")])).render_at(&mut buffer);
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        out.flush()?;
+        // Anything else, like the terminal being resized, draws it again
+        if let Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) = event::read()? {
+            return Ok(code);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::game::Field;
+use crate::solver;
+use crate::versus::Rules;
+
+/// How well a computer opponent plays. Every move it makes has a chance of ignoring the solver and picking a random
+/// hidden cell instead.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "easy" => Some(Self::Easy),
+            "medium" => Some(Self::Medium),
+            "hard" => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    fn error_rate(self) -> f64 {
+        match self {
+            Self::Easy => 0.3,
+            Self::Medium => 0.1,
+            Self::Hard => 0.0,
+        }
+    }
+}
+
+/// Pick the cell (as (row, col)) the computer reveals next, or None if there are no hidden cells left. Under
+/// [`Rules::Reveal`] it wants safe cells; under [`Rules::Flags`] it wants mines.
+pub(crate) fn choose_move(field: &Field, rules: Rules, difficulty: Difficulty, rng: &mut impl Rng) -> Option<(usize, usize)> {
+    let hidden = field.board.iter()
+        .enumerate()
+        .flat_map(|(row, cells)| cells.iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_hidden())
+            .map(move |(col, _)| (row, col))
+        )
+        .collect::<Vec<_>>();
+
+    if rng.gen_bool(difficulty.error_rate()) {
+        return hidden.choose(rng).copied();
+    }
+
+    let analysis = solver::analyze_cached(field);
+    let (wanted, avoided) = match rules {
+        Rules::Reveal(_) => (&analysis.safe, &analysis.mines),
+        Rules::Flags => (&analysis.mines, &analysis.safe),
+    };
+    if let Some(pos) = wanted.choose(rng) {
+        return Some(*pos);
+    }
+
+    let guesses = hidden.iter()
+        .filter(|pos| !avoided.contains(pos))
+        .collect::<Vec<_>>();
+    guesses.choose(rng)
+        .copied()
+        .or_else(|| hidden.choose(rng))
+        .copied()
+}
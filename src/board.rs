@@ -0,0 +1,334 @@
+use crate::game::{Field, Reveal};
+use crate::topology::Topology;
+
+/// What the solver and generators need from a board, whatever stores its cells. Positions are (row, col), and have to
+/// be on the board except where a method says otherwise.
+///
+/// [`Field`] is the board games are played on. [`BitBoard`] holds the same cells in about half the memory, for
+/// working through many boards, or very large ones, without the rest of what a game needs.
+pub trait Board {
+    /// The board size, as (rows, columns).
+    fn size(&self) -> (usize, usize);
+
+    /// The in-bounds neighbors of a cell, as (row, col).
+    fn neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)>;
+
+    /// Whether the cell is a mine, whether or not it has been revealed.
+    fn is_mine(&self, pos: (usize, usize)) -> bool;
+
+    /// The number of adjacent mines, whether or not it has been revealed.
+    fn neighbor_count(&self, pos: (usize, usize)) -> u8;
+
+    /// Returns true if the cell hasn't been revealed yet, flagged or not.
+    fn is_hidden(&self, pos: (usize, usize)) -> bool;
+
+    /// Returns true if the cell has a flag on it.
+    fn is_flagged(&self, pos: (usize, usize)) -> bool;
+
+    /// Returns true if the cell is a hole in the board, rather than part of it.
+    fn is_void(&self, pos: (usize, usize)) -> bool;
+
+    /// Returns true if the cell is a mine that was revealed.
+    fn is_exploded(&self, pos: (usize, usize)) -> bool {
+        self.is_mine(pos) && !self.is_hidden(pos)
+    }
+
+    /// The neighbor count, if it has been revealed to the player.
+    fn shown_number(&self, pos: (usize, usize)) -> Option<u8> {
+        let shown = !self.is_hidden(pos) && !self.is_mine(pos) && !self.is_void(pos);
+        shown.then(|| self.neighbor_count(pos))
+    }
+
+    /// Returns true if every safe cell has been revealed.
+    fn is_cleared(&self) -> bool {
+        let (rows, cols) = self.size();
+        (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .all(|pos| self.is_mine(pos) || !self.is_hidden(pos))
+    }
+
+    /// Returns what the reveal uncovered. Returns None if the given cell has already been cleared or flagged, or is
+    /// off the board.
+    fn clear_cell(&mut self, pos: (usize, usize)) -> Option<Reveal>;
+
+    /// Returns a bool signifying that the flag was valid (i.e., that the cell was not already revealed). Returns None
+    /// if the cell is off the board.
+    fn toggle_flag(&mut self, pos: (usize, usize)) -> Option<bool>;
+}
+
+impl Board for Field {
+    fn size(&self) -> (usize, usize) {
+        Field::size(self)
+    }
+
+    fn neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        Field::neighbors(self, pos)
+    }
+
+    fn is_mine(&self, pos: (usize, usize)) -> bool {
+        self.board[pos.0][pos.1].is_mine()
+    }
+
+    fn neighbor_count(&self, pos: (usize, usize)) -> u8 {
+        self.board[pos.0][pos.1].neighbor_count()
+    }
+
+    fn is_hidden(&self, pos: (usize, usize)) -> bool {
+        self.board[pos.0][pos.1].is_hidden()
+    }
+
+    fn is_flagged(&self, pos: (usize, usize)) -> bool {
+        self.board[pos.0][pos.1].is_flagged()
+    }
+
+    fn is_void(&self, pos: (usize, usize)) -> bool {
+        self.board[pos.0][pos.1].is_void()
+    }
+
+    fn is_exploded(&self, pos: (usize, usize)) -> bool {
+        self.board[pos.0][pos.1].is_exploded()
+    }
+
+    fn shown_number(&self, pos: (usize, usize)) -> Option<u8> {
+        self.board[pos.0][pos.1].shown_number()
+    }
+
+    fn is_cleared(&self) -> bool {
+        Field::is_cleared(self)
+    }
+
+    fn clear_cell(&mut self, pos: (usize, usize)) -> Option<Reveal> {
+        Field::clear_cell(self, pos)
+    }
+
+    fn toggle_flag(&mut self, pos: (usize, usize)) -> Option<bool> {
+        Field::toggle_flag(self, pos)
+    }
+}
+
+/// A set of cells, one bit each, by index.
+#[derive(Clone, Debug)]
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.0[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    fn set(&mut self, i: usize, value: bool) {
+        match value {
+            true => self.0[i / 64] |= 1 << (i % 64),
+            false => self.0[i / 64] &= !(1 << (i % 64)),
+        }
+    }
+}
+
+/// A cell's neighbors, gathered up front so they don't borrow the board.
+struct Neighbors {
+    cells: [(usize, usize); Neighbors::MAX],
+    len: usize,
+}
+
+impl Neighbors {
+    /// The most neighbors a cell can have, in the middle layer of a layered board.
+    const MAX: usize = 26;
+
+    fn push(&mut self, pos: (usize, usize)) {
+        self.cells[self.len] = pos;
+        self.len += 1;
+    }
+
+    fn into_iter(self) -> impl Iterator<Item = (usize, usize)> {
+        self.cells.into_iter().take(self.len)
+    }
+}
+
+/// A board stored compactly: a bit per cell for each of whether it's a mine, revealed, flagged or a void, and a byte
+/// for its neighbor count. That's a byte and a half a cell, where a [`Field`] takes three and a row's worth of
+/// overhead on top. A revealed mine is an exploded one.
+///
+/// It has none of what a [`Field`] keeps for playing a game on it: the solver's cache, the rendered rows, or noisy
+/// numbers.
+#[derive(Clone, Debug)]
+pub struct BitBoard {
+    size: (usize, usize),
+    topology: Topology,
+    mines: Bits,
+    revealed: Bits,
+    flagged: Bits,
+    void: Bits,
+    /// The number of mines around each cell, a row at a time.
+    counts: Vec<u8>,
+}
+
+impl BitBoard {
+    /// A board with mines at exactly the given (row, col) positions. Returns None if either dimension was zero, or a
+    /// mine is off the board or listed twice.
+    pub fn from_mines(size: (usize, usize), mines: &[(usize, usize)]) -> Option<Self> {
+        Self::with_topology(Topology::Square, size, mines)
+    }
+
+    /// Like [`BitBoard::from_mines`], but with cells that fit together in the given way. Also returns None if the
+    /// board size doesn't fit the topology.
+    pub fn with_topology(topology: Topology, size: (usize, usize), mines: &[(usize, usize)]) -> Option<Self> {
+        if size.0 == 0 || size.1 == 0 || !topology.fits(size) {
+            return None;
+        }
+        let len = size.0 * size.1;
+        let mut board = Self {
+            size,
+            topology,
+            mines: Bits::new(len),
+            revealed: Bits::new(len),
+            flagged: Bits::new(len),
+            void: Bits::new(len),
+            counts: vec![0; len],
+        };
+        for &pos in mines {
+            let i = board.index(pos)?;
+            if board.mines.get(i) {
+                return None;
+            }
+            board.mines.set(i, true);
+            for neighbor in topology.neighbors(size, pos) {
+                board.counts[neighbor.0 * size.1 + neighbor.1] += 1;
+            }
+        }
+        Some(board)
+    }
+
+    /// The index of a cell in the bitsets and counts. Returns None if it's off the board.
+    fn index(&self, pos: (usize, usize)) -> Option<usize> {
+        (pos.0 < self.size.0 && pos.1 < self.size.1).then_some(pos.0 * self.size.1 + pos.1)
+    }
+
+    /// Reveal one cell, unless it's already revealed, flagged or a void. Returns whether it was revealed.
+    fn reveal(&mut self, i: usize) -> bool {
+        if self.revealed.get(i) || self.flagged.get(i) || self.void.get(i) {
+            return false;
+        }
+        self.revealed.set(i, true);
+        true
+    }
+}
+
+/// The same board, in the same state, as long as it's one [`Board`] can describe: noisy numbers' ranges are left
+/// behind.
+impl From<&Field> for BitBoard {
+    fn from(field: &Field) -> Self {
+        let size = field.size();
+        let len = size.0 * size.1;
+        let mut board = Self {
+            size,
+            topology: field.topology(),
+            mines: Bits::new(len),
+            revealed: Bits::new(len),
+            flagged: Bits::new(len),
+            void: Bits::new(len),
+            counts: vec![0; len],
+        };
+        for (i, cell) in field.board.iter().flatten().enumerate() {
+            board.mines.set(i, cell.is_mine());
+            board.revealed.set(i, !cell.is_hidden() && !cell.is_void());
+            board.flagged.set(i, cell.is_flagged());
+            board.void.set(i, cell.is_void());
+            board.counts[i] = cell.neighbor_count();
+        }
+        board
+    }
+}
+
+impl Board for BitBoard {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let mut neighbors = Neighbors { cells: [(0, 0); Neighbors::MAX], len: 0 };
+        if self.topology == Topology::Square {
+            // The square grid's neighbors are worked out directly, since most boards are square
+            let (rows, cols) = self.size;
+            for row in pos.0.saturating_sub(1)..(pos.0 + 2).min(rows) {
+                for col in pos.1.saturating_sub(1)..(pos.1 + 2).min(cols) {
+                    if (row, col) != pos {
+                        neighbors.push((row, col));
+                    }
+                }
+            }
+        } else {
+            self.topology.neighbors(self.size, pos).for_each(|neighbor| neighbors.push(neighbor));
+        }
+        neighbors.into_iter()
+    }
+
+    fn is_mine(&self, pos: (usize, usize)) -> bool {
+        self.mines.get(pos.0 * self.size.1 + pos.1)
+    }
+
+    fn neighbor_count(&self, pos: (usize, usize)) -> u8 {
+        self.counts[pos.0 * self.size.1 + pos.1]
+    }
+
+    fn is_hidden(&self, pos: (usize, usize)) -> bool {
+        let i = pos.0 * self.size.1 + pos.1;
+        !self.revealed.get(i) && !self.void.get(i)
+    }
+
+    fn is_flagged(&self, pos: (usize, usize)) -> bool {
+        self.flagged.get(pos.0 * self.size.1 + pos.1)
+    }
+
+    fn is_void(&self, pos: (usize, usize)) -> bool {
+        self.void.get(pos.0 * self.size.1 + pos.1)
+    }
+
+    fn is_exploded(&self, pos: (usize, usize)) -> bool {
+        let i = pos.0 * self.size.1 + pos.1;
+        self.revealed.get(i) && self.mines.get(i)
+    }
+
+    fn shown_number(&self, pos: (usize, usize)) -> Option<u8> {
+        let i = pos.0 * self.size.1 + pos.1;
+        (self.revealed.get(i) && !self.mines.get(i)).then(|| self.counts[i])
+    }
+
+    fn clear_cell(&mut self, pos: (usize, usize)) -> Option<Reveal> {
+        let i = self.index(pos)?;
+        if !self.reveal(i) {
+            return None;
+        }
+        if self.mines.get(i) {
+            return Some(Reveal { exploded: true, cells: vec![pos] });
+        }
+
+        let mut cells = vec![pos];
+        let mut check = Vec::new();
+        if self.counts[i] == 0 {
+            check.extend(self.neighbors(pos));
+        }
+        while let Some(next) = check.pop() {
+            let i = next.0 * self.size.1 + next.1;
+            if !self.reveal(i) {
+                continue;
+            }
+            cells.push(next);
+            // Empty cells have no mines around them, so the flood never reaches one
+            if self.counts[i] == 0 {
+                check.extend(self.neighbors(next));
+            }
+        }
+        Some(Reveal { exploded: false, cells })
+    }
+
+    fn toggle_flag(&mut self, pos: (usize, usize)) -> Option<bool> {
+        let i = self.index(pos)?;
+        if self.revealed.get(i) || self.void.get(i) {
+            return Some(false);
+        }
+        self.flagged.set(i, !self.flagged.get(i));
+        Some(true)
+    }
+}
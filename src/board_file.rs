@@ -0,0 +1,70 @@
+use crate::game::Field;
+
+type IoResult<T> = std::io::Result<T>;
+
+const SAFE: char = '.';
+const MINE: char = '*';
+const VOID: char = '_';
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Read a board file. Boards are saved as plain text, one line per row, with a character per cell:
+///
+/// - `.` is a safe cell,
+/// - `*` is a mine,
+/// - `_` is a void: a hole in the board, which is never revealed and doesn't need clearing.
+///
+/// Every row must be the same length. Blank lines at the end are ignored.
+pub fn parse(text: &str) -> IoResult<Field> {
+    let rows = text.trim_end().lines().map(str::trim_end).collect::<Vec<_>>();
+    let cols = rows.first().map_or(0, |row| row.chars().count());
+    if cols == 0 {
+        return Err(invalid("the board is empty"));
+    }
+
+    let (mut mines, mut voids) = (Vec::new(), Vec::new());
+    for (row, line) in rows.iter().enumerate() {
+        if line.chars().count() != cols {
+            return Err(invalid("every row of the board must be the same length"));
+        }
+        for (col, c) in line.chars().enumerate() {
+            match c {
+                SAFE => {},
+                MINE => mines.push((row, col)),
+                VOID => voids.push((row, col)),
+                _ => return Err(invalid("board cells must be one of . * _")),
+            }
+        }
+    }
+
+    Field::from_mines((rows.len(), cols), &mines)
+        .and_then(|field| field.with_voids(&voids))
+        .ok_or_else(|| invalid("the board is invalid"))
+}
+
+/// Write a board in the board file format. Only the layout is saved, not what has been revealed or flagged.
+pub fn write(field: &Field) -> String {
+    field.board.iter()
+        .map(|row| row.iter()
+            .map(|cell| if cell.is_void() { VOID } else if cell.is_mine() { MINE } else { SAFE })
+            .chain(['\n'])
+            .collect::<String>()
+        )
+        .collect()
+}
+
+/// A hash of the board's size and where its mines and voids are, as 16 hex digits. It's FNV-1a, which (unlike the
+/// standard library's hasher) is the same in every version, so hashes saved in old replays and bookmarks still match.
+pub(crate) fn hash(field: &Field) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let (rows, cols) = field.size();
+    let cells = field.board.iter().flatten().map(|cell| u64::from(cell.is_mine()) | u64::from(cell.is_void()) << 1);
+    let hash = [rows as u64, cols as u64].into_iter()
+        .chain(cells)
+        .flat_map(u64::to_le_bytes)
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,180 @@
+use std::fs;
+use std::io::{stdout, ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crossterm::{
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    cursor::MoveTo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::board_file;
+use crate::console;
+use crate::game::Field;
+use crate::locale;
+use crate::paths;
+use crate::tui::{self, BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Boards saved to try again later (like ones that were lost), with how each has gone since, stored as TOML in the
+/// platform data directory. Boards are laid out at random rather than from a seed, so each bookmark keeps the board
+/// itself.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Bookmarks {
+    #[serde(default, rename = "bookmark")]
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bookmark {
+    /// The board, in the board file format.
+    board: String,
+    /// The board's [`board_file::hash`], to find its bookmark when it's played again.
+    hash: String,
+    size: (usize, usize),
+    mines: usize,
+    /// When the board was bookmarked, in seconds since the Unix epoch.
+    added: u64,
+    /// Games finished on the board since it was bookmarked.
+    #[serde(default)]
+    attempts: u32,
+    /// The fastest win, in milliseconds, if there's been one.
+    best_ms: Option<u64>,
+    /// The most of the board cleared in any attempt, from 0 to 1.
+    #[serde(default)]
+    best_progress: f64,
+}
+
+impl Bookmarks {
+    fn path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("bookmarks.toml"))
+    }
+
+    /// Load the bookmarks file. A missing file (or no known data directory) means there are no bookmarks yet.
+    pub(crate) fn load() -> IoResult<Self> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self) -> IoResult<()> {
+        let Some(path) = Self::path() else { return Ok(()) };
+        let text = toml::to_string(self).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        paths::write_safely(&path, text.as_bytes())
+    }
+
+    /// Bookmark a board, and save the bookmarks file. Returns false if it was already bookmarked.
+    pub(crate) fn add(field: &Field) -> IoResult<bool> {
+        let mut bookmarks = Self::load()?;
+        let hash = board_file::hash(field);
+        if bookmarks.bookmarks.iter().any(|bookmark| bookmark.hash == hash) {
+            return Ok(false);
+        }
+        bookmarks.bookmarks.push(Bookmark {
+            board: board_file::write(field),
+            hash,
+            size: field.size(),
+            mines: field.mine_count(),
+            added: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+            attempts: 0,
+            best_ms: None,
+            best_progress: 0.0,
+        });
+        bookmarks.save()?;
+        Ok(true)
+    }
+
+    /// Count a finished game on the board towards its bookmark, if it has one: how much of it was cleared, and the
+    /// time it was won in if it was.
+    pub(crate) fn attempted(field: &Field, progress: f64, won_in: Option<Duration>) -> IoResult<()> {
+        let mut bookmarks = Self::load()?;
+        let hash = board_file::hash(field);
+        let Some(bookmark) = bookmarks.bookmarks.iter_mut().find(|bookmark| bookmark.hash == hash) else {
+            return Ok(());
+        };
+        bookmark.attempts += 1;
+        bookmark.best_progress = bookmark.best_progress.max(progress);
+        if let Some(time) = won_in {
+            let time = time.as_millis() as u64;
+            bookmark.best_ms = Some(bookmark.best_ms.map_or(time, |best| best.min(time)));
+        }
+        bookmarks.save()
+    }
+
+    /// The bookmarked board at the given index.
+    pub(crate) fn field(&self, i: usize) -> IoResult<Field> {
+        board_file::parse(&self.bookmarks[i].board)
+    }
+
+    /// Show the bookmarks until one is picked to play, deleting any the player asks to along the way. Returns None if
+    /// the player quit instead.
+    pub(crate) fn choose(&mut self) -> IoResult<Option<usize>> {
+        execute!(stdout(), EnterAlternateScreen)?;
+        terminal::enable_raw_mode()?;
+        let mut selected = 0;
+        let result = loop {
+            self.render_menu(selected)?;
+            let Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event::read()?
+            else { continue };
+            match code {
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Down => selected = (selected + 1).min(self.bookmarks.len().saturating_sub(1)),
+                KeyCode::Enter if !self.bookmarks.is_empty() => break Some(selected),
+                KeyCode::Char('d') if !self.bookmarks.is_empty() => {
+                    self.bookmarks.remove(selected);
+                    self.save()?;
+                    selected = selected.min(self.bookmarks.len().saturating_sub(1));
+                },
+                KeyCode::Esc | KeyCode::Char('q') => break None,
+                _ => {},
+            }
+        };
+        terminal::disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        Ok(result)
+    }
+
+    fn render_menu(&self, selected: usize) -> IoResult<()> {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+        let mut lines = vec![
+            locale::tr_with("bookmarks-title", &[("total", &self.bookmarks.len())]),
+            locale::tr("bookmarks-help").to_string(),
+            String::new(),
+        ];
+        if self.bookmarks.is_empty() {
+            lines.push(locale::tr("bookmarks-empty").to_string());
+        }
+        lines.extend(self.bookmarks.iter().enumerate().map(|(i, bookmark)| {
+            let (rows, cols) = bookmark.size;
+            let best = match bookmark.best_ms {
+                Some(ms) => tui::format_time(Duration::from_millis(ms)),
+                None => format!("{:.0}%", bookmark.best_progress * 100.0),
+            };
+            format!(
+                "{} {} {}",
+                if i == selected { console::glyphs().pointer } else { ' ' },
+                if bookmark.best_ms.is_some() { console::glyphs().solved } else { ' ' },
+                locale::tr_with("bookmarks-line", &[
+                    ("cols", &cols), ("rows", &rows), ("mines", &bookmark.mines),
+                    ("attempts", &bookmark.attempts), ("best", &best),
+                ]),
+            )
+        }));
+        BoxedComponent::new(&Text::new(lines)).render_at(&mut buffer);
+
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        out.flush()
+    }
+}
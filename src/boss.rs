@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// The screen the boss key swaps the game for: the tail of a log that looks like work, which keeps scrolling.
+pub(crate) struct BossScreen {
+    pub(crate) lines: VecDeque<String>,
+    /// When the next line is written.
+    next: Instant,
+}
+
+impl BossScreen {
+    /// What the window title says while the screen is shown, if the game sets it.
+    pub(crate) const TITLE: &'static str = "tail -f /var/log/reports/worker.log";
+    /// How many lines are kept, enough to fill any terminal.
+    const KEPT: usize = 200;
+    const MESSAGES: [(&'static str, &'static str); 8] = [
+        ("INFO", "worker-{a}: processed batch {b} ({c} records) in {d}ms"),
+        ("INFO", "sync: fetched {c} objects from origin in {d}ms"),
+        ("INFO", "http: GET /api/v2/reports/{b} 200 {d}ms"),
+        ("INFO", "http: POST /api/v2/reports/{b}/export 202 {d}ms"),
+        ("DEBUG", "cache: hit rate 9{a}% over the last {c} requests"),
+        ("INFO", "db: checkpoint complete, {c} pages written"),
+        ("WARN", "http: slow response from upstream reports-{a} ({d}ms)"),
+        ("INFO", "scheduler: queued {a} jobs for the nightly rollup"),
+    ];
+
+    pub(crate) fn new() -> Self {
+        let mut screen = Self { lines: VecDeque::new(), next: Instant::now() + Self::delay() };
+        // Start with a screenful, as if it had been running for a while
+        let mut at = SystemTime::now() - Duration::from_secs(120);
+        for _ in 0..Self::KEPT {
+            screen.write(at);
+            at += Self::delay();
+        }
+        screen
+    }
+
+    /// How long until the line after this one, a few every second.
+    fn delay() -> Duration {
+        Duration::from_millis(rand::thread_rng().gen_range(50..1200))
+    }
+
+    /// Write a made up line at the end of the log, as if it was written at the given time.
+    fn write(&mut self, at: SystemTime) {
+        let mut rng = rand::thread_rng();
+        let (level, message) = Self::MESSAGES.choose(&mut rng).copied().unwrap_or(Self::MESSAGES[0]);
+        let message = message
+            .replace("{a}", &rng.gen_range(1..10).to_string())
+            .replace("{b}", &rng.gen_range(1000..99999).to_string())
+            .replace("{c}", &rng.gen_range(10..5000).to_string())
+            .replace("{d}", &rng.gen_range(3..900).to_string());
+        let time = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let (secs, ms) = (time.as_secs() % 86400, time.subsec_millis());
+        self.lines.push_back(format!(
+            "{:02}:{:02}:{:02}.{:03} {:<5} {}",
+            secs / 3600, secs / 60 % 60, secs % 60, ms, level, message,
+        ));
+        if self.lines.len() > Self::KEPT {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Write the lines that are due. Returns true if any were.
+    pub(crate) fn tick(&mut self) -> bool {
+        let mut written = false;
+        while self.next <= Instant::now() {
+            self.write(SystemTime::now());
+            self.next += Self::delay();
+            written = true;
+        }
+        written
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command as Process, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::engine::Command;
+use crate::game::{Cell, Field};
+use crate::locale;
+use crate::script;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// What an engine attached to a game does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum BotRole {
+    /// It plays the game, while the player watches.
+    Play,
+    /// It suggests a move whenever the player asks for a hint.
+    Hint,
+}
+
+/// An external program that picks moves, talking over its stdin and stdout a line at a time, a bit like a chess
+/// engine with UCI. termsweeper sends:
+///
+/// - `newgame ROWS COLS MINES` once, at the start.
+/// - `board`, then a line per row with a character per cell, then `go` when it wants a move. Cells are `#` if
+///   hidden, `F` if flagged, `*` for a revealed mine, `_` for a void, or the number shown (counts past 9 carry on
+///   as letters, so a cell is always one character).
+/// - `quit` when the game is closed.
+///
+/// The program answers each `go` with one move, in the same form as in script mode: `reveal R C`, `flag R C` or
+/// `chord R C`, with rows and columns counted from 0.
+pub struct Bot {
+    process: Child,
+    stdin: ChildStdin,
+    replies: Receiver<String>,
+    /// Set between asking for a move and getting it.
+    pending: bool,
+}
+
+impl Bot {
+    /// Start the program, given as a command line, for a game on the given field.
+    pub fn spawn(command: &str, field: &Field) -> IoResult<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or_else(|| std::io::Error::other("the engine command is empty"))?;
+        let mut process = Process::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (process.stdin.take(), process.stdout.take()) else { unreachable!() };
+
+        // Read replies on their own thread, so a slow engine never holds up the game
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut bot = Self { process, stdin, replies, pending: false };
+        let (rows, cols) = field.size();
+        writeln!(bot.stdin, "newgame {} {} {}", rows, cols, field.mine_count())?;
+        Ok(bot)
+    }
+
+    /// Ask for a move on the board as it stands, unless one has already been asked for.
+    pub fn request(&mut self, field: &Field) -> IoResult<()> {
+        if self.pending {
+            return Ok(());
+        }
+        let mut message = "board\n".to_string();
+        for row in &field.board {
+            message.extend(row.iter().map(cell_char));
+            message.push('\n');
+        }
+        message.push_str("go\n");
+        self.stdin.write_all(message.as_bytes())?;
+        self.stdin.flush()?;
+        self.pending = true;
+        Ok(())
+    }
+
+    /// Returns true between asking for a move and getting it.
+    pub fn pending(&self) -> bool {
+        self.pending
+    }
+
+    /// The move asked for on the given field, if it has arrived. Blank lines are skipped; a line that isn't a move on
+    /// the field is an error, as is the engine quitting.
+    pub fn reply(&mut self, field: &Field) -> Option<Result<Command, String>> {
+        if !self.pending {
+            return None;
+        }
+        loop {
+            match self.replies.try_recv() {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => {
+                    self.pending = false;
+                    let words = line.split_whitespace().collect::<Vec<_>>();
+                    let command = script::parse(&words).and_then(|command| match command {
+                        Command::Reveal(pos) | Command::Flag(pos) | Command::Chord(pos)
+                            if field.cell(pos).is_none() => Err(format!("{:?} is off the board", pos)),
+                        command => Ok(command),
+                    });
+                    return Some(command.map_err(|e| locale::tr_with("engine-bad-move", &[("error", &e)])));
+                },
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.pending = false;
+                    return Some(Err(locale::tr("engine-quit").to_string()));
+                },
+            }
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        // The engine may already be gone, in which case there's nothing to tell it
+        let _ = writeln!(self.stdin, "quit");
+        let _ = self.stdin.flush();
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn cell_char(cell: &Cell) -> char {
+    match cell {
+        cell if cell.is_void() => '_',
+        cell if cell.is_flagged() => 'F',
+        cell if cell.is_hidden() => '#',
+        cell if cell.is_mine() => '*',
+        cell => Cell::number_char(cell.neighbor_count()),
+    }
+}
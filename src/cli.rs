@@ -0,0 +1,264 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::path::PathBuf;
+use clap::builder::{PossibleValuesParser, RangedU64ValueParser, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::ai::Difficulty;
+use crate::locale::Lang;
+use crate::profile;
+use crate::stats::ExportFormat;
+
+type IoResult<T> = std::io::Result<T>;
+
+const GENERATORS: &[&str] = &[
+    "uniform", "no-guess", "clustered", "symmetric", "symmetric-vertical", "symmetric-rotational", "rings", "stripes",
+];
+/// The subcommands' names, which `man` takes to print one's page.
+const COMMANDS: &[&str] = &[
+    "stats", "achievements", "edit", "pack", "ladder", "bookmarks", "daily", "replay", "profiles", "custom", "versus",
+    "completions", "man",
+];
+
+/// Minesweeper in the terminal.
+///
+/// Reveal every cell that isn't a mine, without revealing a mine. Without a command, termsweeper starts a game; press
+/// F1 during one for the controls.
+#[derive(Parser, Debug)]
+#[command(name = "termsweeper", version, args_conflicts_with_subcommands = true)]
+pub(crate) struct Cli {
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+    #[command(flatten)]
+    pub(crate) game: GameArgs,
+    #[command(flatten)]
+    pub(crate) global: GlobalArgs,
+}
+
+/// The options every subcommand takes.
+#[derive(Args, Debug)]
+pub(crate) struct GlobalArgs {
+    /// Use the named profile's stats and saved data
+    #[arg(long, global = true, value_name = "NAME", value_parser = profile_name)]
+    pub(crate) profile: Option<String>,
+    /// The language to show text in
+    #[arg(
+        long,
+        global = true,
+        value_name = "LANG",
+        default_value = "en",
+        value_parser = choice(&["en", "de", "es"], Lang::parse),
+    )]
+    pub(crate) lang: Lang,
+    /// Print how long each step took on exit
+    #[arg(short, long, global = true)]
+    pub(crate) verbose: bool,
+}
+
+/// The options for a game: the one termsweeper starts without a subcommand, or with `versus` or `custom`.
+#[derive(Args, Debug, Default, PartialEq, Eq)]
+#[command(next_help_heading = "Game options")]
+pub(crate) struct GameArgs {
+    /// Play a preset (beginner, intermediate or expert), or a game saved from the custom game menu
+    #[arg(long, value_name = "PRESET")]
+    pub(crate) preset: Option<String>,
+    /// How the mines are laid out
+    #[arg(long, value_name = "GENERATOR", value_parser = PossibleValuesParser::new(GENERATORS))]
+    pub(crate) generator: Option<String>,
+    /// Pick the board based on how recent adaptive games went
+    #[arg(long)]
+    pub(crate) adaptive: bool,
+    /// Play 2 to 4 boards side by side
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(2..=4))]
+    pub(crate) boards: Option<usize>,
+    /// Play a 3D board of 2 to 4 layers
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(2..=4))]
+    pub(crate) layers: Option<usize>,
+    /// Play a board of triangles
+    #[arg(long, conflicts_with = "layers")]
+    pub(crate) triangles: bool,
+    /// Play the board in a board file
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub(crate) board: Option<PathBuf>,
+    /// Play the daily challenge of a day, like 2024-03-01, from the archive
+    #[arg(long, value_name = "DATE")]
+    pub(crate) daily: Option<String>,
+    /// Clear as much as possible before the time runs out
+    #[arg(long, conflicts_with = "countdown")]
+    pub(crate) sprint: bool,
+    /// Clear the board before the time runs out
+    #[arg(long)]
+    pub(crate) countdown: bool,
+    /// Add time for every flag on a safe cell
+    #[arg(long)]
+    pub(crate) hardcore: bool,
+    /// Move a mine every few seconds
+    #[arg(long)]
+    pub(crate) moving_mines: bool,
+    /// Show each number as a range of N more numbers around it
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<u8>::new().range(1..=3))]
+    pub(crate) noisy: Option<u8>,
+    /// Let an external engine play
+    #[arg(long, value_name = "COMMAND", value_hint = clap::ValueHint::CommandString)]
+    pub(crate) engine: Option<String>,
+    /// With --engine, only ask it for hints
+    #[arg(long, requires = "engine")]
+    pub(crate) hints: bool,
+    /// Record a replay of the game
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub(crate) record: Option<PathBuf>,
+    /// Stream every game event as JSON to a file, or to fd:N
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub(crate) json_events: Option<String>,
+    /// Play by text commands on stdin
+    #[arg(long)]
+    pub(crate) script: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Show the stats
+    Stats {
+        /// Write the history of games to stdout
+        #[arg(long, value_name = "FORMAT", value_parser = choice(&["csv", "json"], ExportFormat::parse))]
+        export: Option<ExportFormat>,
+        /// Add the games from another client's CSV export
+        #[arg(long, value_name = "FILE", conflicts_with = "export", value_hint = clap::ValueHint::FilePath)]
+        import: Option<PathBuf>,
+    },
+    /// Show the achievements
+    Achievements,
+    /// Edit a board file
+    Edit {
+        #[arg(value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        file: PathBuf,
+        /// The size of a new board
+        #[arg(long, value_name = "ROWSxCOLS", value_parser = board_size)]
+        size: Option<(usize, usize)>,
+    },
+    /// Play a puzzle pack
+    Pack {
+        #[arg(value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        dir: PathBuf,
+    },
+    /// Play the time trial ladder
+    Ladder,
+    /// Play a bookmarked board
+    Bookmarks,
+    /// Play today's daily challenge
+    Daily,
+    /// Export a replay
+    #[command(group = clap::ArgGroup::new("output").required(true).multiple(true))]
+    Replay {
+        #[arg(value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        file: PathBuf,
+        /// Write it as an asciinema cast
+        #[arg(long, value_name = "FILE", group = "output", value_hint = clap::ValueHint::FilePath)]
+        export_cast: Option<PathBuf>,
+        /// Write it as a GIF, with agg
+        #[arg(long, value_name = "FILE", group = "output", value_hint = clap::ValueHint::FilePath)]
+        export_gif: Option<PathBuf>,
+    },
+    /// Pick a profile
+    Profiles,
+    /// Set up a custom game
+    Custom {
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Play against 1 to 3 others on one board
+    Versus {
+        #[command(flatten)]
+        versus: VersusArgs,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Print a completion script for a shell
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
+    /// Print the man page, or a command's
+    Man {
+        #[arg(value_name = "COMMAND", value_parser = PossibleValuesParser::new(COMMANDS))]
+        command: Option<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(next_help_heading = "Versus options")]
+pub(crate) struct VersusArgs {
+    /// How many play
+    #[arg(
+        value_name = "PLAYERS",
+        default_value_t = 2,
+        value_parser = RangedU64ValueParser::<usize>::new().range(2..=4),
+    )]
+    pub(crate) players: usize,
+    /// Score by flagging mines instead of revealing cells
+    #[arg(long, conflicts_with = "sudden_death")]
+    pub(crate) flags: bool,
+    /// Knock out whoever reveals a mine
+    #[arg(long)]
+    pub(crate) sudden_death: bool,
+    /// Make the last player the computer
+    #[arg(
+        long,
+        value_name = "DIFFICULTY",
+        num_args = 0..=1,
+        default_missing_value = "medium",
+        value_parser = choice(&["easy", "medium", "hard"], Difficulty::parse),
+    )]
+    pub(crate) ai: Option<Difficulty>,
+}
+
+/// One of the given words, turned into a value by `parse`, which has to take every one of them.
+fn choice<T: Clone + Send + Sync + 'static>(
+    words: &'static [&'static str],
+    parse: fn(&str) -> Option<T>,
+) -> impl TypedValueParser<Value = T> {
+    PossibleValuesParser::new(words).map(move |word| parse(&word).expect("every choice parses"))
+}
+
+fn profile_name(name: &str) -> Result<String, String> {
+    match profile::valid(name) {
+        true => Ok(name.to_string()),
+        false => Err("a profile's name can't be empty, start with a dot, or have slashes or colons in it".to_string()),
+    }
+}
+
+/// A board size, like 9x9.
+fn board_size(size: &str) -> Result<(usize, usize), String> {
+    size.split_once('x')
+        .and_then(|(rows, cols)| Some((rows.parse().ok()?, cols.parse().ok()?)))
+        .ok_or_else(|| "the size must be like 9x9".to_string())
+}
+
+/// Report a command line that parsed, but can't be played, and exit.
+pub(crate) fn fail(message: impl Display) -> ! {
+    Cli::command().error(ErrorKind::ArgumentConflict, message).exit()
+}
+
+/// Write the completion script for the given shell.
+pub(crate) fn completions(shell: Shell, out: &mut impl Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "termsweeper", out);
+}
+
+/// Write the man page, as roff: termsweeper's, or one of its commands'.
+pub(crate) fn man(command: Option<&str>, out: &mut impl Write) -> IoResult<()> {
+    let mut cli = Cli::command();
+    // Builds it, so that commands have the global options too
+    cli.build();
+    let page = match command {
+        Some(name) => {
+            let subcommand = cli.find_subcommand(name).expect("only commands' names are accepted").clone();
+            // Named the way the main page refers to it, as termsweeper-NAME
+            subcommand.name(format!("termsweeper-{}", name)).version(env!("CARGO_PKG_VERSION"))
+        },
+        None => cli,
+    };
+    let title = page.get_name().to_uppercase();
+    clap_mangen::Man::new(page).title(title).section("6").manual("Games").render(out)
+}
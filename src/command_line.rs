@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+
+use crate::console::{BorderStyle, GlyphSet};
+use crate::engine::Command;
+use crate::preset::Preset;
+use crate::ruler;
+use crate::script;
+
+/// A command typed at the game's `:` prompt:
+///
+/// - `new` starts over on a board like the current one, `new PRESET` on one of the presets, and `new ROWSxCOLS MINES`
+///   on a board of that size, kept to the sizes the custom game menu allows.
+/// - `seed N` starts over on a board like the current one, laid out from the seed, so the same seed gives the same
+///   board again.
+/// - `reveal R C`, `flag R C` and `chord R C` are moves, with the cell counted from 0 as in scripts. The cell can
+///   also be named as on the rulers instead, like `flag C7`.
+/// - `goto R C` (or `goto C7`) moves the cursor to the cell.
+/// - `glyphs SET` and `borders STYLE` change how the game is drawn until it's closed, taking the same values as the
+///   config file.
+/// - `save NAME` saves the board as `NAME.txt` in the current directory, in the board file format.
+/// - `stats` shows the stats.
+#[derive(Debug)]
+pub(crate) enum Line {
+    /// Start over on a new board: the given size (as rows and columns) and number of mines, or the current one's if
+    /// there isn't one, laid out from the seed if there is one.
+    New { board: Option<((usize, usize), usize)>, seed: Option<u64> },
+    Move(Command),
+    /// Move the cursor to the cell, as (row, col).
+    Goto((usize, usize)),
+    Glyphs(GlyphSet),
+    Borders(BorderStyle),
+    Save(String),
+    Stats,
+}
+
+/// Parse a line typed at the prompt, without the `:`.
+pub(crate) fn parse(line: &str) -> Result<Line, String> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["new"] => Ok(Line::New { board: None, seed: None }),
+        ["new", preset] => {
+            let preset = Preset::parse(preset).ok_or_else(|| format!("{} isn't a preset", preset))?;
+            Ok(Line::New { board: Some((preset.size(), preset.mines())), seed: None })
+        },
+        ["new", size, mines] => {
+            let size = size.split_once('x')
+                .and_then(|(rows, cols)| Some((rows.parse().ok()?, cols.parse().ok()?)))
+                .ok_or_else(|| format!("{} isn't a size like 16x30", size))?;
+            let mines = mines.parse().map_err(|_| format!("{} isn't a number of mines", mines))?;
+            Ok(Line::New { board: Some((size, mines)), seed: None })
+        },
+        ["seed", seed] => {
+            let seed = seed.parse().map_err(|_| format!("{} isn't a seed", seed))?;
+            Ok(Line::New { board: None, seed: Some(seed) })
+        },
+        ["goto", ref words @ ..] => cell(words).map(Line::Goto),
+        [verb @ ("reveal" | "flag" | "chord"), name] => {
+            let pos = cell(&[name])?;
+            Ok(Line::Move(match verb {
+                "reveal" => Command::Reveal(pos),
+                "flag" => Command::Flag(pos),
+                _ => Command::Chord(pos),
+            }))
+        },
+        ["glyphs", set] => setting(set).map(Line::Glyphs),
+        ["borders", style] => setting(style).map(Line::Borders),
+        // Saved in the current directory, and nowhere else
+        ["save", name] if name.contains(['/', '\\']) => Err(format!("{} isn't a file name", name)),
+        ["save", name] => Ok(Line::Save(name.to_string())),
+        ["stats"] => Ok(Line::Stats),
+        ref words => script::parse(words).map(Line::Move),
+    }
+}
+
+/// A cell, as (row, col): named as on the rulers, like `C7`, or as a row and a column counted from 0.
+fn cell(words: &[&str]) -> Result<(usize, usize), String> {
+    match words {
+        [name] => ruler::parse_cell(name).ok_or_else(|| format!("{} isn't a cell like C7", name)),
+        [row, col] => Ok((
+            row.parse().map_err(|_| format!("{} isn't a row", row))?,
+            col.parse().map_err(|_| format!("{} isn't a column", col))?,
+        )),
+        _ => Err("expected a cell".to_string()),
+    }
+}
+
+/// A setting's value, spelled as in the config file.
+fn setting<'de, T: Deserialize<'de>>(value: &'de str) -> Result<T, String> {
+    T::deserialize(value.into_deserializer()).map_err(|e: serde::de::value::Error| e.to_string())
+}
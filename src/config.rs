@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+use toml::Table;
+
+use crate::console::{self, BorderStyle, GlyphSet, Graphics};
+use crate::custom::CustomGame;
+use crate::keymap::{self, ControlPreset};
+use crate::palette::{self, ColorDepth};
+use crate::paths;
+use crate::profile;
+use crate::repeat::RepeatConfig;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// User settings, stored as TOML in the platform config directory. Missing keys take their default values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub(crate) controls: ControlPreset,
+    pub(crate) repeat: RepeatConfig,
+    /// Use the kitty keyboard protocol in terminals that support it.
+    pub(crate) enhanced_keyboard: bool,
+    /// Pause the game (and hide the board) while the terminal window doesn't have focus.
+    pub(crate) pause_on_focus_loss: bool,
+    /// The color notes are drawn in: one of the terminal's named colors, like `yellow` or `dark_cyan`, a `#rrggbb`
+    /// hex color or an index into the 256-color palette. Terminals with fewer colors get the nearest they have.
+    pub(crate) note_color: String,
+    pub(crate) assist: AssistConfig,
+    /// Ask before revealing a cell the solver can prove is a mine.
+    pub(crate) warn_mistakes: bool,
+    /// Show the board and clock in the terminal's window title. Off by default, since not every terminal puts the
+    /// old title back afterwards.
+    pub(crate) window_title: bool,
+    /// Send a desktop notification when a board that took a while to lay out is ready, as a dense no-guess board can
+    /// (in a build with the `notifications` feature).
+    pub(crate) notifications: bool,
+    /// Suggest a harder (or easier) preset, going by the win rate on those played so far: before a game started without
+    /// any options, and in the custom game menu.
+    pub(crate) suggestions: bool,
+    /// Briefly highlight the cells the last move revealed or flagged, fading out over a moment, so it's clear what a
+    /// chord or flood fill changed.
+    pub(crate) highlight_changes: bool,
+    /// While the cursor is on a revealed number, highlight the cells chording it would reveal: in green if it has as
+    /// many flags around it as it shows, or in yellow if not.
+    pub(crate) chord_preview: bool,
+    /// Keep the screen still, for players sensitive to motion and for slow remote connections: highlights stay one
+    /// color instead of fading, the boss key's log doesn't scroll, and the terminal's own cursor is hidden instead of
+    /// jumping around as each frame is drawn.
+    pub(crate) reduced_motion: bool,
+    /// Tune the game for slow connections, like SSH over a bad link: it ticks less often, and nothing moves (as with
+    /// `reduced_motion`).
+    pub(crate) slow_link: bool,
+    /// Save power, for long games on a laptop's battery: rather than waking up ten times a second, the game only draws
+    /// a frame when something happens or something on screen moves (like the clock, once a second), and keys that come
+    /// in together are drawn as one frame.
+    pub(crate) low_power: bool,
+    /// Start in presentation mode, for streaming or teaching on a projector: the cursor is drawn in bright colors with
+    /// its row and column highlighted, and the clock big, in place of the controls. Toggled in the game with ctrl+p.
+    pub(crate) presentation: bool,
+    /// Show rulers along the top and left of the boards, naming the columns with letters and the rows with numbers, so
+    /// cells can be named like `C7` (as the `:` prompt's `goto`, `reveal`, `flag` and `chord` take them).
+    pub(crate) rulers: bool,
+    /// Moving the cursor past the edge of the board brings it round to the opposite edge, instead of stopping it.
+    pub(crate) wrap_cursor: bool,
+    /// The boss key, which swaps the game for a screen that looks like work (with the clock stopped), and back: a
+    /// character, or one of `space`, `tab` or `f1` to `f12`. Empty for none.
+    pub(crate) boss_key: String,
+    /// Draw with Unicode (`unicode`) or plain ASCII (`ascii`), or pick whichever the console can show (`auto`).
+    pub(crate) glyphs: GlyphSet,
+    /// The lines boxes are drawn with: `rounded`, `double`, `heavy`, or `none` for none. ASCII boxes are always drawn
+    /// with `+`, `-` and `|`, unless there are none.
+    pub(crate) border_style: BorderStyle,
+    /// The color boxes are drawn in, in any of the ways `note_color` can be given. Empty for the terminal's own text
+    /// color.
+    pub(crate) border_color: String,
+    /// Draw the boards as pictures, with the tiles of the classic game, on terminals that can show them (in a build
+    /// with the `graphics` feature): `kitty` or `sixel` for the way they're sent, `off` for text, or `auto` to pick
+    /// whichever the terminal looks like it handles.
+    pub(crate) graphics: Graphics,
+    /// The FIGlet font big titles (like the end screen's) are drawn in: `standard` for the one built in, `plain` for
+    /// plain text, or the path of a font file. They're plain text anyway if the font can't be loaded, is missing any of
+    /// the title's characters, or makes it too wide for the terminal.
+    pub(crate) title_font: String,
+    /// What the end screen says when a game is won or lost, and the fonts it says it in.
+    pub(crate) banners: BannerConfig,
+    /// Games saved from the custom game menu, by name, for `--preset NAME`.
+    pub(crate) presets: BTreeMap<String, CustomGame>,
+}
+
+/// Moves the game makes for the player whenever the basic solver rules force them. Games played with any assist on
+/// don't go in the stats.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct AssistConfig {
+    /// Flag the cells that are certainly mines.
+    pub(crate) flag: bool,
+    /// Reveal the cells that are certainly safe.
+    pub(crate) reveal: bool,
+}
+
+/// The end screen's banners for won and lost games, each with its own text and font. Anything left out is as usual.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct BannerConfig {
+    pub(crate) won: Banner,
+    pub(crate) lost: Banner,
+}
+
+/// One of the end screen's banners, like `BOOM` in a font of its own for lost games.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Banner {
+    /// What it says, in place of the usual text (which is translated). Empty for the usual.
+    pub(crate) text: String,
+    /// The FIGlet font it's drawn in, as for `title_font`. Empty for `title_font`'s.
+    pub(crate) font: String,
+}
+
+impl Banner {
+    /// The banner's own font, if it has one.
+    pub(crate) fn font(&self) -> Option<&str> {
+        Some(self.font.as_str()).filter(|font| !font.is_empty())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            controls: ControlPreset::default(),
+            repeat: RepeatConfig::default(),
+            enhanced_keyboard: true,
+            pause_on_focus_loss: true,
+            note_color: "yellow".to_string(),
+            assist: AssistConfig::default(),
+            warn_mistakes: false,
+            window_title: false,
+            notifications: true,
+            suggestions: true,
+            highlight_changes: false,
+            chord_preview: false,
+            reduced_motion: false,
+            slow_link: false,
+            low_power: false,
+            presentation: false,
+            rulers: false,
+            wrap_cursor: false,
+            boss_key: "f12".to_string(),
+            glyphs: GlyphSet::default(),
+            border_style: BorderStyle::default(),
+            border_color: String::new(),
+            graphics: Graphics::default(),
+            title_font: "standard".to_string(),
+            banners: BannerConfig::default(),
+            presets: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        Some(paths::config_dir()?.join("config.toml"))
+    }
+
+    /// Load the config file, with the chosen profile's overrides on top. A missing file (or no known config directory)
+    /// is the default config.
+    pub(crate) fn load() -> IoResult<Self> {
+        let mut table = Self::read(Self::path())?;
+        merge(&mut table, Self::read(profile::config_path())?);
+        Self::from_table(table)
+    }
+
+    /// The config as it is now, if it's changed since this one was loaded.
+    #[cfg(feature = "hot-reload")]
+    pub(crate) fn reloaded(&self) -> IoResult<Option<Self>> {
+        let config = Self::load()?;
+        Ok((config.to_table()? != self.to_table()?).then_some(config))
+    }
+
+    /// The settings in a config file, as they're written. A missing file has none.
+    fn read(path: Option<PathBuf>) -> IoResult<Table> {
+        let Some(path) = path else { return Ok(Table::new()) };
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse().map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Table::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn from_table(table: Table) -> IoResult<Self> {
+        toml::Value::Table(table).try_into().map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    fn to_table(&self) -> IoResult<Table> {
+        toml::Table::try_from(self).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Parse the contents of a config file. Malformed files are an [`ErrorKind::InvalidData`] error.
+    pub fn parse(text: &str) -> IoResult<Self> {
+        toml::from_str(text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// The boss key, if there's one and it's a key.
+    pub(crate) fn boss_key(&self) -> Option<KeyCode> {
+        keymap::parse_key(&self.boss_key)
+    }
+
+    /// Whether to keep the screen still: with reduced motion, or on a slow link.
+    pub(crate) fn reduced_motion(&self) -> bool {
+        self.reduced_motion || self.slow_link
+    }
+
+    /// The color notes are drawn in, falling back to yellow if the configured one isn't a color.
+    pub(crate) fn note_color(&self) -> Color {
+        palette::parse(&self.note_color).unwrap_or(Color::Yellow)
+    }
+
+    /// Draw boxes the way this config says to, in the nearest color to its border color that the terminal can show.
+    pub(crate) fn pick_borders(&self, depth: ColorDepth) {
+        console::pick_borders(self.border_style, palette::parse(&self.border_color).map(|color| depth.fit(color)));
+    }
+
+    /// Save the config file. With a profile chosen, only the settings that differ from the config file's go in the
+    /// profile's overrides, so the rest keep following the config file.
+    pub(crate) fn save(&self) -> IoResult<()> {
+        let mut table = self.to_table()?;
+        let path = match profile::config_path() {
+            Some(path) => {
+                let base = Self::from_table(Self::read(Self::path())?)?.to_table()?;
+                table.retain(|key, value| base.get(key) != Some(value));
+                path
+            },
+            None => match Self::path() {
+                Some(path) => path,
+                None => return Ok(()),
+            },
+        };
+        let text = toml::to_string(&table).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        paths::write_safely(&path, text.as_bytes())
+    }
+}
+
+/// Put the settings of `overrides` in `table`, in place of those there. Tables are merged key by key, so a profile can
+/// change one assist without repeating the others.
+fn merge(table: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => merge(table, overrides),
+            (_, value) => _ = table.insert(key, value),
+        }
+    }
+}
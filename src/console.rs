@@ -0,0 +1,207 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+
+/// Which characters the game is drawn with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum GlyphSet {
+    /// Unicode, unless the console can't show it.
+    #[default]
+    Auto,
+    Unicode,
+    /// Plain ASCII, for consoles that aren't set up for UTF-8 (like older Windows consoles).
+    Ascii,
+}
+
+/// Which lines the boxes around panels are drawn with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BorderStyle {
+    /// Thin lines with rounded corners.
+    #[default]
+    Rounded,
+    Double,
+    Heavy,
+    /// No lines at all, just the space they'd take up, so nothing moves.
+    None,
+}
+
+impl BorderStyle {
+    const ALL: [BorderStyle; 4] = [BorderStyle::Rounded, BorderStyle::Double, BorderStyle::Heavy, BorderStyle::None];
+
+    /// The corners, clockwise from the top left, then the horizontal and vertical sides, like [`Glyphs::borders`].
+    /// Only rounded borders can be drawn in ASCII; the other styles are drawn like them there, except for no borders.
+    fn glyphs(self) -> [char; 6] {
+        match self {
+            BorderStyle::Rounded => glyphs().borders,
+            BorderStyle::Double if !ASCII.load(Ordering::Relaxed) => ['╔', '╗', '╝', '╚', '═', '║'],
+            BorderStyle::Heavy if !ASCII.load(Ordering::Relaxed) => ['┏', '┓', '┛', '┗', '━', '┃'],
+            BorderStyle::Double | BorderStyle::Heavy => glyphs().borders,
+            BorderStyle::None => [' '; 6],
+        }
+    }
+}
+
+/// How the boards are drawn, on terminals that can show pictures. Only used with the `graphics` feature.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Graphics {
+    /// Pictures, if the terminal looks like it can show them, or else text.
+    #[default]
+    Auto,
+    /// Pictures sent with Kitty's graphics protocol.
+    Kitty,
+    /// Pictures sent as Sixel graphics.
+    Sixel,
+    /// Always text.
+    Off,
+}
+
+/// The characters everything on screen is drawn with, other than text.
+#[derive(Debug)]
+pub(crate) struct Glyphs {
+    pub(crate) hidden: char,
+    pub(crate) flag: char,
+    pub(crate) exploded: char,
+    pub(crate) empty: char,
+    pub(crate) cursor: char,
+    pub(crate) note: char,
+    /// Hidden triangles on a triangular board, pointing up and down.
+    pub(crate) triangles: [char; 2],
+    /// The corners of a box, clockwise from the top left, then its horizontal and vertical sides.
+    pub(crate) borders: [char; 6],
+    /// Arrow keys, as left, right, up and down, and the shift key.
+    pub(crate) arrows: [char; 4],
+    pub(crate) shift: &'static str,
+    /// The marker beside the selected item of a menu.
+    pub(crate) pointer: char,
+    /// Whether a setting is the chosen one, as yes and no.
+    pub(crate) chosen: [char; 2],
+    /// Whether an achievement has been earned, as yes and no.
+    pub(crate) earned: [char; 2],
+    /// A solved puzzle.
+    pub(crate) solved: char,
+    /// A player that's out of a versus game.
+    pub(crate) eliminated: char,
+    /// A cell marked safe in the board editor.
+    pub(crate) safe: char,
+    /// The bars of a chart, from an eighth of a cell high up to a whole cell.
+    pub(crate) bars: [char; 8],
+    /// The shades of a heatmap, from the lightest to the darkest.
+    pub(crate) shades: [char; 4],
+    /// The frames of a spinner, shown while waiting on something slow.
+    pub(crate) spinner: &'static str,
+    /// A scrollbar's thumb, then its track.
+    pub(crate) scrollbar: [char; 2],
+    /// Between the parts of a line that packs several things in, like the status bar.
+    pub(crate) separator: char,
+    /// Where there's more than fits on screen.
+    pub(crate) more: char,
+}
+
+impl Glyphs {
+    const UNICODE: Glyphs = Glyphs {
+        hidden: '█',
+        flag: '⚑',
+        exploded: '✲',
+        empty: '░',
+        cursor: '◎',
+        note: '•',
+        triangles: ['▲', '▼'],
+        borders: ['╭', '╮', '╯', '╰', '─', '│'],
+        arrows: ['←', '→', '↑', '↓'],
+        shift: "⇧",
+        pointer: '▶',
+        chosen: ['●', '○'],
+        earned: ['★', '☆'],
+        solved: '✓',
+        eliminated: '✗',
+        safe: '·',
+        bars: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        shades: ['░', '▒', '▓', '█'],
+        spinner: "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏",
+        scrollbar: ['█', '│'],
+        separator: '·',
+        more: '…',
+    };
+
+    const ASCII: Glyphs = Glyphs {
+        hidden: '#',
+        flag: 'F',
+        exploded: '*',
+        empty: '.',
+        cursor: '@',
+        note: 'o',
+        triangles: ['^', 'v'],
+        borders: ['+', '+', '+', '+', '-', '|'],
+        arrows: ['<', '>', '^', 'v'],
+        shift: "S-",
+        pointer: '>',
+        chosen: ['*', ' '],
+        earned: ['*', ' '],
+        solved: '+',
+        eliminated: 'x',
+        safe: '.',
+        bars: ['_', '.', ',', ':', '-', '=', '+', '#'],
+        shades: [':', '+', '*', '#'],
+        spinner: "|/-\\",
+        scrollbar: ['#', '|'],
+        separator: '|',
+        more: '+',
+    };
+
+    /// Whether the given setting picks the ASCII glyphs, on a console with the given output code page (if it has one,
+    /// as on Windows). Code page 65001 is UTF-8.
+    fn ascii(set: GlyphSet, code_page: Option<u32>) -> bool {
+        match (set, code_page) {
+            (GlyphSet::Unicode, _) => false,
+            (GlyphSet::Ascii, _) => true,
+            (GlyphSet::Auto, Some(code_page)) => code_page != 65001,
+            (GlyphSet::Auto, None) => false,
+        }
+    }
+}
+
+/// Whether the ASCII glyphs were picked over the Unicode ones.
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Get the console ready to be drawn on, and pick the glyphs it can show. Called once, at startup.
+pub(crate) fn init(set: GlyphSet) {
+    // This fails when the output isn't a console at all (e.g. it's piped), which then has no need for it
+    let _ = platform::enable_escape_codes();
+    pick_glyphs(set);
+}
+
+/// Switch to the glyphs for the given setting, from the next frame on, as when the config file is reloaded.
+pub(crate) fn pick_glyphs(set: GlyphSet) {
+    ASCII.store(Glyphs::ascii(set, platform::code_page()), Ordering::Relaxed);
+}
+
+/// The glyphs picked by [`init`], or the Unicode ones if it hasn't been called (as in headless games).
+pub(crate) fn glyphs() -> &'static Glyphs {
+    if ASCII.load(Ordering::Relaxed) { &Glyphs::ASCII } else { &Glyphs::UNICODE }
+}
+
+static BORDER_STYLE: AtomicU8 = AtomicU8::new(BorderStyle::Rounded as u8);
+/// None for the terminal's own text color.
+static BORDER_COLOR: Mutex<Option<Color>> = Mutex::new(None);
+
+/// Draw boxes with the given style and color (already fit to what the terminal can show) from the next frame on.
+pub(crate) fn pick_borders(style: BorderStyle, color: Option<Color>) {
+    BORDER_STYLE.store(style as u8, Ordering::Relaxed);
+    *BORDER_COLOR.lock().unwrap_or_else(|e| e.into_inner()) = color;
+}
+
+/// The characters boxes are drawn with, in the style picked by [`pick_borders`].
+pub(crate) fn borders() -> [char; 6] {
+    BorderStyle::ALL[BORDER_STYLE.load(Ordering::Relaxed) as usize].glyphs()
+}
+
+/// The color boxes are drawn in, if [`pick_borders`] picked one.
+pub(crate) fn border_color() -> Option<Color> {
+    *BORDER_COLOR.lock().unwrap_or_else(|e| e.into_inner())
+}
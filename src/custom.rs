@@ -0,0 +1,366 @@
+use std::collections::BTreeMap;
+use std::io::{stdout, Write};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use crossterm::{
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    cursor::MoveTo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::adaptive::{self, Suggestion};
+use crate::config::Config;
+use crate::console;
+use crate::daily;
+use crate::keymap;
+use crate::locale;
+use crate::preset::Preset;
+use crate::stats::Stats;
+use crate::tui::{BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// A board and rules picked in the custom game menu, for those who'd rather not spell them out on the command line.
+/// They can be saved in the config under a name, to play again with `--preset NAME`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomGame {
+    /// The board size, as (rows, columns).
+    pub size: (usize, usize),
+    /// The number of mines on the board.
+    pub mines: usize,
+    /// Lay the board out so it can be cleared without guessing.
+    #[serde(default)]
+    pub no_guess: bool,
+    /// Add time for every flag on a safe cell.
+    #[serde(default)]
+    pub hardcore: bool,
+    /// Move a mine every few seconds.
+    #[serde(default)]
+    pub moving_mines: bool,
+}
+
+/// The lines of the menu, top to bottom.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Setting {
+    Rows,
+    Columns,
+    Mines,
+    NoGuess,
+    Hardcore,
+    MovingMines,
+}
+
+impl Setting {
+    const ALL: [Setting; 6] = [
+        Setting::Rows,
+        Setting::Columns,
+        Setting::Mines,
+        Setting::NoGuess,
+        Setting::Hardcore,
+        Setting::MovingMines,
+    ];
+
+    fn label(self) -> &'static str {
+        locale::tr(match self {
+            Setting::Rows => "custom-rows",
+            Setting::Columns => "custom-columns",
+            Setting::Mines => "custom-mines",
+            Setting::NoGuess => "custom-no-guess",
+            Setting::Hardcore => "custom-hardcore",
+            Setting::MovingMines => "custom-moving-mines",
+        })
+    }
+}
+
+impl Default for CustomGame {
+    /// A Beginner board, to start changing from.
+    fn default() -> Self {
+        Self {
+            size: Preset::Beginner.size(),
+            mines: Preset::Beginner.mines(),
+            no_guess: false,
+            hardcore: false,
+            moving_mines: false,
+        }
+    }
+}
+
+impl CustomGame {
+    /// The number of rows the menu allows.
+    pub const ROWS: RangeInclusive<usize> = 2..=50;
+    /// The number of columns the menu allows.
+    pub const COLUMNS: RangeInclusive<usize> = 2..=99;
+
+    /// The most mines a board of this size can take. At most half the cells can be mines, and without guessing, the
+    /// opening in the middle and the cells around it are kept clear too.
+    fn max_mines(&self) -> usize {
+        let (rows, cols) = self.size;
+        let max = (rows * cols).div_ceil(2);
+        if self.no_guess {
+            max.min(rows * cols - rows.min(3) * cols.min(3))
+        } else {
+            max
+        }
+    }
+
+    /// What would stop the board from being played, if anything.
+    fn problem(&self) -> Option<String> {
+        let max = self.max_mines();
+        (self.mines > max).then(|| locale::tr_with("custom-too-many-mines", &[("max", &max)]))
+    }
+
+    /// Step a number up or down, or flip a toggle.
+    fn change(&mut self, setting: Setting, by: isize) {
+        let step = |value: usize, range: RangeInclusive<usize>| {
+            value.saturating_add_signed(by).clamp(*range.start(), *range.end())
+        };
+        match setting {
+            Setting::Rows => self.size.0 = step(self.size.0, Self::ROWS),
+            Setting::Columns => self.size.1 = step(self.size.1, Self::COLUMNS),
+            Setting::Mines => self.mines = step(self.mines, 1..=self.size.0 * self.size.1),
+            Setting::NoGuess => self.no_guess = !self.no_guess,
+            Setting::Hardcore => self.hardcore = !self.hardcore,
+            Setting::MovingMines => self.moving_mines = !self.moving_mines,
+        }
+    }
+
+    /// Move the board's bottom right corner by the given (rows, columns), with as many mines as keep it at the given
+    /// density.
+    pub fn resize(&mut self, (rows, cols): (isize, isize), density: f64) {
+        self.change(Setting::Rows, rows);
+        self.change(Setting::Columns, cols);
+        let cells = (self.size.0 * self.size.1) as f64;
+        self.mines = ((cells * density).round() as usize).clamp(1, self.max_mines());
+    }
+
+    /// Show the menu until a game is picked: set up in the menu, one of those saved in the config, or the preset it
+    /// suggests. Returns None if the player quit instead.
+    pub(crate) fn choose() -> IoResult<Option<Choice>> {
+        execute!(stdout(), EnterAlternateScreen)?;
+        terminal::enable_raw_mode()?;
+        let config = Config::load()?;
+        // Not having any stats to go by is no reason to stop the menu from showing
+        let suggestion = adaptive::pending(&Stats::load().unwrap_or_default(), &config);
+        let result = Menu::new(config.presets, suggestion).run();
+        terminal::disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        result
+    }
+
+    /// The board, described for the list of saved ones.
+    fn summary(&self) -> String {
+        locale::tr_with("custom-summary", &[("rows", &self.size.0), ("columns", &self.size.1), ("mines", &self.mines)])
+    }
+}
+
+/// What was picked in the custom game menu.
+pub(crate) enum Choice {
+    /// A board and rules set up in the menu, or saved from it.
+    Custom(CustomGame),
+    /// The preset the menu suggested, played as usual rather than as a custom game, so it counts like any other.
+    Preset(Preset),
+}
+
+/// A picture of a board of the given size as (rows, columns), a character per cell, with the corner being dragged
+/// marked with the cursor. Boards that don't fit in the given room are drawn smaller, each character standing for a
+/// square of cells.
+pub fn preview((rows, cols): (usize, usize), width: usize, height: usize) -> Vec<String> {
+    let scale = rows.div_ceil(height).max(cols.div_ceil(width)).max(1);
+    let (rows, cols) = (rows.div_ceil(scale), cols.div_ceil(scale));
+    let glyphs = console::glyphs();
+    (0..rows)
+        .map(|row| (0..cols)
+            .map(|col| if (row, col) == (rows - 1, cols - 1) { glyphs.cursor } else { glyphs.hidden })
+            .collect())
+        .collect()
+}
+
+/// The custom game menu: the settings, followed by the saved games.
+struct Menu {
+    custom: CustomGame,
+    saved: BTreeMap<String, CustomGame>,
+    /// The selected line, counting the saved games after the settings.
+    selected: usize,
+    /// The name being typed for the game to be saved as, once saving has started.
+    naming: Option<String>,
+    /// The result of the last save, shown until anything changes.
+    status: Option<String>,
+    /// The density of mines when resizing the board by dragging its corner started, kept while it's being dragged so
+    /// that the mines follow the size without rounding creeping in.
+    resizing: Option<f64>,
+    /// A preset to try, from how the stats say the player does. Shown until it's tried or dismissed.
+    suggestion: Option<Suggestion>,
+    /// The day of the daily challenge shown in the banner, with how it's gone, loaded again once the day is over.
+    daily: (u64, daily::Status),
+}
+
+impl Menu {
+    fn new(saved: BTreeMap<String, CustomGame>, suggestion: Option<Suggestion>) -> Self {
+        Self {
+            custom: CustomGame::default(),
+            saved,
+            selected: 0,
+            naming: None,
+            status: None,
+            resizing: None,
+            suggestion,
+            daily: Self::daily(),
+        }
+    }
+
+    /// Today's daily challenge, and how it's gone. A results file that can't be read is no reason to stop the menu
+    /// from showing, so that's taken as not having played it.
+    fn daily() -> (u64, daily::Status) {
+        let today = daily::today();
+        (today, daily::status(today, false).unwrap_or(daily::Status::NotAttempted))
+    }
+
+    fn run(mut self) -> IoResult<Option<Choice>> {
+        loop {
+            self.render()?;
+            // Draw the menu again every second, for the countdown to the next daily challenge
+            if !event::poll(Duration::from_secs(1))? {
+                if self.daily.0 != daily::today() {
+                    self.daily = Self::daily();
+                }
+                continue;
+            }
+            let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) =
+                event::read()?
+            else { continue };
+            if let Some(name) = &mut self.naming {
+                match code {
+                    KeyCode::Char(c) if !c.is_control() => name.push(c),
+                    KeyCode::Backspace => _ = name.pop(),
+                    KeyCode::Enter => self.save()?,
+                    KeyCode::Esc => self.naming = None,
+                    _ => {},
+                }
+                continue;
+            }
+            if let Some(density) = self.resizing {
+                let by = if modifiers.contains(KeyModifiers::SHIFT) { 10 } else { 1 };
+                match code {
+                    KeyCode::Up => self.custom.resize((-by, 0), density),
+                    KeyCode::Down => self.custom.resize((by, 0), density),
+                    KeyCode::Left => self.custom.resize((0, -by), density),
+                    KeyCode::Right => self.custom.resize((0, by), density),
+                    KeyCode::Enter | KeyCode::Esc | KeyCode::Char('r') => self.resizing = None,
+                    _ => {},
+                }
+                continue;
+            }
+
+            self.status = None;
+            let by = if modifiers.contains(KeyModifiers::SHIFT) { 10 } else { 1 };
+            let setting = Setting::ALL.get(self.selected).copied();
+            match (code, setting) {
+                (KeyCode::Up, _) => self.selected = self.selected.saturating_sub(1),
+                (KeyCode::Down, _) => self.selected = (self.selected + 1).min(Setting::ALL.len() + self.saved.len() - 1),
+                (KeyCode::Left, Some(setting)) => self.custom.change(setting, -by),
+                (KeyCode::Right | KeyCode::Char(' '), Some(setting)) => self.custom.change(setting, by),
+                (KeyCode::Enter, Some(_)) if self.custom.problem().is_none() => {
+                    return Ok(Some(Choice::Custom(self.custom)));
+                },
+                (KeyCode::Enter, None) => {
+                    let saved = self.saved.values().nth(self.selected - Setting::ALL.len()).copied();
+                    return Ok(saved.map(Choice::Custom));
+                },
+                (KeyCode::Char('s'), _) if self.custom.problem().is_none() => self.naming = Some(String::new()),
+                (KeyCode::Char('t'), _) => if let Some(suggestion) = self.suggestion {
+                    return Ok(Some(Choice::Preset(suggestion.next)));
+                },
+                (KeyCode::Char('x'), _) => if let Some(suggestion) = self.suggestion.take() {
+                    adaptive::dismiss(suggestion)?;
+                },
+                (KeyCode::Char('r'), _) => {
+                    self.resizing = Some(self.custom.mines as f64 / (self.custom.size.0 * self.custom.size.1) as f64);
+                },
+                (KeyCode::Esc | KeyCode::Char('q'), _) => return Ok(None),
+                _ => {},
+            }
+        }
+    }
+
+    /// Save the game set up in the menu under the name typed, unless it's the name of a standard preset, which
+    /// `--preset` would pick instead.
+    fn save(&mut self) -> IoResult<()> {
+        let Some(name) = self.naming.take().filter(|name| !name.trim().is_empty()) else { return Ok(()) };
+        let name = name.trim().to_string();
+        if Preset::parse(&name).is_some() {
+            self.status = Some(locale::tr_with("custom-name-taken", &[("name", &name)]));
+            return Ok(());
+        }
+        let mut config = Config::load()?;
+        config.presets.insert(name.clone(), self.custom);
+        config.save()?;
+        self.saved = config.presets;
+        self.status = Some(locale::tr_with("custom-saved", &[("name", &name)]));
+        Ok(())
+    }
+
+    fn render(&self) -> IoResult<()> {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+        let custom = &self.custom;
+        let toggle = |on: bool| locale::tr(if on { "custom-on" } else { "custom-off" }).to_string();
+        let settings = Setting::ALL.map(|setting| (setting.label(), match setting {
+            Setting::Rows => custom.size.0.to_string(),
+            Setting::Columns => custom.size.1.to_string(),
+            Setting::Mines => custom.mines.to_string(),
+            Setting::NoGuess => toggle(custom.no_guess),
+            Setting::Hardcore => toggle(custom.hardcore),
+            Setting::MovingMines => toggle(custom.moving_mines),
+        }));
+        let pointer = |i: usize| if i == self.selected { console::glyphs().pointer } else { ' ' };
+
+        let mut lines = vec![locale::tr("custom-title").to_string(), locale::tr("custom-help").to_string(), String::new()];
+        let (day, status) = self.daily;
+        lines.insert(2, locale::tr_with(
+            "daily-banner",
+            &[("date", &daily::date(day)), ("status", &status.describe()), ("next", &daily::countdown())],
+        ));
+        if let Some(suggestion) = self.suggestion {
+            lines.insert(2, suggestion.question("custom-suggestion"));
+        }
+        let settings = keymap::help_lines(&settings);
+        lines.extend(settings.iter().enumerate().map(|(i, line)| format!("{} {}", pointer(i), line)));
+        lines.push(String::new());
+        lines.push(match (&self.naming, &self.status) {
+            _ if self.resizing.is_some() => locale::tr("custom-resizing").to_string(),
+            (Some(name), _) => locale::tr_with("custom-save-as", &[("name", name)]),
+            (None, Some(status)) => status.clone(),
+            (None, None) => custom.problem().unwrap_or_else(|| {
+                let density = format!("{:.1}", custom.mines as f64 * 100.0 / (custom.size.0 * custom.size.1) as f64);
+                locale::tr_with("custom-density", &[("density", &density)])
+            }),
+        });
+        if self.resizing.is_some() {
+            lines.push(String::new());
+            // Whatever room is left on screen, below the menu and inside its box
+            let (width, _) = terminal::size()?;
+            let room = (height as usize).saturating_sub(lines.len() + 2).max(1);
+            lines.extend(preview(custom.size, (width.saturating_sub(2) as usize).max(1), room));
+        }
+        if !self.saved.is_empty() {
+            lines.push(String::new());
+            lines.push(locale::tr("custom-saved-games").to_string());
+            let saved = self.saved.iter().map(|(name, game)| (name.as_str(), game.summary())).collect::<Vec<_>>();
+            let saved = keymap::help_lines(&saved);
+            lines.extend(saved.iter().enumerate().map(|(i, line)| {
+                format!("{} {}", pointer(Setting::ALL.len() + i), line)
+            }));
+        }
+        BoxedComponent::new(&Text::new(lines)).render_at(&mut buffer);
+
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        out.flush()
+    }
+}
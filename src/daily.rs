@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Field;
+use crate::locale;
+use crate::paths;
+use crate::preset::Preset;
+use crate::stats;
+use crate::tui;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The daily challenge is played on a board of this preset.
+pub const PRESET: Preset = Preset::Intermediate;
+const DAY: u64 = 24 * 60 * 60;
+
+/// How the daily challenge went on a day.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Status {
+    /// Not started yet.
+    NotAttempted,
+    /// Started, but not finished (perhaps quit part way through).
+    InProgress,
+    /// Lost, which can't be tried again.
+    Lost,
+    /// Won, in the given time.
+    Won(Duration),
+}
+
+impl Status {
+    /// Returns true once the day's challenge has been played to the end, so it can't be played again.
+    pub(crate) fn finished(self) -> bool {
+        matches!(self, Status::Lost | Status::Won(_))
+    }
+
+    /// Returns true if this went better than the other: a finished game over one that isn't, a win over a loss, or a
+    /// faster win.
+    fn better_than(self, other: Status) -> bool {
+        match (self, other) {
+            (Status::Won(time), Status::Won(other)) => time < other,
+            (Status::Won(_), _) => true,
+            (Status::Lost, other) => !other.finished(),
+            _ => false,
+        }
+    }
+
+    /// How it went, as the banner and `termsweeper daily` say it, like `won in 1:05`.
+    pub fn describe(self) -> String {
+        match self {
+            Status::NotAttempted => locale::tr("daily-not-attempted").to_string(),
+            Status::InProgress => locale::tr("daily-in-progress").to_string(),
+            Status::Lost => locale::tr("daily-lost").to_string(),
+            Status::Won(time) => locale::tr_with("daily-won", &[("time", &tui::format_time(time))]),
+        }
+    }
+}
+
+/// The result of each day's challenge that's been started, by date, stored as TOML in the platform data directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Results {
+    /// Challenges played on their own day, which make up the streak.
+    #[serde(default)]
+    days: BTreeMap<String, DayResult>,
+    /// Past days' challenges played from the archive, kept apart so they can't fill in a streak after the fact.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    archive: BTreeMap<String, DayResult>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+struct DayResult {
+    /// None until the game is over.
+    won: Option<bool>,
+    /// How long the game took, once it's won.
+    time_ms: Option<u64>,
+}
+
+impl DayResult {
+    fn status(&self) -> Status {
+        match *self {
+            DayResult { won: None, .. } => Status::InProgress,
+            DayResult { won: Some(false), .. } => Status::Lost,
+            DayResult { won: Some(true), time_ms } => Status::Won(Duration::from_millis(time_ms.unwrap_or(0))),
+        }
+    }
+}
+
+impl Results {
+    fn path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("daily.toml"))
+    }
+
+    /// Load the results file. A missing file (or no known data directory) means no challenge has been started yet.
+    fn load() -> IoResult<Self> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn results(&mut self, archive: bool) -> &mut BTreeMap<String, DayResult> {
+        if archive { &mut self.archive } else { &mut self.days }
+    }
+
+    /// Set the day's result, and save the results file. An archive result only replaces one that was worse, since the
+    /// archive can be played again as often as wanted.
+    fn set(day: u64, archive: bool, result: DayResult) -> IoResult<()> {
+        let mut results = Self::load()?;
+        let old = results.results(archive).get(&date(day)).map(DayResult::status);
+        if archive && old.is_some_and(|old| old.better_than(result.status())) {
+            return Ok(());
+        }
+        results.results(archive).insert(date(day), result);
+        let Some(path) = Self::path() else { return Ok(()) };
+        let text = toml::to_string(&results).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        paths::write_safely(&path, text.as_bytes())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs())
+}
+
+/// Today, as days since the Unix epoch. Days start at midnight UTC, so everyone has the same board at once.
+pub fn today() -> u64 {
+    now() / DAY
+}
+
+/// How long until the next day's challenge, like `3:04:05`.
+pub(crate) fn countdown() -> String {
+    let left = DAY - now() % DAY;
+    format!("{}:{:02}:{:02}", left / 3600, left / 60 % 60, left % 60)
+}
+
+/// The day, like `2024-03-01`.
+pub fn date(day: u64) -> String {
+    stats::utc_date(day * DAY)[..10].to_string()
+}
+
+/// Parse a day given as a date like `2024-03-01`, for playing its challenge from the archive. Days that don't exist,
+/// and those still to come, are None.
+pub fn parse_day(date_text: &str) -> Option<u64> {
+    let day = stats::parse_date(date_text)? / DAY;
+    // Dates like 2024-02-31 parse as some other day
+    (date(day) == date_text && day <= today()).then_some(day)
+}
+
+/// The day's board, laid out from the day so it's the same for everyone, and no two days have the same board.
+pub fn field(day: u64) -> Field {
+    Field::with_rng(PRESET.size(), PRESET.mines(), &mut StdRng::seed_from_u64(seed(day)))
+        .expect("the daily challenge's preset fits its mines")
+}
+
+/// The seed the day's board is laid out from: an FNV-1a hash of its date, salted so it isn't the day's number, which
+/// would give the same board as `:seed` with it. FNV-1a is the same in every version, so old days keep their boards.
+fn seed(day: u64) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    format!("termsweeper daily {}", date(day))
+        .bytes()
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// How the day's challenge went, played on its day or (the best of the times it was played) from the archive.
+pub(crate) fn status(day: u64, archive: bool) -> IoResult<Status> {
+    Ok(Results::load()?.results(archive).get(&date(day)).map_or(Status::NotAttempted, DayResult::status))
+}
+
+/// How many days in a row the challenge has been won on its day, up to today. Today doesn't break the streak until
+/// it's over, so it only counts once won.
+pub(crate) fn streak() -> IoResult<usize> {
+    let days = Results::load()?.days;
+    let won = |day: u64| days.get(&date(day)).is_some_and(|result| result.won == Some(true));
+    let today = today();
+    let last = if won(today) { today } else { today.saturating_sub(1) };
+    Ok((0..=last).rev().take_while(|&day| won(day)).count())
+}
+
+/// Mark the day's challenge as started. Archive games aren't marked, as they can be played again anyway.
+pub(crate) fn start(day: u64) -> IoResult<()> {
+    Results::set(day, false, DayResult { won: None, time_ms: None })
+}
+
+/// Save how the day's challenge ended: won in the given time, or lost if there isn't one.
+pub(crate) fn finish(day: u64, archive: bool, won_in: Option<Duration>) -> IoResult<()> {
+    let result = DayResult { won: Some(won_in.is_some()), time_ms: won_in.map(|time| time.as_millis() as u64) };
+    Results::set(day, archive, result)
+}
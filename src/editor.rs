@@ -0,0 +1,173 @@
+use std::io::{stdout, Write};
+use std::path::PathBuf;
+use crossterm::{
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    cursor::MoveTo,
+};
+
+use crate::board_file;
+use crate::config::Config;
+use crate::console;
+use crate::game::{Direction, Field};
+use crate::keymap::{self, Action, Keymap};
+use crate::locale;
+use crate::tui::{BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The board editor, for laying out custom puzzles by hand and saving them as board files.
+pub struct Editor {
+    /// The board as laid out so far, with every cell hidden.
+    field: Field,
+    cursor: (usize, usize),
+    path: PathBuf,
+    keymap: Keymap,
+    /// The result of the last save, shown until the board changes.
+    status: Option<String>,
+}
+
+impl Editor {
+    /// The size of new boards, unless another is given.
+    pub const DEFAULT_SIZE: (usize, usize) = (9, 9);
+
+    /// Edit the board file at `path`, or start a new blank board of the given size if there isn't one yet. The
+    /// controls are the configured ones.
+    pub fn open(path: PathBuf, size: (usize, usize), config: &Config) -> IoResult<Self> {
+        let keymap = config.controls.keymap();
+        let field = if path.exists() {
+            board_file::parse(&std::fs::read_to_string(&path)?)?
+        } else {
+            // Neither dimension is zero and there are no mines, so this is always a valid board
+            Field::from_mines((size.0.max(1), size.1.max(1)), &[]).unwrap()
+        };
+        Ok(Self { field, cursor: (0, 0), path, keymap, status: None })
+    }
+
+    /// Take over the terminal and edit until the player quits.
+    pub fn run(mut self) -> IoResult<()> {
+        execute!(stdout(), EnterAlternateScreen)?;
+        terminal::enable_raw_mode()?;
+        let result = (|| {
+            self.render()?;
+            while self.handle_event(event::read()?)? {
+                self.render()?;
+            }
+            Ok(())
+        })();
+        terminal::disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        result
+    }
+
+    /// What would stop the board from being played, if anything.
+    fn problem(&self) -> Option<&'static str> {
+        let safe = self.field.board.iter().flatten().filter(|cell| !cell.is_mine() && !cell.is_void()).count();
+        if self.field.mine_count() == 0 {
+            Some(locale::tr("editor-no-mines"))
+        } else if safe == 0 {
+            Some(locale::tr("editor-no-safe-cells"))
+        } else {
+            None
+        }
+    }
+
+    /// React to a terminal event: the controls for revealing and flagging cells add and take away mines and voids,
+    /// and Ctrl+S saves the board. Returns false if the editor should exit.
+    pub fn handle_event(&mut self, event: Event) -> IoResult<bool> {
+        let Event::Key(key @ KeyEvent { code, modifiers, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event
+        else { return Ok(true) };
+
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('s') {
+            self.status = Some(match self.problem() {
+                Some(problem) => problem.to_string(),
+                None => {
+                    std::fs::write(&self.path, board_file::write(&self.field))?;
+                    locale::tr_with("editor-saved", &[("path", &self.path.display())])
+                },
+            });
+            return Ok(true);
+        }
+
+        let pos = self.cursor;
+        // The cursor is always on the board
+        let cell = self.field.cell(pos).unwrap();
+        let (mine, void) = (cell.is_mine(), cell.is_void());
+        match self.keymap.action(&key) {
+            Some(Action::Move(direction)) => self.move_cursor(direction),
+            Some(Action::Reveal) if mine => _ = self.field.remove_mine(pos),
+            Some(Action::Reveal) => {
+                // A void has to be made a safe cell before it can be a mine
+                self.field.set_void(pos, false);
+                self.field.add_mine(pos);
+            },
+            Some(Action::Flag) if void => _ = self.field.set_void(pos, false),
+            Some(Action::Flag) => {
+                self.field.remove_mine(pos);
+                self.field.set_void(pos, true);
+            },
+            Some(Action::Quit) => return Ok(false),
+            _ => return Ok(true),
+        }
+        self.status = None;
+        Ok(true)
+    }
+
+    fn move_cursor(&mut self, direction: Direction) {
+        let (rows, cols) = self.field.size();
+        if let Some(pos) = direction.offset(self.cursor).filter(|pos| pos.0 < rows && pos.1 < cols) {
+            self.cursor = pos;
+        }
+    }
+
+    fn render(&self) -> IoResult<()> {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+
+        let board = self.field.board.iter()
+            .map(|row| row.iter()
+                .map(|cell| match cell {
+                    cell if cell.is_void() => ' ',
+                    cell if cell.is_mine() => '*',
+                    _ => console::glyphs().safe,
+                })
+                .collect()
+            )
+            .collect();
+        let buf = BoxedComponent::new(&Text::new(board)).render_at(&mut buffer);
+
+        let field = &self.field;
+        let mut stats = vec![
+            (locale::tr("editor-size"), format!("{}x{}", field.size().0, field.size().1)),
+            (locale::tr("editor-mines"), field.mine_count().to_string()),
+        ];
+        if self.problem().is_none() {
+            stats.push((locale::tr("editor-3bv"), field.bbbv().to_string()));
+        }
+        let mut info = keymap::help_lines(&stats);
+        info.extend(self.problem().map(str::to_string));
+        info.extend(self.status.clone());
+        let buf = BoxedComponent::new(&Text::new(info)).render_at(buf);
+
+        let mut controls = self.keymap.help_with(|action| match action {
+            Action::Move(_) => Some(locale::tr("action-move")),
+            Action::Reveal => Some(locale::tr("editor-mine")),
+            Action::Flag => Some(locale::tr("editor-void")),
+            Action::Quit => Some(locale::tr("action-quit")),
+            _ => None,
+        });
+        controls.push((locale::tr("control-save"), "ctrl+s".to_string()));
+        BoxedComponent::new(&Text::new(keymap::help_lines(&controls))).render_at(buf);
+
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        execute!(out, MoveTo(self.cursor.1 as u16 + 1, self.cursor.0 as u16 + 1))?;
+        write!(out, "{}", console::glyphs().cursor)?;
+        out.flush()
+    }
+}
@@ -0,0 +1,65 @@
+use crossterm::event::KeyCode;
+
+use crate::locale;
+use crate::tui::Handled;
+
+/// What can be done once a game is over, from the end panel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum EndAction {
+    /// Play the same board again, from the start.
+    Retry,
+    /// Play a new board like this one.
+    NewBoard,
+    /// Mark the cells the player got wrong on the board.
+    Review,
+    /// Save a summary of the game as text, to paste somewhere.
+    Share,
+    /// End the game, going back to whatever started it.
+    Back,
+}
+
+impl EndAction {
+    pub(crate) fn label(self) -> &'static str {
+        locale::tr(match self {
+            EndAction::Retry => "end-retry",
+            EndAction::NewBoard => "end-new-board",
+            EndAction::Review => "end-review",
+            EndAction::Share => "end-share",
+            EndAction::Back => "end-back",
+        })
+    }
+}
+
+/// The panel shown under the title once a game is over: a summary of how it went, then the actions that can be taken
+/// on it, picked with the arrows and enter.
+pub(crate) struct EndMenu {
+    pub(crate) summary: Vec<String>,
+    pub(crate) actions: Vec<EndAction>,
+    pub(crate) selected: usize,
+    /// The action just picked, until the game carries it out.
+    picked: Option<EndAction>,
+}
+
+impl EndMenu {
+    pub(crate) fn new(summary: Vec<String>, actions: Vec<EndAction>) -> Self {
+        Self { summary, actions, selected: 0, picked: None }
+    }
+
+    /// Escape closes the panel, handing the arrows back to the cursor. Everything else but picking an action is left to
+    /// the game, so the rest of the controls still work under the panel.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> Handled {
+        match code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(self.actions.len() - 1),
+            KeyCode::Enter => self.picked = Some(self.actions[self.selected]),
+            KeyCode::Esc => return Handled::Closed,
+            _ => return Handled::Ignored,
+        }
+        Handled::Consumed
+    }
+
+    /// The action picked since the last time this was called, if one was.
+    pub(crate) fn take_picked(&mut self) -> Option<EndAction> {
+        self.picked.take()
+    }
+}
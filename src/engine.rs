@@ -0,0 +1,537 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Direction, Field};
+use crate::topology::Topology;
+use crate::versus::Versus;
+
+/// Something that happened in a game. Positions are (row, col).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum GameEvent {
+    /// A safe cell was revealed. A flood fill reveals many cells at once, each with its own event.
+    CellRevealed {
+        /// The cell, as (row, col).
+        pos: (usize, usize),
+        /// The number of adjacent mines shown on the cell.
+        number: u8,
+    },
+    /// A flag was placed on or removed from a hidden cell.
+    CellFlagged {
+        /// The cell, as (row, col).
+        pos: (usize, usize),
+        /// True if the flag was placed, false if it was removed.
+        flagged: bool,
+    },
+    /// A mine was revealed.
+    MineExploded {
+        /// The cell, as (row, col).
+        pos: (usize, usize),
+    },
+    /// Every safe cell was revealed in a single-player game.
+    GameWon,
+    /// A single-player game was lost: a mine was revealed (sent after the [`GameEvent::MineExploded`] for it), time
+    /// ran out in a [`Challenge::Countdown`], or the last click allowed by [`Constraints::max_clicks`] didn't clear the
+    /// board.
+    GameLost,
+    /// The time limit of a [`Challenge`] ran out. Sent before the [`GameEvent::GameLost`] in a countdown.
+    TimeUp,
+    /// A hidden mine moved somewhere else, in a game with moving mines. Followed by a [`GameEvent::NumberChanged`]
+    /// for each revealed cell whose number changed because of it.
+    MineMoved,
+    /// The number on a revealed cell changed, because a mine moved.
+    NumberChanged {
+        /// The cell, as (row, col).
+        pos: (usize, usize),
+        /// The number of adjacent mines now shown on the cell.
+        number: u8,
+    },
+    /// Time was added to the clock as a penalty, for flagging a safe cell in hardcore mode.
+    Penalty {
+        /// The cell, as (row, col).
+        pos: (usize, usize),
+        /// How much time was added.
+        #[serde(rename = "time_ms", serialize_with = "serialize_millis")]
+        time: Duration,
+    },
+}
+
+fn serialize_millis<S: serde::Serializer>(time: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(time.as_millis() as u64)
+}
+
+/// A time limit for a single-player game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Challenge {
+    /// Clear as much of the board as possible before time runs out. Running out of time ends the game without losing
+    /// it; the score is the number of safe cells revealed.
+    Sprint(Duration),
+    /// Clear the whole board before time runs out, or lose.
+    Countdown(Duration),
+}
+
+impl Challenge {
+    /// The usual time limit for a challenge.
+    pub const DEFAULT_LIMIT: Duration = Duration::from_secs(3 * 60);
+
+    /// The time limit.
+    pub fn limit(self) -> Duration {
+        match self {
+            Challenge::Sprint(limit) | Challenge::Countdown(limit) => limit,
+        }
+    }
+}
+
+/// Something a player does. Every change to a game goes through [`GameEngine::apply`] as one of these. Positions are
+/// (row, col).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Command {
+    /// Reveal a hidden cell, flood filling from it if it has no adjacent mines.
+    Reveal((usize, usize)),
+    /// Place or remove a flag on a hidden cell.
+    Flag((usize, usize)),
+    /// On a revealed number with as many flags around it as it shows, reveal all its other hidden neighbors.
+    Chord((usize, usize)),
+    /// Move the cursor one cell, stopping at the edges of the board.
+    Move(Direction),
+}
+
+/// Extra limits on how a single-player game may be played, on top of any [`Challenge`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Constraints {
+    /// Flags can't be placed at all.
+    pub no_flags: bool,
+    /// The board must be cleared within this many clicks (reveals and chords that reveal something), or the game is
+    /// lost.
+    pub max_clicks: Option<usize>,
+}
+
+/// The state of the moving mines variant.
+#[derive(Debug)]
+pub(crate) struct MovingMines {
+    interval: Duration,
+    /// When the next mine moves, on the game clock.
+    next: Duration,
+    /// Picks which mine moves where. It's seeded, so the game stays deterministic.
+    rng: StdRng,
+}
+
+impl MovingMines {
+    pub(crate) fn new(interval: Duration, seed: u64) -> Self {
+        Self { interval, next: interval, rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+/// The state of the noisy numbers variant.
+#[derive(Debug)]
+pub(crate) struct NoisyNumbers {
+    /// How far off a number can be shown: each range covers this many numbers more than the real one.
+    pub(crate) noise: u8,
+    /// What the ranges were picked from, kept so a replay can pick the same ones.
+    pub(crate) seed: u64,
+    rng: StdRng,
+}
+
+impl NoisyNumbers {
+    pub(crate) fn new(noise: u8, seed: u64) -> Self {
+        Self { noise, seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// Pick the range to show a number as, out of those `noise` wide that hold it, as (low, high). Ranges stop at 1,
+    /// since a 0 would have flood filled, and at the most mines the cell could have around it.
+    fn range(&mut self, number: u8, most: u8) -> (u8, u8) {
+        let lowest = number.saturating_sub(self.noise).max(1);
+        let low = self.rng.gen_range(lowest..=number.min(most.saturating_sub(self.noise).max(lowest)));
+        (low, (low + self.noise).min(most))
+    }
+}
+
+/// The rules of a game, on top of a [`Field`]: whose turn it is, and when the game is over. Anything that happens is
+/// sent to every subscriber as a [`GameEvent`].
+///
+/// The engine does no IO and never reads the clock, so the same field, commands and [`GameEngine::advance`] calls
+/// always play out the same way.
+#[derive(Debug)]
+pub struct GameEngine {
+    pub(crate) field: Field,
+    pub(crate) versus: Option<Versus>,
+    /// The cell the player is pointing at, as (row, col).
+    pub(crate) cursor: (usize, usize),
+    pub(crate) challenge: Option<Challenge>,
+    /// Time added to the clock for each flag placed on a safe cell, in hardcore mode.
+    pub(crate) flag_penalty: Option<Duration>,
+    pub(crate) moving_mines: Option<MovingMines>,
+    pub(crate) noisy_numbers: Option<NoisyNumbers>,
+    pub(crate) constraints: Constraints,
+    /// Whether the cursor wraps around to the opposite edge when moved past one.
+    pub(crate) wrap_cursor: bool,
+    /// Reveals and chords that revealed something, counted against [`Constraints::max_clicks`].
+    clicks: usize,
+    /// Time added to the clock as penalties so far, included in `elapsed`.
+    penalties: Duration,
+    /// Set by the first reveal, which starts the game clock.
+    started: bool,
+    elapsed: Duration,
+    ended: bool,
+    won: bool,
+    subscribers: Vec<Sender<GameEvent>>,
+}
+
+impl GameEngine {
+    /// A single-player game on the given field.
+    pub fn new(field: Field) -> Self {
+        Self {
+            field,
+            versus: None,
+            cursor: (0, 0),
+            challenge: None,
+            flag_penalty: None,
+            moving_mines: None,
+            noisy_numbers: None,
+            constraints: Constraints::default(),
+            wrap_cursor: false,
+            clicks: 0,
+            penalties: Duration::ZERO,
+            started: false,
+            elapsed: Duration::ZERO,
+            ended: false,
+            won: false,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Play against a time limit.
+    pub fn with_challenge(mut self, challenge: Challenge) -> Self {
+        self.challenge = Some(challenge);
+        self
+    }
+
+    /// Play in hardcore mode: each flag placed on a safe cell while the clock is running adds the given time to it.
+    pub fn with_flag_penalty(mut self, penalty: Duration) -> Self {
+        self.flag_penalty = Some(penalty);
+        self
+    }
+
+    /// Play with moving mines: every `interval` on the game clock, a hidden mine moves to a hidden cell that isn't next
+    /// to where it was. The seed decides which mines move where.
+    pub fn with_moving_mines(mut self, interval: Duration, seed: u64) -> Self {
+        self.moving_mines = Some(MovingMines::new(interval, seed));
+        self
+    }
+
+    /// Play with noisy numbers: each revealed number is shown as a range (see [`Field::shown_range`]) `noise` wider
+    /// than it, somewhere around the real number. The seed decides where.
+    pub fn with_noisy_numbers(mut self, noise: u8, seed: u64) -> Self {
+        self.set_noisy_numbers(noise, seed);
+        self
+    }
+
+    pub(crate) fn set_noisy_numbers(&mut self, noise: u8, seed: u64) {
+        self.noisy_numbers = Some(NoisyNumbers::new(noise, seed));
+        self.field.ranges.get_or_insert_with(BTreeMap::new);
+    }
+
+    /// Play under the given constraints.
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Wrap the cursor around to the opposite edge of the board when it's moved past one, rather than stopping it.
+    pub fn with_wrapping_cursor(mut self) -> Self {
+        self.wrap_cursor = true;
+        self
+    }
+
+    /// A new game on the given field by the same rules as this one, with any randomness in them (like where mines
+    /// move) picked from the seed.
+    pub(crate) fn rematch(&self, field: Field, seed: u64) -> Self {
+        let mut engine = Self::new(field);
+        engine.challenge = self.challenge;
+        engine.flag_penalty = self.flag_penalty;
+        engine.moving_mines = self.moving_mines.as_ref().map(|moving| MovingMines::new(moving.interval, seed));
+        if let Some(noisy) = &self.noisy_numbers {
+            engine.set_noisy_numbers(noisy.noise, seed);
+        }
+        engine.constraints = self.constraints;
+        engine.wrap_cursor = self.wrap_cursor;
+        engine
+    }
+
+    /// Get every event from now on. Events queue up in the receiver until they're read; dropping it unsubscribes.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.subscribers.retain(|subscriber| subscriber.send(event).is_ok());
+    }
+
+    /// The board being played.
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    /// The cell the player is pointing at, as (row, col).
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Move the cursor straight to a cell, as (row, col). Returns false, leaving it where it was, if the cell is off
+    /// the board.
+    pub fn put_cursor(&mut self, pos: (usize, usize)) -> bool {
+        if self.field.cell(pos).is_none() {
+            return false;
+        }
+        self.cursor = pos;
+        true
+    }
+
+    /// Returns true between the first reveal and the end of the game, while the game clock is running.
+    pub fn running(&self) -> bool {
+        self.started && !self.ended
+    }
+
+    /// Time on the game clock, which only counts while the game is [running](GameEngine::running), plus any
+    /// penalties.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Move the game clock forward by the given amount of time. This can end a [`Challenge`].
+    pub fn advance(&mut self, by: Duration) {
+        if !self.running() {
+            return;
+        }
+        self.add_time(by);
+    }
+
+    /// Time added to the clock as penalties so far. It's included in [`GameEngine::elapsed`].
+    pub fn penalties(&self) -> Duration {
+        self.penalties
+    }
+
+    fn add_time(&mut self, by: Duration) {
+        self.elapsed += by;
+        while self.moving_mines.as_ref().is_some_and(|moving| self.elapsed >= moving.next) {
+            self.move_mine();
+        }
+        let Some(challenge) = self.challenge else { return };
+        if self.elapsed >= challenge.limit() {
+            self.elapsed = challenge.limit();
+            self.ended = true;
+            self.emit(GameEvent::TimeUp);
+            if let Challenge::Countdown(_) = challenge {
+                self.emit(GameEvent::GameLost);
+            }
+        }
+    }
+
+    /// The time limit being played against, if any.
+    pub fn challenge(&self) -> Option<Challenge> {
+        self.challenge
+    }
+
+    /// Time left on the clock in a [`Challenge`].
+    pub fn remaining(&self) -> Option<Duration> {
+        Some(self.challenge?.limit().saturating_sub(self.elapsed))
+    }
+
+    /// Returns true if a [`Challenge`] ended because time ran out.
+    pub fn time_up(&self) -> bool {
+        self.remaining() == Some(Duration::ZERO)
+    }
+
+    /// How much of the board has been cleared so far, from 0 to 1. Voids aren't part of the board, so they don't count.
+    pub fn progress(&self) -> f64 {
+        let (cleared, safe) = self.field.board.iter()
+            .flatten()
+            .filter(|cell| !cell.is_mine() && !cell.is_void())
+            .fold((0, 0), |(cleared, safe), cell| (cleared + usize::from(!cell.is_hidden()), safe + 1));
+        if safe == 0 { 1.0 } else { cleared as f64 / safe as f64 }
+    }
+
+    /// The number of safe cells revealed so far: the score in a [`Challenge::Sprint`].
+    pub fn cleared(&self) -> usize {
+        self.field.board.iter()
+            .flatten()
+            .filter(|cell| !cell.is_mine() && !cell.is_hidden())
+            .count()
+    }
+
+    /// The constraints being played under.
+    pub fn constraints(&self) -> Constraints {
+        self.constraints
+    }
+
+    /// The number of clicks so far: reveals and chords that revealed something.
+    pub fn clicks(&self) -> usize {
+        self.clicks
+    }
+
+    /// Returns true once the game is over, won or lost.
+    pub fn ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Returns true if a single-player game ended with the board cleared.
+    pub fn won(&self) -> bool {
+        self.won
+    }
+
+    /// Carry out a command for whoever's turn it is. Returns false if it did nothing: the game is over, or the command
+    /// doesn't apply to the cell it was given.
+    pub fn apply(&mut self, command: Command) -> bool {
+        match command {
+            Command::Move(direction) => self.move_cursor(direction),
+            _ if self.ended => false,
+            Command::Reveal(pos) => self.reveal(&[pos]),
+            Command::Flag(_) if self.constraints.no_flags => false,
+            Command::Flag(pos) => self.toggle_flag(pos),
+            Command::Chord(pos) => match self.chord_targets(pos) {
+                Some(targets) => self.reveal(&targets),
+                None => false,
+            },
+        }
+    }
+
+    fn move_cursor(&mut self, direction: Direction) -> bool {
+        let step = if self.wrap_cursor { Topology::wrapping_step } else { Topology::step };
+        match step(self.field.topology, self.field.size(), self.cursor, direction) {
+            Some(pos) => {
+                self.cursor = pos;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// The hidden, unflagged neighbors of a revealed number, if it has as many flags around it as it shows.
+    pub(crate) fn chord_targets(&self, pos: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        self.chord_preview(pos).filter(|(_, ready)| *ready).map(|(targets, _)| targets)
+    }
+
+    /// The hidden, unflagged neighbors of a revealed number, and whether it has as many flags around it as it shows,
+    /// so that chording it would reveal them.
+    pub(crate) fn chord_preview(&self, pos: (usize, usize)) -> Option<(Vec<(usize, usize)>, bool)> {
+        let number = self.field.cell(pos)?.shown_number()?;
+        let flags = self.field.neighbors(pos)
+            .filter(|&(row, col)| self.field.board[row][col].is_flagged())
+            .count();
+        let targets = self.field.neighbors(pos)
+            .filter(|&(row, col)| self.field.board[row][col].is_hidden() && !self.field.board[row][col].is_flagged())
+            .collect();
+        Some((targets, flags == number as usize))
+    }
+
+    /// Move a random unflagged hidden mine to a random unflagged hidden cell that isn't next to it.
+    fn move_mine(&mut self) {
+        let Some(moving) = &mut self.moving_mines else { return };
+        moving.next += moving.interval;
+
+        let (rows, cols) = self.field.size();
+        let hidden = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.field.board[row][col].is_hidden() && !self.field.board[row][col].is_flagged())
+            .collect::<Vec<_>>();
+        let mines = hidden.iter()
+            .filter(|&&(row, col)| self.field.board[row][col].is_mine())
+            .collect::<Vec<_>>();
+        let Some(&&from) = mines.choose(&mut moving.rng) else { return };
+        let destinations = hidden.iter()
+            .filter(|&&(row, col)| !self.field.board[row][col].is_mine())
+            .filter(|&&pos| pos != from && !self.field.neighbors(from).any(|neighbor| neighbor == pos))
+            .collect::<Vec<_>>();
+        let Some(&&to) = destinations.choose(&mut moving.rng) else { return };
+
+        let Some(changed) = self.field.move_mine(from, to) else { return };
+        self.emit(GameEvent::MineMoved);
+        for pos in changed {
+            let number = self.field.board[pos.0][pos.1].neighbor_count();
+            self.pick_range(pos);
+            self.emit(GameEvent::NumberChanged { pos, number });
+        }
+    }
+
+    /// With noisy numbers, pick the range a revealed number is shown as. Called whenever the number is revealed or
+    /// changes, so the ranges are picked in the same order every time the game is played the same way.
+    fn pick_range(&mut self, pos: (usize, usize)) {
+        let Some(noisy) = &mut self.noisy_numbers else { return };
+        let number = self.field.board[pos.0][pos.1].neighbor_count();
+        // A cell a moving mine left with no mines around it couldn't be anything else
+        let range = if number == 0 { (0, 0) } else { noisy.range(number, self.field.neighbors(pos).count() as u8) };
+        self.field.set_range(pos, range);
+    }
+
+    /// Reveal the given cells as a single move, then work out whose turn it is and whether the game is over.
+    fn reveal(&mut self, targets: &[(usize, usize)]) -> bool {
+        let mut safe = 0;
+        let mut exploded = None;
+        for &pos in targets {
+            let Some(reveal) = self.field.clear_cell(pos) else { continue };
+            if reveal.exploded {
+                exploded.get_or_insert(pos);
+                self.emit(GameEvent::MineExploded { pos });
+            } else {
+                safe += reveal.cells.len();
+                for &pos in &reveal.cells {
+                    let number = self.field.board[pos.0][pos.1].neighbor_count();
+                    self.pick_range(pos);
+                    self.emit(GameEvent::CellRevealed { pos, number });
+                }
+            }
+        }
+        if safe == 0 && exploded.is_none() {
+            return false;
+        }
+        self.started = true;
+        self.clicks += 1;
+        let out_of_clicks = self.constraints.max_clicks.is_some_and(|max| self.clicks >= max);
+
+        match &mut self.versus {
+            Some(versus) => {
+                let pos = exploded.unwrap_or(targets[0]);
+                versus.record_reveal((pos.1 as u16, pos.0 as u16), safe, exploded.is_some());
+                self.ended = versus.decided() || self.field.is_cleared();
+            },
+            None => {
+                self.won = exploded.is_none() && self.field.is_cleared();
+                self.ended = exploded.is_some() || self.won || out_of_clicks;
+                if self.won {
+                    self.emit(GameEvent::GameWon);
+                } else if self.ended {
+                    self.emit(GameEvent::GameLost);
+                }
+            },
+        }
+        true
+    }
+
+    fn toggle_flag(&mut self, pos: (usize, usize)) -> bool {
+        if self.field.toggle_flag(pos) != Some(true) {
+            return false;
+        }
+        let cell = self.field.board[pos.0][pos.1];
+        self.emit(GameEvent::CellFlagged { pos, flagged: cell.is_flagged() });
+        // Penalties count against the clock, so there are none before it starts
+        let penalized = cell.is_flagged() && !cell.is_mine() && self.running();
+        if let Some(time) = self.flag_penalty.filter(|_| penalized) {
+            self.penalties += time;
+            self.emit(GameEvent::Penalty { pos, time });
+            self.add_time(time);
+        }
+        true
+    }
+}
@@ -0,0 +1,318 @@
+use crate::game::{Cell, Field};
+use crate::topology::Topology;
+
+/// How big each cell is drawn, in pixels.
+const TILE: usize = 24;
+/// The smallest tile pictures are drawn with, as (width, height) in pixels: room for a number and a pixel around it.
+#[cfg(any(feature = "png", feature = "graphics"))]
+pub(crate) const MIN_TILE: (usize, usize) = (5, 7);
+
+/// The colors pictures are drawn in, as RGB.
+const BACKGROUND: [u8; 3] = [0x40, 0x40, 0x40];
+const HIDDEN: [u8; 3] = [0x9e, 0x9e, 0x9e];
+const REVEALED: [u8; 3] = [0xdd, 0xdd, 0xdd];
+const EXPLODED: [u8; 3] = [0xe0, 0x30, 0x30];
+const FLAG: [u8; 3] = [0xd0, 0x20, 0x20];
+const MINE: [u8; 3] = [0x20, 0x20, 0x20];
+/// The outline around the cursor's cell, when there is one.
+#[cfg(any(feature = "png", feature = "graphics"))]
+const CURSOR: [u8; 3] = [0xf0, 0xc0, 0x00];
+/// The classic colors for each number, with 9 starting them over.
+const NUMBERS: [[u8; 3]; 8] = [
+    [0x00, 0x00, 0xff],
+    [0x00, 0x80, 0x00],
+    [0xff, 0x00, 0x00],
+    [0x00, 0x00, 0x80],
+    [0x80, 0x00, 0x00],
+    [0x00, 0x80, 0x80],
+    [0x00, 0x00, 0x00],
+    [0x80, 0x80, 0x80],
+];
+/// Counts past 9, shown as letters, get a color of their own instead.
+const HIGH_NUMBER: [u8; 3] = [0x80, 0x00, 0x80];
+
+/// What a cell looks like in a picture.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Tile {
+    Hidden,
+    Flagged,
+    Revealed(u8),
+    Exploded,
+    Void,
+}
+
+impl Tile {
+    fn of(cell: &Cell) -> Self {
+        match cell {
+            cell if cell.is_void() => Tile::Void,
+            cell if cell.is_flagged() => Tile::Flagged,
+            cell if cell.is_hidden() => Tile::Hidden,
+            cell if cell.is_mine() => Tile::Exploded,
+            cell => Tile::Revealed(cell.neighbor_count()),
+        }
+    }
+
+    fn background(self) -> Option<[u8; 3]> {
+        match self {
+            Tile::Hidden | Tile::Flagged => Some(HIDDEN),
+            Tile::Revealed(_) => Some(REVEALED),
+            Tile::Exploded => Some(EXPLODED),
+            Tile::Void => None,
+        }
+    }
+}
+
+/// The outline a cell is drawn in, which follows the board's topology.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Shape {
+    Square,
+    /// A triangle pointing up, or down, filling its tile.
+    Up,
+    Down,
+}
+
+impl Shape {
+    fn of(topology: Topology, pos: (usize, usize)) -> Self {
+        match topology {
+            Topology::Triangular if Topology::points_up(pos) => Shape::Up,
+            Topology::Triangular => Shape::Down,
+            Topology::Square | Topology::Layered { .. } => Shape::Square,
+        }
+    }
+
+    /// How far down a tile of the given height the middle of the shape is, where what's on the cell goes.
+    fn middle(self, height: usize) -> usize {
+        match self {
+            Shape::Square => height / 2,
+            Shape::Up => height * 2 / 3,
+            Shape::Down => height / 3,
+        }
+    }
+}
+
+/// Every cell's tile and shape, with where it's drawn as (x, y) in cells from the top left, laid out as in the
+/// terminal.
+fn tiles(field: &Field) -> impl Iterator<Item = ((usize, usize), Tile, Shape)> + '_ {
+    field.board.iter()
+        .enumerate()
+        .flat_map(|(row, cells)| cells.iter().enumerate().map(move |(col, cell)| ((row, col), cell)))
+        .map(|(pos, cell)| {
+            // In cells rather than columns, as noisy numbers draw cells several columns wide
+            (field.topology().screen_pos(field.size(), pos), Tile::of(cell), Shape::of(field.topology(), pos))
+        })
+}
+
+/// The size of the picture of a board, in pixels, with each cell drawn as a tile of the given size.
+fn picture_size(field: &Field, (tile_width, tile_height): (usize, usize)) -> (usize, usize) {
+    let (width, height) = field.topology().screen_size(field.size());
+    (width * tile_width, height * tile_height)
+}
+
+fn number_color(number: u8) -> [u8; 3] {
+    match number {
+        10.. => HIGH_NUMBER,
+        _ => NUMBERS[(number as usize - 1) % NUMBERS.len()],
+    }
+}
+
+fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Draw the board as it stands as an SVG image.
+pub fn svg(field: &Field) -> String {
+    let (width, height) = picture_size(field, (TILE, TILE));
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n\
+        <rect width=\"{0}\" height=\"{1}\" fill=\"{2}\"/>\n",
+        width,
+        height,
+        hex(BACKGROUND),
+    );
+    for ((x, y), tile, shape) in tiles(field) {
+        let (left, top) = (x * TILE, y * TILE);
+        let (center_x, center_y) = (left + TILE / 2, top + TILE / 2);
+        let middle = top + shape.middle(TILE);
+        let (right, bottom) = (left + TILE - 1, top + TILE - 1);
+        let Some(background) = tile.background() else { continue };
+        svg += &match shape {
+            Shape::Square => format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"{3}\"/>\n",
+                left + 1,
+                top + 1,
+                TILE - 2,
+                hex(background),
+            ),
+            Shape::Up => format!(
+                "<polygon points=\"{},{} {},{} {},{}\" fill=\"{}\"/>\n",
+                left + 1,
+                bottom,
+                center_x,
+                top + 1,
+                right,
+                bottom,
+                hex(background),
+            ),
+            Shape::Down => format!(
+                "<polygon points=\"{},{} {},{} {},{}\" fill=\"{}\"/>\n",
+                left + 1,
+                top + 1,
+                right,
+                top + 1,
+                center_x,
+                bottom,
+                hex(background),
+            ),
+        };
+        match tile {
+            Tile::Revealed(0) | Tile::Hidden | Tile::Void => {},
+            Tile::Revealed(number) => svg += &format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-family=\"monospace\" font-size=\"{}\" \
+                font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                center_x,
+                middle,
+                hex(number_color(number)),
+                TILE * 2 / 3,
+                Cell::number_char(number),
+            ),
+            Tile::Flagged => svg += &format!(
+                "<polygon points=\"{},{} {},{} {},{}\" fill=\"{}\"/>\n",
+                center_x - TILE / 4,
+                top + TILE / 4,
+                center_x + TILE / 4,
+                center_y,
+                center_x - TILE / 4,
+                top + TILE * 3 / 4,
+                hex(FLAG),
+            ),
+            Tile::Exploded => svg += &format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                center_x,
+                middle,
+                TILE / 4,
+                hex(MINE),
+            ),
+        }
+    }
+    svg + "</svg>\n"
+}
+
+/// A tiny pixel font for the numbers on a board, as 3x5 bitmaps with a row per byte (the low 3 bits, left to right).
+/// Boards with more than 9 neighbors per cell carry on into letters, as in the terminal.
+#[cfg(any(feature = "png", feature = "graphics"))]
+const GLYPHS: [[u8; 5]; 27] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b010, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b110, 0b100, 0b111],
+    [0b111, 0b100, 0b110, 0b100, 0b100],
+    [0b011, 0b100, 0b101, 0b101, 0b011],
+    [0b101, 0b101, 0b111, 0b101, 0b101],
+    [0b111, 0b010, 0b010, 0b010, 0b111],
+    [0b001, 0b001, 0b001, 0b101, 0b010],
+    [0b101, 0b101, 0b110, 0b101, 0b101],
+    [0b100, 0b100, 0b100, 0b100, 0b111],
+    [0b101, 0b111, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b101, 0b101, 0b101],
+    [0b010, 0b101, 0b101, 0b101, 0b010],
+    [0b110, 0b101, 0b110, 0b100, 0b100],
+    [0b010, 0b101, 0b101, 0b110, 0b011],
+];
+
+/// Draw the board as it stands as RGB pixels, a row at a time, with each cell a tile of the given size in pixels (or
+/// bigger, for tiles with no room for a number) and an outline around the cursor's cell if it's given. Returns the
+/// picture's width and height, and its pixels.
+#[cfg(any(feature = "png", feature = "graphics"))]
+pub fn pixels(field: &Field, (tile_width, tile_height): (usize, usize), cursor: Option<(usize, usize)>)
+    -> (usize, usize, Vec<u8>)
+{
+    let (tile_width, tile_height) = (tile_width.max(MIN_TILE.0), tile_height.max(MIN_TILE.1));
+    let (width, height) = picture_size(field, (tile_width, tile_height));
+    let mut pixels = BACKGROUND.repeat(width * height);
+    let mut fill = |x: usize, y: usize, w: usize, h: usize, color: [u8; 3]| {
+        for row in y..y + h {
+            for col in x..x + w {
+                let i = (row * width + col) * 3;
+                pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    };
+
+    // Glyphs are drawn as big as fits with a margin, which is 3 pixels per dot (so 9x15) in 24 pixel tiles
+    let dot = ((tile_width - 2) / 4).min((tile_height - 2) / 6).max(1);
+    for ((x, y), tile, shape) in tiles(field) {
+        let (left, top) = (x * tile_width, y * tile_height);
+        let Some(background) = tile.background() else { continue };
+        let (inner_width, inner_height) = (tile_width - 2, tile_height - 2);
+        match shape {
+            Shape::Square => fill(left + 1, top + 1, inner_width, inner_height, background),
+            // A row at a time, each as wide as the triangle is that far down (or up) it
+            Shape::Up | Shape::Down => for dy in 0..inner_height {
+                let from_point = if shape == Shape::Up { dy } else { inner_height - 1 - dy };
+                let span = (inner_width * (from_point + 1) / inner_height).max(1);
+                fill(left + 1 + (inner_width - span) / 2, top + 1 + dy, span, 1, background);
+            },
+        }
+        match tile {
+            Tile::Revealed(0) | Tile::Hidden | Tile::Void => {},
+            Tile::Revealed(number) => {
+                let color = number_color(number);
+                let glyph = GLYPHS[(number as usize).min(GLYPHS.len() - 1)];
+                let glyph_left = left + tile_width.saturating_sub(3 * dot) / 2;
+                let glyph_top = top + (2 * shape.middle(tile_height)).saturating_sub(5 * dot) / 2;
+                for (dy, bits) in glyph.iter().enumerate() {
+                    for dx in (0..3).filter(|dx| bits & (0b100 >> dx) != 0) {
+                        fill(glyph_left + dx * dot, glyph_top + dy * dot, dot, dot, color);
+                    }
+                }
+            },
+            // A pennant, narrowing to a point on the right
+            Tile::Flagged => {
+                for dy in 0..tile_height / 2 {
+                    let reach = tile_height / 4 - (dy as isize - tile_height as isize / 4).unsigned_abs();
+                    let reach = (reach * tile_width / tile_height).max(1);
+                    fill(left + tile_width / 4, top + tile_height / 4 + dy, reach * 2, 1, FLAG);
+                }
+            },
+            Tile::Exploded => {
+                let mine_top = top + shape.middle(tile_height) - tile_height / 6;
+                fill(left + tile_width / 3, mine_top, tile_width / 3, tile_height / 3, MINE);
+            },
+        }
+    }
+    if let Some(pos) = cursor {
+        let (x, y) = field.topology().screen_pos(field.size(), pos);
+        let (left, top) = (x * tile_width, y * tile_height);
+        let thickness = (tile_width / 8).max(1);
+        fill(left, top, tile_width, thickness, CURSOR);
+        fill(left, top + tile_height - thickness, tile_width, thickness, CURSOR);
+        fill(left, top, thickness, tile_height, CURSOR);
+        fill(left + tile_width - thickness, top, thickness, tile_height, CURSOR);
+    }
+    (width, height, pixels)
+}
+
+/// Draw the board as it stands as a PNG image.
+#[cfg(feature = "png")]
+pub fn png(field: &Field) -> std::io::Result<Vec<u8>> {
+    let (width, height, pixels) = pixels(field, (TILE, TILE), None);
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let invalid = |e| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    encoder.write_header().map_err(invalid)?.write_image_data(&pixels).map_err(invalid)?;
+    Ok(png)
+}
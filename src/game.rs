@@ -1,111 +1,1850 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::mpsc::Receiver;
 use std::fmt::{Display, Formatter};
 use std::io::{stdout, stderr, Write};
-use rand::{Rng, thread_rng};
-use rand::distributions::Uniform;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use rand::{Rng, SeedableRng, thread_rng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 use crossterm::{
-    execute,
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
-    event::{Event, KeyEvent, KeyEventKind, KeyCode},
-    cursor::{MoveTo},
+    execute, queue,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
+    event::{Event, KeyEvent, KeyEventKind, KeyCode, KeyModifiers, EnableBracketedPaste, DisableBracketedPaste,
+        EnableFocusChange, DisableFocusChange},
+    cursor::{Hide, MoveTo, Show},
+    style::{Color, SetBackgroundColor, SetForegroundColor, ResetColor},
 };
 
-use crate::tui::{Component, BoxedComponent, Controls, Title};
+use crate::board_file;
+use crate::bookmark::Bookmarks;
+use crate::command_line::{self, Line};
+use crate::config::{AssistConfig, Banner, Config};
+use crate::engine::{Challenge, Command, Constraints, GameEngine, GameEvent, MovingMines};
+use crate::generator::{self, BoardGenerator};
+#[cfg(feature = "graphics")]
+use crate::graphics::Protocol;
+use crate::keymap::{self, Action, Keymap};
+use crate::repeat::KeyRepeat;
+use crate::ruler;
+use crate::timer::Timer;
+use crate::timing;
+use crate::settings::Settings;
+use crate::tui::{
+    self, format_time, Area, Component, BoxedComponent, Confirm, Controls, Frontier, Handled, Layers, Paused,
+    ProgressBar, Prompt, Scroll, Text, Title, TitleFonts,
+};
+use crate::versus::Versus;
+use crate::ai;
+use crate::boss::BossScreen;
+use crate::bot::{Bot, BotRole};
+use crate::console;
+use crate::custom::CustomGame;
+use crate::daily;
+use crate::end_menu::{EndAction, EndMenu};
+use crate::export;
+use crate::keyboard;
+use crate::locale;
+use crate::achievements;
+use crate::pack::{Progress, PuzzleId};
+use crate::palette::ColorDepth;
+use crate::preset::Preset;
+use crate::replay::Replay;
+use crate::solver;
+use crate::stats::{DailyRun, GameRecord, Stats};
+use crate::topology::Topology;
+#[cfg(feature = "hot-reload")]
+use crate::watch::ConfigWatcher;
 
 type IoResult<T> = std::io::Result<T>;
 
+/// How much is drawn around the boards, as the terminal gets narrower. See [`Game::layout`].
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Layout {
+    /// The clock and the controls, each in a panel of its own.
+    Full,
+    /// A status bar in place of the clock and the controls.
+    Compact,
+    /// Only the boards, with a count of what's been left out.
+    Minimal,
+}
+
+/// The components that can be shown over the game, and take its input while they are.
+#[derive(Copy, Clone)]
+enum Overlay {
+    Boss,
+    Confirm,
+    Prompt,
+    Help,
+    Stats,
+    Settings,
+    End,
+}
+
+/// A game of termsweeper: a field plus everything around it, from the cursor to versus mode, drawn in the terminal.
 //#[derive(Debug)]
-pub(crate) struct Game {
-    pub(crate) field: Field,
-    field_loc: (u16, u16),
-    cursor: (u16, u16),
+pub struct Game {
+    /// The boards being played side by side; only multi-board games have more than one.
+    boards: Vec<Board>,
+    /// The board that actions apply to.
+    focus: usize,
     terminal_size: (u16, u16),
-    game_ended: bool,
     closed: bool,
+    player_color: Color,
+    pings: Vec<Ping>,
+    /// The cells the last move changed, while their highlight fades.
+    changes: Vec<Change>,
+    last_move: Instant,
+    preset: Option<Preset>,
+    /// The level the board was picked at, in adaptive mode.
+    adaptive_level: Option<f64>,
+    /// The puzzle being played and its name, if it's from a puzzle pack.
+    puzzle: Option<(PuzzleId, String)>,
+    /// Which rung of the time trial ladder the game is, counting from 0, and the time taken on the rungs before it.
+    ladder: Option<(usize, Duration)>,
+    /// The day whose daily challenge the game is, as days since the Unix epoch, and whether it's played from the
+    /// archive.
+    daily: Option<(u64, bool)>,
+    timer: Timer,
+    clicks: usize,
+    flags: usize,
+    /// Where the mine that lost the game was, as (row, col).
+    death: Option<(usize, usize)>,
+    toasts: Vec<Toast>,
+    config: Config,
+    keymap: Keymap,
+    /// The fonts from the config, loaded once rather than every frame.
+    title_fonts: TitleFonts,
+    settings: Option<Settings>,
+    /// The help screen, shown in place of the game while it's open.
+    help: Option<Scroll>,
+    /// The stats, shown over the game after the `stats` command.
+    stats: Option<Scroll>,
+    /// The `:` prompt, while a command is being typed.
+    prompt: Option<Prompt>,
+    key_repeat: KeyRepeat,
+    enhanced_keyboard: bool,
+    /// The lines written in the last frame, for only writing what changed.
+    last_frame: Vec<String>,
+    /// Screen rows that had something drawn over them after the last frame's lines were written.
+    overlay_rows: Vec<u16>,
+    /// What the overlay (like a question or the help screen) covered in the last frame, if there was one.
+    covered: Option<Area>,
+    /// What's been drawn of the next frame, until it's written to `out` all at once.
+    frame: Vec<u8>,
+    /// Where frames are written: stdout, unless the game is headless.
+    out: Box<dyn Write>,
+    /// The replay being recorded, and where it's saved once the game ends.
+    recording: Option<(PathBuf, Replay)>,
+    /// Time spent playing (not paused) since the game started, which moves are recorded against.
+    played: Duration,
+    /// Set while playing back a replay, so the result isn't saved again.
+    playback: bool,
+    /// Where every game event is streamed as a line of JSON, if anywhere.
+    json_events: Option<Box<dyn Write>>,
+    /// The external engine attached to the game, until it fails.
+    bot: Option<Bot>,
+    /// Highlight the frontier and dim everything else.
+    show_frontier: bool,
+    /// A move that the numbers say is a mistake, waiting for the player to confirm it.
+    confirm: Option<Confirm<Command>>,
+    /// How many colors the terminal has, for the colors that come from the config.
+    color_depth: ColorDepth,
+    /// Watches the config file, to pick up changes to it during the game.
+    #[cfg(feature = "hot-reload")]
+    config_watcher: Option<ConfigWatcher>,
+    /// The screen shown in place of the game while the boss key has it hidden.
+    boss: Option<BossScreen>,
+    /// The window title last set, if the game sets it.
+    window_title: Option<String>,
+    /// What the engine was attached to do. Kept even if it fails, since the game has been played with its help.
+    bot_role: Option<BotRole>,
+    /// How the boards are drawn as pictures, if the terminal can show them.
+    #[cfg(feature = "graphics")]
+    graphics: Option<Protocol>,
+    /// What the pictures on screen were drawn from (the boards' lines of the frame, the cursor and which board has
+    /// it), if there are any, for only drawing them again when they change.
+    #[cfg(feature = "graphics")]
+    pictures: Option<(Vec<String>, (usize, usize), usize)>,
+    /// Each board's layout as it was when the game started, in the board file format, to retry them from the end panel
+    /// (moving mines, for one, change the boards as they're played).
+    layouts: Vec<String>,
+    /// The panel of what to do next, once the game is over, until it's closed to look over the board.
+    end_menu: Option<EndMenu>,
+    /// Draw for an audience, as when streaming or teaching on a projector: a bigger, brighter cursor and bigger text.
+    /// Starts as the config says, and toggled with ctrl+p.
+    presentation: bool,
+    /// Mark the cells the player got wrong, picked from the end panel.
+    reviewing: bool,
+    /// Set once going back is picked from the end panel, to end the game.
+    back: bool,
+    /// The kind of action the key press being handled was, and when it came in, until the frame showing it goes out.
+    /// Only kept with `--verbose`, to time how long key presses take to show.
+    key_press: Option<(&'static str, Instant)>,
+}
+
+/// A line of the JSON event stream: an event, with which board it happened on and when.
+#[derive(Serialize)]
+struct EventLine {
+    board: usize,
+    /// Time on the board's game clock.
+    elapsed_ms: u64,
+    #[serde(flatten)]
+    event: GameEvent,
 }
 
 impl Game {
-    pub(crate) fn new(field: Field) -> IoResult<Self> {
-        execute!(stdout(), EnterAlternateScreen)?;
-//        crossterm::terminal::enable_raw_mode()?;
-        Ok(Self {
-            field,
-            field_loc: (1, 1),
-            cursor: (0, 0),
-            terminal_size: terminal::size()?,
-            game_ended: false,
-            closed: false
-        })
+    /// How long the main loop waits for input before ticking timed state (e.g. pings).
+    const TICK_RATE: Duration = Duration::from_millis(100);
+    /// The tick rate on a slow link, where every frame costs more.
+    const SLOW_TICK_RATE: Duration = Duration::from_millis(500);
+    /// How long a computer opponent waits before making its move, so human players can follow along.
+    const COMPUTER_DELAY: Duration = Duration::from_millis(700);
+    /// How often a mine moves, in the moving mines variant.
+    const MOVING_MINES_INTERVAL: Duration = Duration::from_secs(10);
+    /// The highlight on numbers that changed because a mine moved.
+    const PULSE_COLOR: Color = Color::DarkRed;
+    /// The highlight on the cells a chord would reveal, when it can be chorded and when it can't yet.
+    const CHORD_COLORS: [Color; 2] = [Color::DarkGreen, Color::DarkYellow];
+    /// The highlight on the cells the player got wrong, while reviewing them.
+    const MISTAKE_COLOR: Color = Color::Red;
+    /// The cursor's colors in presentation mode, as (foreground, background): as bright as they come, to stand out on a
+    /// projector.
+    const PRESENTATION_CURSOR_COLORS: (Color, Color) = (Color::Black, Color::Yellow);
+    /// The highlight along the cursor's row and column in presentation mode.
+    const CROSSHAIR_COLOR: Color = Color::DarkBlue;
+    /// The highlight on the cell an engine suggests as a hint.
+    const HINT_COLOR: Color = Color::Green;
+    /// The time added for each flag placed on a safe cell in hardcore mode.
+    const HARDCORE_PENALTY: Duration = Duration::from_secs(10);
+    /// How long the bar showing how much of the board has been cleared is.
+    const PROGRESS_WIDTH: usize = 10;
+
+    /// Set up the terminal (alternate screen, raw mode, etc.) and start a game in it. The terminal is restored when
+    /// the game is closed or dropped.
+    pub(crate) fn new(field: Field, config: Config) -> IoResult<Self> {
+        // With bracketed paste, pasted text arrives as one event instead of a storm of key presses
+        execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste, EnableFocusChange)?;
+        terminal::enable_raw_mode()?;
+        let enhanced_keyboard = config.enhanced_keyboard && keyboard::enable()?;
+        if config.window_title {
+            // Save the current title on the terminal's title stack, to put back on close
+            write!(stdout(), "\x1b[22;0t")?;
+        }
+        if config.reduced_motion() {
+            execute!(stdout(), Hide)?;
+        }
+        let mut game = Self::headless(field, config, terminal::size()?, stdout());
+        game.enhanced_keyboard = enhanced_keyboard;
+        game.color_depth = ColorDepth::detect();
+        game.closed = false;
+        #[cfg(feature = "hot-reload")]
+        {
+            game.config_watcher = ConfigWatcher::new();
+        }
+        #[cfg(feature = "graphics")]
+        {
+            game.graphics = Protocol::pick(game.config.graphics);
+        }
+        Ok(game)
+    }
+
+    /// Start a game that renders frames of the given terminal size to `out`, without touching the real terminal.
+    /// Useful for tests and benchmarks. Colors are drawn as configured, as if the terminal had 24-bit color.
+    pub fn headless(field: Field, config: Config, terminal_size: (u16, u16), out: impl Write + 'static) -> Self {
+        let layouts = vec![board_file::write(&field)];
+        Self {
+            boards: vec![Board::new(field, config.wrap_cursor)],
+            focus: 0,
+            terminal_size,
+            closed: true,
+            player_color: Color::Cyan,
+            pings: Vec::new(),
+            changes: Vec::new(),
+            last_move: Instant::now(),
+            preset: None,
+            adaptive_level: None,
+            puzzle: None,
+            ladder: None,
+            daily: None,
+            timer: Timer::default(),
+            clicks: 0,
+            flags: 0,
+            death: None,
+            toasts: Vec::new(),
+            keymap: config.controls.keymap(),
+            presentation: config.presentation,
+            title_fonts: TitleFonts::default(),
+            config,
+            settings: None,
+            help: None,
+            stats: None,
+            prompt: None,
+            key_repeat: KeyRepeat::default(),
+            enhanced_keyboard: false,
+            last_frame: Vec::new(),
+            overlay_rows: Vec::new(),
+            covered: None,
+            frame: Vec::new(),
+            out: Box::new(out),
+            recording: None,
+            played: Duration::ZERO,
+            playback: false,
+            json_events: None,
+            bot: None,
+            bot_role: None,
+            confirm: None,
+            #[cfg(feature = "hot-reload")]
+            config_watcher: None,
+            boss: None,
+            window_title: None,
+            color_depth: ColorDepth::TrueColor,
+            show_frontier: false,
+            #[cfg(feature = "graphics")]
+            graphics: None,
+            #[cfg(feature = "graphics")]
+            pictures: None,
+            layouts,
+            end_menu: None,
+            reviewing: false,
+            back: false,
+            key_press: None,
+        }
+    }
+
+    /// Mark the field as generated from a preset, making the game ranked.
+    pub fn with_preset(mut self, preset: Preset) -> Self {
+        self.preset = Some(preset);
+        self
+    }
+
+    /// Mark the field as picked by adaptive mode at the given level, so the result steers the next game's level.
+    pub(crate) fn with_adaptive_level(mut self, level: f64) -> Self {
+        self.adaptive_level = Some(level);
+        self
+    }
+
+    /// Stream every [`GameEvent`] to `out` as it happens, as newline-delimited JSON. Each line is an object with the
+    /// event's name under `"event"`, its fields, the index of the board it happened on under `"board"`, and the time
+    /// on that board's game clock under `"elapsed_ms"`.
+    pub fn with_json_events(mut self, out: impl Write + 'static) -> Self {
+        self.json_events = Some(Box::new(out));
+        self
+    }
+
+    /// Attach an external engine, either to play the game or to give hints on `?`.
+    pub(crate) fn with_bot(mut self, bot: Bot, role: BotRole) -> Self {
+        self.bot = Some(bot);
+        self.bot_role = Some(role);
+        self
+    }
+
+    /// Mark the field as a puzzle from a pack, so winning marks it as solved.
+    pub(crate) fn with_puzzle(mut self, puzzle: PuzzleId, name: String) -> Self {
+        self.puzzle = Some((puzzle, name));
+        self
+    }
+
+    /// Mark the game as the given rung of the time trial ladder (counting from 0), with the time taken on the rungs
+    /// before it, so the clock shows the ladder's total too.
+    pub(crate) fn with_ladder(mut self, rung: usize, so_far: Duration) -> Self {
+        self.ladder = Some((rung, so_far));
+        self
+    }
+
+    /// Mark the game as the given day's daily challenge (as days since the Unix epoch), so its result is saved as the
+    /// day's: with the archive's results if it's played after its day, or the streak's if not.
+    pub fn with_daily(mut self, day: u64, archive: bool) -> Self {
+        self.daily = Some((day, archive));
+        self
+    }
+
+    /// Time added to the clock as penalties so far.
+    pub(crate) fn penalties(&self) -> Duration {
+        self.engine().penalties()
+    }
+
+    /// The time the game was won in, penalties and all, or None if it hasn't been won.
+    pub(crate) fn won_in(&self) -> Option<Duration> {
+        self.won().then(|| self.engine().elapsed())
+    }
+
+    /// Record every move, and save the replay to the given file once the game ends.
+    pub(crate) fn with_recording(mut self, path: PathBuf) -> Self {
+        self.recording = Some((path, Self::replay(self.engine())));
+        self
+    }
+
+    /// A new recording of the game on the engine's board, by its rules.
+    fn replay(engine: &GameEngine) -> Replay {
+        let mut replay = Replay::new(&engine.field);
+        if let Some(noisy) = &engine.noisy_numbers {
+            replay = replay.with_noisy_numbers(noisy.noise, noisy.seed);
+        }
+        if let Some(penalty) = engine.flag_penalty {
+            replay = replay.with_flag_penalty(penalty);
+        }
+        replay
+    }
+
+    /// Mark the game as the playback of a replay, so its result isn't recorded.
+    pub(crate) fn with_playback(mut self) -> Self {
+        self.playback = true;
+        self
+    }
+
+    /// Play against a time limit.
+    pub(crate) fn with_challenge(mut self, challenge: Challenge) -> Self {
+        for board in &mut self.boards {
+            board.engine.challenge = Some(challenge);
+        }
+        self
+    }
+
+    /// Play under extra limits, like a puzzle in a campaign.
+    pub(crate) fn with_constraints(mut self, constraints: Constraints) -> Self {
+        for board in &mut self.boards {
+            board.engine.constraints = constraints;
+        }
+        self
+    }
+
+    /// Play with mines that move around every so often.
+    pub(crate) fn with_moving_mines(mut self) -> Self {
+        for board in &mut self.boards {
+            board.engine.moving_mines = Some(MovingMines::new(Self::MOVING_MINES_INTERVAL, thread_rng().gen()));
+        }
+        self
+    }
+
+    /// Play with noisy numbers, each shown as a range `noise` wider than it, picked from the seed.
+    pub fn with_noisy_numbers(mut self, noise: u8, seed: u64) -> Self {
+        for board in &mut self.boards {
+            board.engine.set_noisy_numbers(noise, seed);
+        }
+        self
+    }
+
+    /// Play in hardcore mode, where flagging a safe cell costs time.
+    pub(crate) fn with_hardcore(self) -> Self {
+        self.with_flag_penalty(Self::HARDCORE_PENALTY)
+    }
+
+    /// Add the given time to the clock for each flag placed on a safe cell.
+    pub(crate) fn with_flag_penalty(mut self, penalty: Duration) -> Self {
+        for board in &mut self.boards {
+            board.engine.flag_penalty = Some(penalty);
+        }
+        self
+    }
+
+    /// Play in hotseat versus mode, with players taking turns on this board.
+    pub(crate) fn with_versus(mut self, versus: Versus) -> Self {
+        self.engine_mut().versus = Some(versus);
+        self
+    }
+
+    /// Play more boards alongside the first, side by side. Every action applies to the focused board (switched with
+    /// tab), and the game is only won once every board is. The boards should all be the same size.
+    pub fn with_boards(mut self, fields: impl IntoIterator<Item = Field>) -> Self {
+        let wrap_cursor = self.config.wrap_cursor;
+        for field in fields {
+            self.layouts.push(board_file::write(&field));
+            self.boards.push(Board::new(field, wrap_cursor));
+        }
+        self
+    }
+
+    /// The focused board's engine.
+    fn engine(&self) -> &GameEngine {
+        &self.boards[self.focus].engine
+    }
+
+    fn engine_mut(&mut self) -> &mut GameEngine {
+        &mut self.boards[self.focus].engine
+    }
+
+    /// Returns true once the game is over: once any board is lost, or once all of them are finished.
+    fn ended(&self) -> bool {
+        self.boards.iter().all(|board| board.engine.ended())
+            || self.boards.iter().any(|board| board.engine.ended() && !board.engine.won())
+    }
+
+    /// Returns true while the clock is running on any board.
+    pub(crate) fn running(&self) -> bool {
+        self.boards.iter().any(|board| board.engine.running())
+    }
+
+    /// Returns true if every board was won.
+    fn won(&self) -> bool {
+        self.boards.iter().all(|board| board.engine.won())
+    }
+
+    /// How much room the rulers take up, if they're shown: the width of the one beside the first board, and the
+    /// height of those over the boards.
+    fn ruler_size(&self) -> (usize, usize) {
+        if !self.config.rulers {
+            return (0, 0);
+        }
+        let field = &self.engine().field;
+        (ruler::gutter_width(field), ruler::columns(field).len())
+    }
+
+    /// Where the first board's top left cell is drawn: inside its box, after any rulers. The rest follow to its right,
+    /// one column apart.
+    fn field_loc(&self) -> (u16, u16) {
+        let (gutter, ruler) = self.ruler_size();
+        (gutter as u16 + 1, ruler as u16 + 1)
+    }
+
+    /// Where the given board's top left cell is drawn.
+    fn board_loc(&self, board: usize) -> (u16, u16) {
+        // Each board is boxed, with a column of space between the boxes
+        let stride = self.engine().field.width() as u16 + 3;
+        let field_loc = self.field_loc();
+        (field_loc.0 + board as u16 * stride, field_loc.1)
+    }
+
+    /// Bring every board's clock up to date.
+    fn advance(&mut self) {
+        let elapsed = self.timer.lap();
+        self.played += elapsed;
+        for board in &mut self.boards {
+            board.engine.advance(elapsed);
+        }
+    }
+
+    /// Move the game clock forward without waiting for the time to pass, for playing back a replay.
+    pub(crate) fn advance_by(&mut self, by: Duration) -> IoResult<()> {
+        for board in &mut self.boards {
+            board.engine.advance(by);
+        }
+        self.handle_game_events()
+    }
+
+    /// Stop the game while another one is shown in its place, with its clock stopped.
+    pub(crate) fn suspend(&mut self) -> IoResult<()> {
+        self.advance();
+        self.handle_game_events()?;
+        self.timer.pause();
+        Ok(())
+    }
+
+    /// Carry on with a suspended game, redrawing it from scratch over whatever was shown in the meantime.
+    pub(crate) fn resume(&mut self, terminal_size: (u16, u16)) -> IoResult<()> {
+        self.timer.resume();
+        self.terminal_size = terminal_size;
+        #[cfg(feature = "graphics")]
+        self.take_down_pictures()?;
+        self.last_frame.clear();
+        queue!(self.frame, Clear(ClearType::All))
+    }
+
+    /// Pick up the changes to the config file. Colors, glyphs and controls change straight away, but the settings for
+    /// how a board plays (like wrapping the cursor) wait for the next game. A file that doesn't load (perhaps being
+    /// saved halfway) is reported, and the config from before kept.
+    #[cfg(feature = "hot-reload")]
+    fn reload_config(&mut self) {
+        match self.config.reloaded() {
+            Ok(Some(config)) => {
+                console::pick_glyphs(config.glyphs);
+                config.pick_borders(self.color_depth);
+                // The boards' rows are cached as drawn with the old glyphs
+                for board in &self.boards {
+                    board.engine.field.rendered.borrow_mut().fill(None);
+                }
+                self.keymap = config.controls.keymap();
+                #[cfg(feature = "graphics")]
+                if config.graphics != self.config.graphics {
+                    // Failing to take the old pictures down only leaves them until they're drawn over
+                    let _ = self.take_down_pictures();
+                    self.graphics = Protocol::pick(config.graphics);
+                    self.last_frame.clear();
+                }
+                // The font files may have been changed too
+                self.title_fonts = TitleFonts::default();
+                self.config = config;
+                self.toast(locale::tr("toast-config-reloaded").to_string());
+            },
+            Ok(None) => {},
+            Err(e) => self.toast(locale::tr_with("toast-config-failed", &[("error", &e)])),
+        }
+    }
+
+    /// Swap the game for the boss screen, stopping the clock, or bring it back.
+    fn toggle_boss(&mut self) -> IoResult<()> {
+        if self.boss.take().is_some() {
+            self.timer.resume();
+            self.last_frame.clear();
+            queue!(self.frame, Clear(ClearType::All))
+        } else {
+            self.key_repeat.release();
+            self.timer.pause();
+            self.boss = Some(BossScreen::new());
+            Ok(())
+        }
+    }
+
+    /// Returns true if the game set up the terminal, and so puts it back as it was when it's closed.
+    pub(crate) fn owns_terminal(&self) -> bool {
+        !self.closed
+    }
+
+    /// Show a short-lived message below the game.
+    pub(crate) fn toast(&mut self, text: String) {
+        self.toasts.push(Toast::new(text));
+    }
+
+    /// Say which of the session's tabs the game is in, in place of anything it said about that before.
+    pub(crate) fn toast_tab(&mut self, number: usize, count: usize) {
+        self.toasts.retain(|toast| !toast.tab);
+        let text = locale::tr_with("session-game", &[("number", &number), ("count", &count)]);
+        self.toasts.push(Toast { tab: true, ..Toast::new(text) });
     }
 
     pub(crate) fn close(&mut self) -> IoResult<()> {
-//        crossterm::terminal::disable_raw_mode()?;
-        execute!(stdout(), LeaveAlternateScreen)?;
+        if self.enhanced_keyboard {
+            keyboard::disable()?;
+        }
+        if self.config.window_title {
+            write!(stdout(), "\x1b[23;0t")?;
+        }
+        if self.config.reduced_motion() {
+            execute!(stdout(), Show)?;
+        }
+        #[cfg(feature = "graphics")]
+        if let Some(protocol) = self.graphics {
+            protocol.clear(&mut stdout())?;
+        }
+        terminal::disable_raw_mode()?;
+        execute!(stdout(), DisableFocusChange, DisableBracketedPaste, LeaveAlternateScreen)?;
         self.closed = true;
         Ok(())
     }
 
-    pub(crate) fn render(&self) -> IoResult<()> {
-        let mut buffer = vec![String::new(); self.terminal_size.1 as usize];
-        let buf = BoxedComponent(&self.field).render_at(&mut buffer);
-        let buf = BoxedComponent(&Controls).render_at(buf);
-        if self.game_ended {
-            Title::new("Game Over").render_at(buf);
+    /// Draw a frame, writing only the lines that changed since the last one.
+    pub fn render(&mut self) -> IoResult<()> {
+        self.draw()?;
+        // The frame goes out in one write, so the terminal never shows half of it (like the cursor without the board)
+        self.out.write_all(&self.frame)?;
+        self.frame.clear();
+        self.out.flush()?;
+        if let Some((action, at)) = self.key_press.take() {
+            timing::latency(action, at.elapsed());
         }
-        execute!(stdout(), MoveTo(0, 0))?;
-        write!(stdout(), "{}", buffer.into_iter()
-            .collect::<Vec<_>>()
-            .join("\n")
-        )?;
+        Ok(())
+    }
 
-        execute!(stdout(), MoveTo(self.cursor.0 + self.field_loc.0, self.cursor.1 + self.field_loc.1))?;
-        write!(stdout(), "◎")?;
-        execute!(stdout(), MoveTo(0, self.field.board.len() as u16 + 1))
+    fn draw(&mut self) -> IoResult<()> {
+        #[cfg(feature = "graphics")]
+        let pictures = self.pictures_shown();
+        #[cfg(feature = "graphics")]
+        if !pictures {
+            self.take_down_pictures()?;
+        }
+        if let Some(boss) = &self.boss {
+            let mut buffer = vec![String::new(); self.terminal_size.1 as usize];
+            boss.render_at(&mut buffer);
+            // Lines that wrapped would scroll the screen, so they're cut off at its edge (the log is all ASCII)
+            buffer.iter_mut().for_each(|line| line.truncate(self.terminal_size.0 as usize));
+            self.covered = None;
+            return self.write_frame(buffer);
+        }
+        let mut layers = Layers::new(self.terminal_size);
+        let buffer = layers.base();
+        let (gutter, ruler) = self.ruler_size();
+        let height = (ruler + self.engine().field.height() + 2).min(buffer.len());
+        let ruler = ruler.min(height);
+        if self.config.rulers {
+            let field = &self.engine().field;
+            // Every board gets the same names over it, past the gaps and borders between them
+            let columns = ruler::columns(field);
+            let columns = columns.iter().map(|line| vec![line.as_str(); self.boards.len()].join("   "));
+            for (line, columns) in buffer.iter_mut().zip(columns) {
+                line.push_str(&format!("{} {}", " ".repeat(gutter), columns));
+            }
+            // The row names are beside the rows, between the box's top and bottom borders
+            for (y, line) in buffer[ruler..height].iter_mut().enumerate() {
+                let name = y.checked_sub(1).filter(|&row| row < field.size().0).map(ruler::row_name);
+                line.push_str(&format!("{} ", tui::pad_start(&name.unwrap_or_default(), gutter - 1)));
+            }
+        }
+        // The boards share the same lines, each drawn to the right of the one before
+        let boards = &mut buffer[ruler..];
+        for (i, board) in self.boards.iter().enumerate() {
+            if i > 0 {
+                boards.iter_mut().take(height - ruler).for_each(|line| line.push(' '));
+            }
+            // Hide the board while paused, so the pause can't be used to study it off the clock
+            if self.timer.paused() {
+                BoxedComponent::new(&Paused::new(&board.engine.field)).render_at(boards);
+            } else if self.show_frontier {
+                BoxedComponent::new(&Frontier::new(&board.engine.field)).render_at(boards);
+            } else {
+                BoxedComponent::new(&board.engine.field).render_at(boards);
+            }
+        }
+        let buf = &mut buffer[height..];
+        let controls = Controls::new(
+            &self.keymap,
+            self.boards.len() > 1,
+            matches!(self.engine().field.topology(), Topology::Layered { .. }),
+            self.bot_role == Some(BotRole::Hint),
+        );
+        let controls = BoxedComponent::new(&controls).titled(locale::tr("title-controls"));
+        let layout = self.layout(&controls);
+        // Presenting, the time is drawn big enough to read from the back of the room, and the controls give way to it
+        let buf = match layout {
+            Layout::Full | Layout::Compact if self.presentation => {
+                let time = self.engine().remaining().unwrap_or_else(|| self.engine().elapsed());
+                let font = self.title_fonts.get(&self.config.title_font);
+                Title::new(&format_time(time), font, self.terminal_size.0 as usize).render_at(buf)
+            },
+            _ => buf,
+        };
+        let buf = match layout {
+            Layout::Full if self.presentation => BoxedComponent::new(&self.clock()).render_at(buf),
+            Layout::Full => controls.render_at(BoxedComponent::new(&self.clock()).render_at(buf)),
+            Layout::Compact => BoxedComponent::new(&self.status_bar()).render_at(buf),
+            // Everything else is left out, but not without saying so
+            Layout::Minimal => {
+                let hidden = 2 + usize::from(self.engine().versus.is_some()) + usize::from(self.ended())
+                    + self.toasts.len();
+                Text::new(vec![format!("{}{}", console::glyphs().more, hidden)]).render_at(buf);
+                &mut []
+            },
+        };
+        let buf = match &self.engine().versus {
+            Some(versus) => BoxedComponent::new(versus).render_at(buf),
+            None => buf,
+        };
+        let mut buf = if self.ended() {
+            let banner = |banner: &Banner, id| match banner.text.as_str() {
+                "" => locale::tr(id).to_string(),
+                text => text.to_string(),
+            };
+            let (text, font) = match self.engine().versus.as_ref().map(Versus::winner) {
+                Some(Some(winner)) => (locale::tr_with("end-wins", &[("name", &winner.name)]), None),
+                Some(None) => (locale::tr("end-draw").to_string(), None),
+                None if self.won() => (banner(&self.config.banners.won, "end-won"), self.config.banners.won.font()),
+                None if matches!(self.engine().challenge(), Some(Challenge::Sprint(_))) => {
+                    (locale::tr_with("end-cleared", &[("cleared", &self.engine().cleared())]), None)
+                },
+                None if self.engine().time_up() => (locale::tr("end-time-up").to_string(), None),
+                None => (banner(&self.config.banners.lost, "end-lost"), self.config.banners.lost.font()),
+            };
+            let font = self.title_fonts.get(font.unwrap_or(&self.config.title_font));
+            let buf = Title::new(&text, font, self.terminal_size.0 as usize).render_at(buf);
+            match (&self.end_menu, &self.engine().versus) {
+                (Some(end_menu), _) => BoxedComponent::new(end_menu).render_at(buf),
+                (None, Some(_)) => buf,
+                (None, None) => BoxedComponent::new(&self.flag_summary()).render_at(buf),
+            }
+        } else {
+            buf
+        };
+        for toast in &self.toasts {
+            buf = BoxedComponent::new(&toast.text).render_at(buf);
+        }
+        // The board stays bright behind a question, since it's about a move on the board
+        if let Some(confirm) = &self.confirm {
+            layers.overlay(BoxedComponent::new(confirm), false);
+        } else if let Some(prompt) = &self.prompt {
+            layers.overlay(BoxedComponent::new(prompt), false);
+        } else if let Some(help) = &self.help {
+            layers.overlay(BoxedComponent::new(help), true);
+        } else if let Some(stats) = &self.stats {
+            layers.overlay(BoxedComponent::new(stats), true);
+        } else if let Some(settings) = &self.settings {
+            layers.overlay(BoxedComponent::new(settings).titled(locale::tr("title-controls")), true);
+        }
+        self.covered = layers.covered();
+        self.write_frame(layers.into_lines())?;
+
+        // Park the terminal's own cursor below the boards
+        let below = self.field_loc().1 + self.engine().field.height() as u16;
+        if self.timer.paused() {
+            return queue!(self.frame, MoveTo(0, below));
+        }
+        // The pictures stand in for everything drawn over the boards' cells, cursor and all
+        #[cfg(feature = "graphics")]
+        if pictures {
+            self.draw_pictures()?;
+            return queue!(self.frame, MoveTo(0, below));
+        }
+        let mut highlights = self.crosshair();
+        let reduced_motion = self.config.reduced_motion();
+        highlights.extend(self.changes.iter()
+            .map(|change| (change.board, change.pos, self.color_depth.fit(change.color(reduced_motion)))));
+        if self.config.chord_preview && !self.ended() {
+            if let Some((targets, ready)) = self.engine().chord_preview(self.engine().cursor()) {
+                let color = Self::CHORD_COLORS[if ready { 0 } else { 1 }];
+                highlights.extend(targets.into_iter().map(|(row, col)| (self.focus, (col as u16, row as u16), color)));
+            }
+        }
+        if self.reviewing {
+            for (i, board) in self.boards.iter().enumerate() {
+                let mistakes = board.engine.field.mistakes().into_iter();
+                highlights.extend(mistakes.map(|(row, col)| (i, (col as u16, row as u16), Self::MISTAKE_COLOR)));
+            }
+        }
+        if let Some(versus) = &self.engine().versus {
+            highlights.extend(versus.claims.iter().map(|(pos, player)| (0, *pos, versus.players[*player].color)));
+        }
+        highlights.extend(self.pings.iter().map(|ping| (ping.board, ping.pos, ping.color)));
+        for (board, pos, color) in highlights {
+            self.highlight_cell(board, pos, color)?;
+        }
+        self.draw_notes()?;
+
+        let (x, y) = self.engine().field.screen_pos(self.engine().cursor());
+        let loc = self.board_loc(self.focus);
+        let cursor = (x + loc.0, y + loc.1);
+        if !self.is_covered(cursor) {
+            self.overlay_rows.push(cursor.1);
+            queue!(self.frame, MoveTo(cursor.0, cursor.1))?;
+            if self.presentation {
+                let (foreground, background) = Self::PRESENTATION_CURSOR_COLORS;
+                queue!(self.frame, SetForegroundColor(foreground), SetBackgroundColor(background))?;
+                write!(self.frame, "{}", console::glyphs().cursor)?;
+                queue!(self.frame, ResetColor)?;
+            } else {
+                write!(self.frame, "{}", console::glyphs().cursor)?;
+            }
+        }
+        queue!(self.frame, MoveTo(0, below))
+    }
+
+    /// In presentation mode, the highlight along the focused board's row and column through the cursor, so the cursor
+    /// can be followed from afar. Nothing otherwise.
+    fn crosshair(&self) -> Vec<(usize, (u16, u16), Color)> {
+        if !self.presentation {
+            return Vec::new();
+        }
+        let (rows, cols) = self.engine().field.size();
+        let (row, col) = self.engine().cursor();
+        let cells = (0..cols).map(|col| (row, col)).chain((0..rows).map(|row| (row, col)));
+        cells.map(|(row, col)| (self.focus, (col as u16, row as u16), Self::CROSSHAIR_COLOR)).collect()
+    }
+
+    /// The window title: what's being played, the clock and how many mines are left to flag on the focused board.
+    fn title(&self) -> String {
+        let field = &self.engine().field;
+        let board = self.board_name();
+        let time = self.engine().remaining().unwrap_or_else(|| self.engine().elapsed());
+        let flags = field.flag_report();
+        let left = field.mine_count() as isize - (flags.correct + flags.wrong) as isize;
+        locale::tr_with("window-title", &[("board", &board), ("time", &format_time(time)), ("left", &left)])
+    }
+
+    /// What's being played: the puzzle, the preset, or else the board's size.
+    fn board_name(&self) -> String {
+        let field = &self.engine().field;
+        match (&self.puzzle, self.preset) {
+            (Some((_, puzzle)), _) => puzzle.clone(),
+            (None, Some(preset)) => preset.label().to_string(),
+            (None, None) => format!("{}x{}", field.size().0, field.size().1),
+        }
+    }
+
+    /// How much fits around the boards at the terminal's width: everything, if the widest panel fits, the status bar in
+    /// place of the clock and the controls if that fits, or else only the boards. Picked afresh for each frame, so it
+    /// follows the terminal as it's resized.
+    fn layout(&self, controls: &BoxedComponent<'_, Controls>) -> Layout {
+        let width = self.terminal_size.0 as usize;
+        if controls.width().max(BoxedComponent::new(&self.clock()).width()) <= width {
+            Layout::Full
+        } else if BoxedComponent::new(&self.status_bar()).width() <= width {
+            Layout::Compact
+        } else {
+            Layout::Minimal
+        }
+    }
+
+    /// The clock and how much of the board is cleared on a single line, with the key for help (which lists the rest of
+    /// the controls), for narrow terminals.
+    fn status_bar(&self) -> Text {
+        let mut parts = self.clock_lines();
+        parts.push(format!("{:.0}%", self.engine().progress() * 100.0));
+        parts.push(format!("f1 {}", locale::tr("control-help")));
+        Text::new(vec![parts.join(&format!(" {} ", console::glyphs().separator))])
     }
 
-    // Returned bool indicates whether to continue (true for continue, false for exit)
-    pub(crate) fn handle_event(&mut self, event: Event) -> IoResult<bool> {
+    /// The game clock, counting down in a challenge, plus the score in a sprint and any limit on clicks, and how much
+    /// of the board has been cleared.
+    fn clock(&self) -> Text {
+        let mut lines = self.clock_lines();
+        lines.push(ProgressBar::determinate(self.engine().progress(), Self::PROGRESS_WIDTH).line());
+        Text::new(lines)
+    }
+
+    fn clock_lines(&self) -> Vec<String> {
+        let mut lines = vec![match self.engine().remaining() {
+            Some(remaining) => locale::tr_with("clock-time-left", &[("time", &format_time(remaining))]),
+            None => locale::tr_with("clock-time", &[("time", &format_time(self.engine().elapsed()))]),
+        }];
+        if self.engine().penalties() > Duration::ZERO {
+            lines[0].push_str(&format!(" (+{})", format_time(self.engine().penalties())));
+        }
+        if let Some(Challenge::Sprint(_)) = self.engine().challenge() {
+            lines.push(locale::tr_with("clock-cleared", &[("cleared", &self.engine().cleared())]));
+        }
+        if let Some(max) = self.engine().constraints().max_clicks {
+            lines.push(locale::tr_with("clock-clicks", &[("clicks", &self.engine().clicks()), ("max", &max)]));
+        }
+        if let Some((rung, so_far)) = self.ladder {
+            let total = format_time(so_far + self.engine().elapsed());
+            let rungs = Preset::ALL.len();
+            lines.push(locale::tr_with("clock-ladder", &[("rung", &(rung + 1)), ("rungs", &rungs), ("total", &total)]));
+        }
+        // With --verbose, how quickly key presses show, for telling whether it's the game or the terminal that lags
+        if let Some((p50, p99)) = timing::latency_percentiles() {
+            let [p50, p99] = [p50, p99].map(|time| format!("{:.1}ms", time.as_secs_f64() * 1000.0));
+            lines.push(locale::tr_with("clock-latency", &[("p50", &p50), ("p99", &p99)]));
+        }
+        lines
+    }
+
+    /// How the flags held up, across all the boards, for the end screen.
+    fn flag_summary(&self) -> Text {
+        let (correct, wrong, unflagged) = self.boards.iter()
+            .map(|board| board.engine.field.flag_report())
+            .fold((0, 0, 0), |(c, w, u), report| (c + report.correct, w + report.wrong, u + report.unflagged));
+        let missed = match unflagged {
+            1 => locale::tr("end-missed-mine").to_string(),
+            count => locale::tr_with("end-missed-mines", &[("count", &count)]),
+        };
+        Text::new(keymap::help_lines(&[
+            (locale::tr("end-flags"), locale::tr_with("end-flags-counts", &[("right", &correct), ("wrong", &wrong)])),
+            (locale::tr("end-missed"), missed),
+        ]))
+    }
+
+    /// Draw the notes over the hidden cells they're on. Flagging a cell hides its note until the flag comes off.
+    fn draw_notes(&mut self) -> IoResult<()> {
+        let color = self.color_depth.fit(self.config.note_color());
+        for (i, board) in self.boards.iter().enumerate() {
+            let loc = self.board_loc(i);
+            for (&pos, note) in &board.notes {
+                if board.engine.field.board[pos.0][pos.1].is_flagged() {
+                    continue;
+                }
+                let (x, y) = board.engine.field.screen_pos(pos);
+                if self.is_covered((x + loc.0, y + loc.1)) {
+                    continue;
+                }
+                self.overlay_rows.push(y + loc.1);
+                queue!(self.frame, MoveTo(x + loc.0, y + loc.1), SetForegroundColor(color))?;
+                write!(self.frame, "{}", note.glyph())?;
+                queue!(self.frame, ResetColor)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a frame, only the lines that changed since the last one, and keep the window title up to date.
+    fn write_frame(&mut self, buffer: Vec<String>) -> IoResult<()> {
+        // Only write the lines that changed since the last frame, plus those that overlays were drawn over
+        let overlay_rows = std::mem::take(&mut self.overlay_rows);
+        for (i, line) in buffer.iter().enumerate() {
+            if self.last_frame.get(i) == Some(line) && !overlay_rows.contains(&(i as u16)) {
+                continue;
+            }
+            // Raw mode doesn't return the carriage on newlines, so position each line explicitly
+            queue!(self.frame, MoveTo(0, i as u16))?;
+            write!(self.frame, "{}", line)?;
+            // Clear whatever was left over from longer lines in previous frames
+            queue!(self.frame, Clear(ClearType::UntilNewLine))?;
+        }
+        self.last_frame = buffer;
+
+        if self.config.window_title && !self.closed {
+            let title = if self.boss.is_some() { BossScreen::TITLE.to_string() } else { self.title() };
+            if self.window_title.as_ref() != Some(&title) {
+                queue!(self.frame, SetTitle(&title))?;
+                self.window_title = Some(title);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the boards are drawn as pictures in this frame: only while they're in plain view, with nothing over or
+    /// in place of them, and all of them fit on screen.
+    #[cfg(feature = "graphics")]
+    fn pictures_shown(&self) -> bool {
+        let right = self.board_loc(self.boards.len() - 1).0 as usize + self.engine().field.width() + 1;
+        let bottom = self.field_loc().1 as usize + self.engine().field.height() + 1;
+        // Pictures show the numbers as they are, so not with noisy numbers
+        self.graphics.is_some() && self.overlay().is_none() && !self.timer.paused() && !self.show_frontier
+            && self.engine().noisy_numbers.is_none()
+            && right <= self.terminal_size.0 as usize && bottom <= self.terminal_size.1 as usize
+    }
+
+    /// Draw each board as a picture over its cells, if anything on them has changed since they were last drawn.
+    #[cfg(feature = "graphics")]
+    fn draw_pictures(&mut self) -> IoResult<()> {
+        let Some(protocol) = self.graphics else { return Ok(()) };
+        let top = self.field_loc().1 as usize;
+        let rows = self.last_frame.get(top..top + self.engine().field.height()).unwrap_or_default();
+        let drawn = (rows.to_vec(), self.engine().cursor(), self.focus);
+        if self.pictures.as_ref() == Some(&drawn) {
+            return Ok(());
+        }
+        let locs = (0..self.boards.len()).map(|i| self.board_loc(i)).collect::<Vec<_>>();
+        for (i, (board, loc)) in self.boards.iter().zip(locs).enumerate() {
+            queue!(self.frame, MoveTo(loc.0, loc.1))?;
+            let cursor = (i == self.focus).then(|| board.engine.cursor());
+            protocol.draw(&mut self.frame, i, &board.engine.field, cursor)?;
+        }
+        self.pictures = Some(drawn);
+        Ok(())
+    }
+
+    /// Take the boards' pictures off the screen, if they're on it. Sixel pictures go once they're drawn over, so the
+    /// whole of the next frame is written.
+    #[cfg(feature = "graphics")]
+    fn take_down_pictures(&mut self) -> IoResult<()> {
+        let Some(protocol) = self.graphics else { return Ok(()) };
+        if self.pictures.take().is_some() {
+            protocol.clear(&mut self.frame)?;
+            if protocol == Protocol::Sixel {
+                self.last_frame.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the given spot on screen was under the overlay in the last frame, so nothing is drawn there.
+    fn is_covered(&self, (x, y): (u16, u16)) -> bool {
+        self.covered.is_some_and(|area| area.contains((x as usize, y as usize)))
+    }
+
+    fn highlight_cell(&mut self, board: usize, pos: (u16, u16), color: Color) -> IoResult<()> {
+        let field = &self.boards[board].engine.field;
+        let glyph = field.glyph((pos.1 as usize, pos.0 as usize));
+        let (x, y) = field.screen_pos((pos.1 as usize, pos.0 as usize));
+        let loc = self.board_loc(board);
+        let pos = (x + loc.0, y + loc.1);
+        if self.is_covered(pos) {
+            return Ok(());
+        }
+        self.overlay_rows.push(pos.1);
+        queue!(self.frame, MoveTo(pos.0, pos.1), SetBackgroundColor(color))?;
+        write!(self.frame, "{}", glyph)?;
+        queue!(self.frame, ResetColor)
+    }
+
+    /// React to a terminal event. Returns false if the game should exit.
+    pub fn handle_event(&mut self, event: Event) -> IoResult<bool> {
+        if let Event::Key(key @ KeyEvent { kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event {
+            if timing::enabled() {
+                let action = self.keymap.action(&key).map_or("other", Action::name);
+                self.key_press = Some((action, Instant::now()));
+            }
+        }
+        // Bring the game clock up to date before anything the event does, so it's stopped at the right time
+        self.advance();
+        self.handle_game_events()?;
         match event {
-            Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) => match code {
-                KeyCode::Left => self.step_cursor(Direction::Left),
-                KeyCode::Right => self.step_cursor(Direction::Right),
-                KeyCode::Up => self.step_cursor(Direction::Up),
-                KeyCode::Down => self.step_cursor(Direction::Down),
-                KeyCode::Char(' ') => {
-                    let r = self.field.clear_cell((self.cursor.1 as usize, self.cursor.0 as usize));
-                    if matches!(r, Some(true)) {
-                        self.game_ended = true;
+            Event::Key(KeyEvent { kind: KeyEventKind::Release, .. }) => self.key_repeat.release(),
+            Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) if Some(code) == self.config.boss_key() => {
+                self.toggle_boss()?;
+            },
+            // Any key resumes, in case the terminal doesn't report regaining focus
+            Event::Key(_) if self.boss.is_none() && self.timer.paused() => self.timer.resume(),
+            // Only movement auto-repeats; repeating anything else (e.g. toggling a flag) would be surprising
+            Event::Key(key @ KeyEvent { kind: KeyEventKind::Repeat, .. })
+                if !matches!(self.keymap.action(&key), Some(Action::Move(_))) => {},
+            _ if self.route(&event)? => {},
+            Event::Key(key @ KeyEvent { code, modifiers, .. }) => {
+                let action = self.keymap.action(&key);
+                if code == KeyCode::F(1) {
+                    let (width, rows) = self.help_size();
+                    self.help = Some(Scroll::new(tui::help_lines(&self.keymap, width), rows));
+                    return Ok(true);
+                }
+                if code == KeyCode::Esc {
+                    self.settings = Some(Settings::new(self.config.controls));
+                    return Ok(true);
+                }
+                if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('e') {
+                    self.export();
+                    return Ok(true);
+                }
+                if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('b') {
+                    self.bookmark();
+                    return Ok(true);
+                }
+                if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('p') {
+                    self.presentation = !self.presentation;
+                    let id = if self.presentation { "toast-presenting" } else { "toast-not-presenting" };
+                    self.toast(locale::tr(id).to_string());
+                    return Ok(true);
+                }
+                match code {
+                    KeyCode::Tab => self.focus = (self.focus + 1) % self.boards.len(),
+                    KeyCode::BackTab => self.focus = (self.focus + self.boards.len() - 1) % self.boards.len(),
+                    KeyCode::Char('<') => self.apply(Command::Move(Direction::PrevLayer))?,
+                    KeyCode::Char('>') => self.apply(Command::Move(Direction::NextLayer))?,
+                    KeyCode::Char(':') => self.prompt = Some(Prompt::new(':')),
+                    KeyCode::Char('?') if self.bot_role == Some(BotRole::Hint) && !self.ended() => self.ask_bot(),
+                    _ => {},
+                }
+
+                if !matches!(action, Some(Action::Move(_))) {
+                    self.key_repeat.release();
+                }
+                match action {
+                    Some(Action::Move(direction)) => {
+                        for _ in 0..self.key_repeat.press(direction, &self.config.repeat) {
+                            self.apply(Command::Move(direction))?;
+                        }
+                    },
+                    Some(Action::Reveal) if !self.ended() && !self.computer_turn() && !self.watching() => {
+                        let pos = self.engine().cursor();
+                        // Revealing a number that's already shown chords it instead
+                        let command = if self.engine().field.board[pos.0][pos.1].is_hidden() {
+                            Command::Reveal(pos)
+                        } else {
+                            Command::Chord(pos)
+                        };
+                        match self.mistake(command) {
+                            Some(question) if self.config.warn_mistakes => {
+                                self.confirm = Some(Confirm::new(command, question));
+                            },
+                            _ => {
+                                self.clicks += 1;
+                                self.apply(command)?;
+                            },
+                        }
+                    },
+                    Some(Action::Flag) if !self.ended() && !self.watching() => {
+                        self.clicks += 1;
+                        self.apply(Command::Flag(self.engine().cursor()))?;
+                    },
+                    Some(Action::Note) if !self.ended() => self.cycle_note(),
+                    Some(Action::Ping) => {
+                        let versus = self.engine().versus.as_ref();
+                        let color = versus.map_or(self.player_color, |versus| versus.current().color);
+                        let (row, col) = self.engine().cursor();
+                        self.ping((col as u16, row as u16), color);
+                    },
+                    Some(Action::Frontier) => self.show_frontier = !self.show_frontier,
+                    Some(Action::Quit) => return Ok(false),
+                    _ => { },
+                }
+            },
+            Event::FocusLost if self.config.pause_on_focus_loss && self.running() => self.timer.pause(),
+            Event::FocusGained => self.timer.resume(),
+            Event::Paste(_) => self.toast(locale::tr("toast-pasted").to_string()),
+            Event::Resize(width, height) => {
+                self.terminal_size = (width, height);
+                let (width, rows) = self.help_size();
+                if let Some(help) = &mut self.help {
+                    help.reflow(tui::help_lines(&self.keymap, width), rows);
+                }
+                if let Some(stats) = &mut self.stats {
+                    stats.resize(rows);
+                }
+                #[cfg(feature = "graphics")]
+                self.take_down_pictures()?;
+                // The terminal may have reflowed or dropped what was on screen, so redraw everything
+                self.last_frame.clear();
+                queue!(self.frame, Clear(ClearType::All))?;
+            },
+            _ => { },
+        }
+
+        Ok(!self.back)
+    }
+
+    /// The component on top, which gets the first look at events: the boss screen, then a question, then the prompt,
+    /// then the help screen, then the stats, then the settings, then the end panel.
+    fn overlay(&self) -> Option<Overlay> {
+        if self.boss.is_some() {
+            Some(Overlay::Boss)
+        } else if self.confirm.is_some() {
+            Some(Overlay::Confirm)
+        } else if self.prompt.is_some() {
+            Some(Overlay::Prompt)
+        } else if self.help.is_some() {
+            Some(Overlay::Help)
+        } else if self.stats.is_some() {
+            Some(Overlay::Stats)
+        } else if self.settings.is_some() {
+            Some(Overlay::Settings)
+        } else if self.end_menu.is_some() {
+            Some(Overlay::End)
+        } else {
+            None
+        }
+    }
+
+    /// Pass an event to the component on top, closing it if it's done. Returns false if there's no component on top, or
+    /// it ignored the event, so that the game should handle it.
+    fn route(&mut self, event: &Event) -> IoResult<bool> {
+        let Some(overlay) = self.overlay() else {
+            return Ok(false);
+        };
+        let handled = match overlay {
+            Overlay::Boss => self.boss.as_mut().map(|boss| boss.handle_event(event)),
+            Overlay::Confirm => self.confirm.as_mut().map(|confirm| confirm.handle_event(event)),
+            Overlay::Prompt => self.prompt.as_mut().map(|prompt| prompt.handle_event(event)),
+            Overlay::Help => self.help.as_mut().map(|help| help.handle_event(event)),
+            Overlay::Stats => self.stats.as_mut().map(|stats| stats.handle_event(event)),
+            Overlay::Settings => self.settings.as_mut().map(|settings| settings.handle_event(event)),
+            Overlay::End => self.end_menu.as_mut().map(|end_menu| end_menu.handle_event(event)),
+        };
+        if let Some(controls) = self.settings.as_mut().and_then(Settings::take_picked) {
+            self.config.controls = controls;
+            self.keymap = controls.keymap();
+            self.config.save()?;
+        }
+        if let Some(action) = self.end_menu.as_mut().and_then(EndMenu::take_picked) {
+            self.end_action(action)?;
+        }
+        match handled {
+            Some(Handled::Ignored) | None => return Ok(false),
+            Some(Handled::Consumed) => {},
+            Some(Handled::Closed) => match overlay {
+                Overlay::Boss => self.toggle_boss()?,
+                Overlay::Confirm => {
+                    if let Some(Confirm { action, accepted: true, .. }) = self.confirm.take() {
+                        self.clicks += 1;
+                        self.apply(action)?;
                     }
                 },
-                KeyCode::Char('f') => {
-                    let _ = self.field.toggle_flag((self.cursor.1 as usize, self.cursor.0 as usize));
+                Overlay::Prompt => {
+                    if let Some(Prompt { input, submitted: true, .. }) = self.prompt.take() {
+                        self.run_line(&input)?;
+                    }
+                },
+                Overlay::Help => self.help = None,
+                Overlay::Stats => self.stats = None,
+                Overlay::Settings => self.settings = None,
+                Overlay::End => self.end_menu = None,
+            },
+        }
+        Ok(true)
+    }
+
+    /// How wide the help screen's text can be, and how many lines of it fit on the terminal, inside its box and beside
+    /// its scrollbar.
+    fn help_size(&self) -> (usize, usize) {
+        ((self.terminal_size.0 as usize).saturating_sub(3), (self.terminal_size.1 as usize).saturating_sub(2))
+    }
+
+    /// Save a picture of the focused board to the current directory, as SVG (and PNG too, with the `png` feature).
+    /// Failing to save doesn't end the game; it's only reported.
+    fn export(&mut self) {
+        let name = format!(
+            "termsweeper-{}",
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+        );
+        let field = &self.engine().field;
+        let saved = std::fs::write(format!("{}.svg", name), export::svg(field));
+        #[cfg(feature = "png")]
+        let saved = saved.and_then(|_| std::fs::write(format!("{}.png", name), export::png(field)?));
+        self.toast(match saved {
+            Ok(()) if cfg!(feature = "png") => locale::tr_with("toast-pictures-saved", &[("name", &name)]),
+            Ok(()) => locale::tr_with("toast-picture-saved", &[("name", &name)]),
+            Err(e) => locale::tr_with("toast-picture-failed", &[("error", &e)]),
+        });
+    }
+
+    /// Bookmark the focused board, to try again later from `termsweeper bookmarks`.
+    fn bookmark(&mut self) {
+        if self.engine().field.topology() != Topology::Square {
+            self.toast(locale::tr("toast-bookmark-topology").to_string());
+            return;
+        }
+        self.toast(match Bookmarks::add(&self.engine().field) {
+            Ok(true) => locale::tr("toast-bookmarked").to_string(),
+            Ok(false) => locale::tr("toast-already-bookmarked").to_string(),
+            Err(e) => locale::tr_with("toast-bookmark-failed", &[("error", &e)]),
+        });
+    }
+
+    /// The end panel for the game just over: how the flags went (except in versus mode, where the board is shared),
+    /// and what can be done next.
+    fn end_panel(&self) -> EndMenu {
+        let summary = match self.engine().versus {
+            Some(_) => Vec::new(),
+            None => self.flag_summary().into_lines(),
+        };
+        let mut actions = Vec::new();
+        if self.can_start_over() {
+            actions.extend([EndAction::Retry, EndAction::NewBoard]);
+        }
+        actions.extend([EndAction::Review, EndAction::Share, EndAction::Back]);
+        EndMenu::new(summary, actions)
+    }
+
+    /// Carry out an action picked from the end panel. Anything that can't be done is reported, and the panel stays.
+    fn end_action(&mut self, action: EndAction) -> IoResult<()> {
+        match action {
+            // Where the mines are is known by now, so a retry isn't ranked
+            EndAction::Retry => match self.retry_fields() {
+                Some(fields) => self.start_over(fields, None)?,
+                None => self.toast(locale::tr("toast-board-invalid").to_string()),
+            },
+            EndAction::NewBoard => {
+                let field = &self.engine().field;
+                match self.new_fields(field.size(), field.mine_count(), &mut thread_rng()) {
+                    Some(fields) => self.start_over(fields, self.preset)?,
+                    None => self.toast(locale::tr("toast-board-invalid").to_string()),
+                }
+            },
+            EndAction::Review => {
+                self.reviewing = !self.reviewing;
+                let mistakes = self.boards.iter().map(|board| board.engine.field.mistakes().len()).sum::<usize>();
+                if self.reviewing {
+                    self.toast(match mistakes {
+                        0 => locale::tr("toast-no-mistakes").to_string(),
+                        count => locale::tr_with("toast-mistakes", &[("count", &count)]),
+                    });
+                }
+            },
+            EndAction::Share => self.share(),
+            EndAction::Back => self.back = true,
+        }
+        Ok(())
+    }
+
+    /// Save a summary of the game as text to the current directory, to paste wherever it's to be shared. Failing to
+    /// save doesn't end the game; it's only reported.
+    fn share(&mut self) {
+        let path = format!(
+            "termsweeper-{}.txt",
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+        );
+        let field = &self.engine().field;
+        let header = locale::tr_with("share-header", &[("board", &self.board_name()), ("mines", &field.mine_count())]);
+        let elapsed = self.engine().elapsed();
+        let time = format!("{}.{}", format_time(elapsed), elapsed.subsec_millis() / 100);
+        let result = if self.won() {
+            locale::tr_with("share-won", &[("time", &time), ("bbbv", &field.bbbv()), ("clicks", &self.clicks)])
+        } else {
+            let progress = format!("{:.0}", self.engine().progress() * 100.0);
+            locale::tr_with("share-lost", &[("progress", &progress), ("time", &time), ("clicks", &self.clicks)])
+        };
+        self.toast(match std::fs::write(&path, format!("{}\n{}\n", header, result)) {
+            Ok(()) => locale::tr_with("toast-share-saved", &[("path", &path)]),
+            Err(e) => locale::tr_with("toast-share-failed", &[("error", &e)]),
+        });
+    }
+
+    /// Carry out a line typed at the `:` prompt. Anything that can't be done is reported, and the game carries on.
+    fn run_line(&mut self, line: &str) -> IoResult<()> {
+        let line = match command_line::parse(line) {
+            Ok(line) => line,
+            Err(e) => {
+                self.toast(locale::tr_with("toast-command-failed", &[("error", &e)]));
+                return Ok(());
+            },
+        };
+        match line {
+            Line::New { .. } if !self.can_start_over() => self.toast(locale::tr("toast-cant-start-over").to_string()),
+            Line::New { board, seed } => {
+                let field = &self.engine().field;
+                let (size, mines) = match board {
+                    // Typed sizes are kept to those the custom game menu allows
+                    Some(((rows, cols), mines)) => {
+                        let fit = |n: usize, range: RangeInclusive<usize>| n.clamp(*range.start(), *range.end());
+                        let size = (fit(rows, CustomGame::ROWS), fit(cols, CustomGame::COLUMNS));
+                        if size != (rows, cols) {
+                            self.toast(locale::tr_with("toast-board-size-clamped", &[
+                                ("rows", &size.0), ("columns", &size.1),
+                            ]));
+                        }
+                        (size, mines)
+                    },
+                    None => (field.size(), field.mine_count()),
+                };
+                let fields = match seed {
+                    Some(seed) => self.new_fields(size, mines, &mut StdRng::seed_from_u64(seed)),
+                    None => self.new_fields(size, mines, &mut thread_rng()),
+                };
+                // A board of another size isn't the preset any more, and one from a chosen seed can be looked up
+                let preset = self.preset.filter(|_| board.is_none() && seed.is_none());
+                match fields {
+                    Some(fields) => self.start_over(fields, preset)?,
+                    None => self.toast(locale::tr("toast-board-invalid").to_string()),
+                }
+            },
+            Line::Move(Command::Reveal(pos) | Command::Flag(pos) | Command::Chord(pos))
+                if self.engine().field.cell(pos).is_none() =>
+            {
+                self.toast(locale::tr("toast-off-the-board").to_string());
+            },
+            Line::Move(command) if !self.ended() && !self.computer_turn() && !self.watching() => {
+                self.clicks += 1;
+                self.apply(command)?;
+            },
+            Line::Move(_) => {},
+            Line::Goto(pos) => if !self.engine_mut().put_cursor(pos) {
+                self.toast(locale::tr("toast-off-the-board").to_string());
+            },
+            Line::Glyphs(set) => {
+                console::pick_glyphs(set);
+                self.config.glyphs = set;
+                // The boards' rows are cached as drawn with the old glyphs
+                for board in &self.boards {
+                    board.engine.field.rendered.borrow_mut().fill(None);
+                }
+            },
+            Line::Borders(style) => {
+                self.config.border_style = style;
+                self.config.pick_borders(self.color_depth);
+            },
+            Line::Save(name) => {
+                let path = format!("{}.txt", name);
+                self.toast(match std::fs::write(&path, board_file::write(&self.engine().field)) {
+                    Ok(()) => locale::tr_with("toast-board-saved", &[("path", &path)]),
+                    Err(e) => locale::tr_with("toast-board-save-failed", &[("error", &e)]),
+                });
+            },
+            Line::Stats => {
+                let (_, rows) = self.help_size();
+                let mut stats = Scroll::new(tui::stats_panel(&Stats::load()?).into_lines(), rows);
+                stats.resize(rows);
+                self.stats = Some(stats);
+            },
+        }
+        Ok(())
+    }
+
+    /// Whether the game can start over on a new board. Only a single-player game of its own can: the rest are tied to
+    /// the board they were started on, or to someone else's game.
+    fn can_start_over(&self) -> bool {
+        !self.playback
+            && self.bot.is_none()
+            && self.puzzle.is_none()
+            && self.ladder.is_none()
+            && self.daily.is_none()
+            && self.engine().versus.is_none()
+    }
+
+    /// The boards as they were when the game started, to play them again. Returns None if one of them no longer reads
+    /// back.
+    fn retry_fields(&self) -> Option<Vec<Field>> {
+        let topology = self.engine().field.topology();
+        self.layouts.iter()
+            .map(|layout| {
+                let field = board_file::parse(layout).ok()?;
+                if topology == Topology::Square {
+                    return Some(field);
                 }
-                KeyCode::Char('q') => return Ok(false),
-                _ => { },
+                // The board file format only keeps the cells, so they're fitted together again as they were
+                let (rows, cols) = field.size();
+                let mines = (0..rows)
+                    .flat_map(|row| (0..cols).map(move |col| (row, col)))
+                    .filter(|&(row, col)| field.board[row][col].is_mine())
+                    .collect::<Vec<_>>();
+                Field::with_topology(topology, field.size(), &mines)
+            })
+            .collect()
+    }
+
+    /// As many new boards as there are now, of the given size and mines, and with the same topology, laid out with the
+    /// given random number generator. Returns None if they don't fit.
+    fn new_fields(&self, size: (usize, usize), mines: usize, rng: &mut impl Rng) -> Option<Vec<Field>> {
+        if size.0 == 0 || size.1 == 0 || mines > (size.0 * size.1).div_ceil(2) {
+            return None;
+        }
+        let topology = self.engine().field.topology();
+        (0..self.boards.len())
+            .map(|_| Field::with_topology(topology, size, &generator::Uniform.place_mines(size, mines, rng)?))
+            .collect()
+    }
+
+    /// Start over on new boards, as a new game by the same rules, on the given preset (if the boards are one).
+    /// Everything about the old boards goes with them. Any recording starts over too, on the new board.
+    fn start_over(&mut self, fields: Vec<Field>, preset: Option<Preset>) -> IoResult<()> {
+        self.preset = preset;
+        self.adaptive_level = None;
+        self.layouts = fields.iter().map(board_file::write).collect();
+        self.boards = fields.into_iter()
+            .zip(&self.boards)
+            .map(|(field, board)| board.rematch(field, thread_rng().gen()))
+            .collect();
+        if let Some((_, replay)) = &mut self.recording {
+            *replay = Self::replay(&self.boards[0].engine);
+        }
+        self.focus = 0;
+        self.clicks = 0;
+        self.flags = 0;
+        self.death = None;
+        self.changes.clear();
+        self.pings.clear();
+        self.played = Duration::ZERO;
+        self.show_frontier = false;
+        self.end_menu = None;
+        self.reviewing = false;
+        #[cfg(feature = "graphics")]
+        self.take_down_pictures()?;
+        // The new board may well be a different size, so redraw everything
+        self.last_frame.clear();
+        queue!(self.frame, Clear(ClearType::All))
+    }
+
+    /// Carry out a command on the focused board, recording it if there's a replay being recorded, then any moves the
+    /// assists make because of it.
+    pub(crate) fn apply(&mut self, command: Command) -> IoResult<()> {
+        if let Command::Reveal(_) | Command::Chord(_) = command {
+            self.last_move = Instant::now();
+        }
+        // Only the latest move is highlighted, though moving the cursor doesn't count
+        if !matches!(command, Command::Move(_)) {
+            self.changes.clear();
+        }
+        if self.run(command) && self.assisted() {
+            self.assist();
+        }
+        self.handle_game_events()
+    }
+
+    /// Record a command and carry it out. Returns true if it changed anything.
+    fn run(&mut self, command: Command) -> bool {
+        if let Some((_, replay)) = &mut self.recording {
+            replay.record(self.played, command);
+        }
+        self.engine_mut().apply(command)
+    }
+
+    /// Returns true if the assists can make moves in this game: not in versus mode, where the moves would be taken
+    /// on someone's turn, nor while a replay (which already has them) plays back, nor with noisy numbers, where the
+    /// solver would go by numbers the player can't see.
+    fn assisted(&self) -> bool {
+        self.config.assist != AssistConfig::default()
+            && !self.playback
+            && self.engine().versus.is_none()
+            && self.engine().noisy_numbers.is_none()
+    }
+
+    /// Flag and reveal whatever the assists are on for, until the solver finds nothing more to do.
+    fn assist(&mut self) {
+        let AssistConfig { flag, reveal } = self.config.assist;
+        loop {
+            let field = &self.engine().field;
+            let analysis = solver::analyze_cached(field);
+            let unflagged = |&&(row, col): &&(usize, usize)| !field.board[row][col].is_flagged();
+            let mut commands = Vec::new();
+            if flag {
+                commands.extend(analysis.mines().iter().filter(unflagged).map(|&pos| Command::Flag(pos)));
             }
-            Event::Resize(width, height) => self.terminal_size = (width, height),
-            _ => { },
+            if reveal {
+                commands.extend(analysis.safe().iter().filter(unflagged).map(|&pos| Command::Reveal(pos)));
+            }
+            // A reveal can flood over cells found safe alongside it, so not every command does something
+            let mut changed = false;
+            for command in commands {
+                changed |= self.run(command);
+            }
+            if !changed || self.ended() {
+                break;
+            }
+        }
+    }
+
+    /// React to what happened in the engine. The TUI follows the game through the same events as any other subscriber.
+    fn handle_game_events(&mut self) -> IoResult<()> {
+        let mut finished = false;
+        for board in 0..self.boards.len() {
+            while let Ok(event) = self.boards[board].events.try_recv() {
+                if let Some(out) = &mut self.json_events {
+                    let elapsed_ms = self.boards[board].engine.elapsed().as_millis() as u64;
+                    let line = serde_json::to_string(&EventLine { board, elapsed_ms, event })?;
+                    writeln!(out, "{}", line)?;
+                    out.flush()?;
+                }
+                if let GameEvent::CellRevealed { pos, .. } | GameEvent::CellFlagged { pos, .. } = event {
+                    if self.config.highlight_changes {
+                        self.changes.push(Change { board, pos: (pos.1 as u16, pos.0 as u16), made: Instant::now() });
+                    }
+                }
+                match event {
+                    GameEvent::CellFlagged { flagged: true, .. } => self.flags += 1,
+                    GameEvent::MineExploded { pos } => _ = self.death.get_or_insert(pos),
+                    // Notes are only for hidden cells
+                    GameEvent::CellRevealed { pos, .. } => _ = self.boards[board].notes.remove(&pos),
+                    GameEvent::Penalty { pos, time } => if let Some((_, replay)) = &mut self.recording {
+                        replay.penalty(self.played, pos, time);
+                    },
+                    GameEvent::MineMoved => self.toasts.push(Toast::new(locale::tr("toast-mine-moved").to_string())),
+                    // Flash the numbers that changed, so the move doesn't go unnoticed
+                    GameEvent::NumberChanged { pos, .. } => self.pings.push(Ping {
+                        board,
+                        pos: (pos.1 as u16, pos.0 as u16),
+                        color: Self::PULSE_COLOR,
+                        placed: Instant::now(),
+                    }),
+                    GameEvent::GameWon | GameEvent::GameLost => {
+                        finished = true;
+                        if let (GameEvent::GameWon, Some((puzzle, _))) = (event, &self.puzzle) {
+                            Progress::complete(puzzle)?;
+                            self.toasts.push(Toast::new(locale::tr("toast-puzzle-solved").to_string()));
+                        }
+                        if let Some((day, archive)) = self.daily {
+                            daily::finish(day, archive, self.won_in())?;
+                        }
+                        if self.tracked() {
+                            self.record_result()?;
+                            let (progress, won_in) = (self.engine().progress(), self.won_in());
+                            // A bookmarks file that can't be read or written is no reason to end the game
+                            if let Err(e) = Bookmarks::attempted(&self.engine().field, progress, won_in) {
+                                self.toast(locale::tr_with("toast-bookmark-update-failed", &[("error", &e)]));
+                            }
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+        if self.ended() {
+            if let Some((path, replay)) = self.recording.take() {
+                replay.save(&path)?;
+                self.toast(locale::tr_with("toast-replay-saved", &[("path", &path.display())]));
+            }
+            // Only put up the panel as the game ends, so it stays down once it's closed
+            if finished {
+                self.end_menu = Some(self.end_panel());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the game is played by the regular rules, and so goes in the stats. Challenges, constraints,
+    /// noisy numbers, multiple boards and other topologies aren't comparable with regular games, replays were already
+    /// counted, and games with an engine's or the assists' help weren't the player's alone. Nor were past days' daily
+    /// challenges, whose boards are known in advance and can be played again as often as wanted.
+    fn tracked(&self) -> bool {
+        !self.playback
+            && !self.daily.is_some_and(|(_, archive)| archive)
+            && self.bot_role.is_none()
+            && self.config.assist == AssistConfig::default()
+            && self.engine().challenge().is_none()
+            && self.engine().constraints() == Constraints::default()
+            && self.boards.len() == 1
+            && self.engine().field.topology() == Topology::Square
+            && self.engine().noisy_numbers.is_none()
+    }
+
+    /// Save the result of a finished single-player game to the stats file, and announce any achievements it earned.
+    fn record_result(&mut self) -> IoResult<()> {
+        let (rows, cols) = self.engine().field.size();
+        let record = GameRecord {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+            preset: self.preset,
+            size: (rows, cols),
+            mines: self.engine().field.mine_count(),
+            won: self.engine().won(),
+            time_ms: self.engine().elapsed().as_millis() as u64,
+            bbbv: self.engine().field.bbbv(),
+            clicks: self.clicks,
+            flags: Some(self.flags),
+            death: self.death,
+            adaptive_level: self.adaptive_level,
+            penalty_ms: self.engine().penalties().as_millis() as u64,
+            daily: self.daily.map(|(day, _)| DailyRun { date: daily::date(day) }),
+        };
+        let dropped = Stats::record(&record)?;
+        if dropped > 0 {
+            self.toast(locale::tr_with("toast-stats-damaged", &[("count", &dropped)]));
+        }
+
+        let stats = Stats::load()?;
+        for achievement in achievements::newly_unlocked(&stats.games) {
+            self.toasts.push(Toast::new(locale::tr_with("toast-achievement", &[("name", &achievement.name())])));
+        }
+        Ok(())
+    }
+
+    /// If a reveal or chord would uncover a cell the solver can prove is a mine, the question to ask before doing it.
+    /// Never with noisy numbers, since the solver knows what they really are.
+    fn mistake(&self, command: Command) -> Option<String> {
+        if self.engine().noisy_numbers.is_some() {
+            return None;
+        }
+        let (targets, question) = match command {
+            Command::Reveal(pos) => (vec![pos], "mistake-reveal"),
+            // A chord only goes wrong when one of the flags around the number is
+            Command::Chord(pos) => (self.engine().chord_targets(pos)?, "mistake-chord"),
+            _ => return None,
+        };
+        let mines = solver::analyze_cached(&self.engine().field).mines;
+        let (row, col) = targets.into_iter().find(|pos| mines.contains(pos))?;
+        Some(locale::tr_with(question, &[("row", &row), ("col", &col)]))
+    }
+
+    /// Put a note on the hidden cell under the cursor, or change or remove the one that's there.
+    fn cycle_note(&mut self) {
+        let board = &mut self.boards[self.focus];
+        let pos = board.engine.cursor();
+        let cell = board.engine.field.board[pos.0][pos.1];
+        if !cell.is_hidden() || cell.is_flagged() || cell.is_void() {
+            return;
+        }
+        match board.notes.get(&pos).map_or(Some(Note::Dot), |note| note.next()) {
+            Some(note) => board.notes.insert(pos, note),
+            None => board.notes.remove(&pos),
+        };
+    }
+
+    /// Returns true if an engine is playing the game, so the player can only watch.
+    fn watching(&self) -> bool {
+        self.bot_role == Some(BotRole::Play)
+    }
+
+    /// Ask the engine for a move, giving up on it if it can't be reached.
+    fn ask_bot(&mut self) {
+        let Some(bot) = &mut self.bot else { return };
+        if let Err(e) = bot.request(&self.boards[self.focus].engine.field) {
+            self.toast(locale::tr_with("toast-engine-unreachable", &[("error", &e)]));
+            self.bot = None;
         }
+    }
 
+    /// Play or show the engine's move, if it has sent one. Returns true if anything changed.
+    fn bot_move(&mut self) -> IoResult<bool> {
+        if self.watching()
+            && !self.ended()
+            && !self.timer.paused()
+            && self.bot.as_ref().is_some_and(|bot| !bot.pending())
+            && self.last_move.elapsed() >= Self::COMPUTER_DELAY
+        {
+            self.ask_bot();
+        }
+        let field = &self.boards[self.focus].engine.field;
+        let Some(reply) = self.bot.as_mut().and_then(|bot| bot.reply(field)) else { return Ok(false) };
+        match reply {
+            Ok(command) if self.watching() => {
+                // Moves are counted from when they're made, so the next one waits its turn too
+                self.last_move = Instant::now();
+                self.apply(command)?;
+            },
+            Ok(Command::Reveal((row, col)) | Command::Flag((row, col)) | Command::Chord((row, col))) => {
+                self.ping((col as u16, row as u16), Self::HINT_COLOR);
+            },
+            Ok(Command::Move(_)) => {},
+            Err(e) => {
+                self.toast(e);
+                self.bot = None;
+            },
+        }
         Ok(true)
     }
 
-    fn move_cursor(&mut self, pos: (u16, u16)) {
-        if pos.0 >= self.field_loc.0
-            && pos.1 >= self.field_loc.1
-            && pos.0 < self.field_loc.0 + self.field.width() as u16
-            && pos.1 < self.field_loc.1 + self.field.height() as u16
+    fn computer_turn(&self) -> bool {
+        self.engine().versus.as_ref().is_some_and(|versus| versus.current().computer.is_some())
+    }
+
+    /// Make the computer opponent's move, pinging the cell it chose so human players can see it.
+    fn computer_move(&mut self) -> IoResult<()> {
+        let Some(versus) = &self.engine().versus else { return Ok(()) };
+        let (Some(difficulty), color) = (versus.current().computer, versus.current().color) else { return Ok(()) };
+        if let Some((row, col)) = ai::choose_move(&self.engine().field, versus.rules(), difficulty, &mut thread_rng()) {
+            let pos = (col as u16, row as u16);
+            self.ping(pos, color);
+            self.apply(Command::Reveal((row, col)))?;
+        }
+        Ok(())
+    }
+
+    /// Highlight a cell in the given player's color for [`Ping::DURATION`]. Each player (identified by their color)
+    /// has at most one active ping, so pinging again moves it.
+    pub(crate) fn ping(&mut self, pos: (u16, u16), color: Color) {
+        self.pings.retain(|ping| ping.color != color);
+        self.pings.push(Ping { board: self.focus, pos, color, placed: Instant::now() });
+    }
+
+    /// How long the main loop should wait for input before calling [`Game::tick`], or None to wait for as long as it
+    /// takes. In low-power mode it's only as long as nothing on screen has to change by itself.
+    pub fn tick_rate(&self) -> Option<Duration> {
+        let rate = if self.config.slow_link { Self::SLOW_TICK_RATE } else { Self::TICK_RATE };
+        if !self.config.low_power {
+            return Some(rate);
+        }
+        self.next_change(rate)
+    }
+
+    /// Returns true if events that come in together should be drawn as one frame, in low-power mode.
+    pub(crate) fn coalesce_events(&self) -> bool {
+        self.config.low_power
+    }
+
+    /// How long until something on screen next changes by itself: a tick away while anything moves or a computer or
+    /// engine is playing, else when the clock shows another second or the next toast or highlight goes. A watched
+    /// config file is checked on the slow tick. None if nothing will until there's input.
+    fn next_change(&self, rate: Duration) -> Option<Duration> {
+        let mut moving = !self.config.reduced_motion() && (!self.changes.is_empty() || self.boss.is_some());
+        moving |= self.computer_turn() || self.bot.is_some();
+        if moving {
+            return Some(rate);
+        }
+        let second = Duration::from_secs(1);
+        let clock = (self.running() && !self.timer.paused())
+            .then(|| second - Duration::from_nanos(self.engine().elapsed().subsec_nanos() as u64));
+        let pings = self.pings.iter().map(|ping| Ping::DURATION.saturating_sub(ping.placed.elapsed()));
+        let toasts = self.toasts.iter().map(|toast| Toast::DURATION.saturating_sub(toast.shown.elapsed()));
+        let fade = Change::STEP * Change::FADE.len() as u32;
+        let changes = self.changes.iter().map(|change| fade.saturating_sub(change.made.elapsed()));
+        #[cfg(feature = "hot-reload")]
+        let watch = self.config_watcher.is_some().then_some(Self::SLOW_TICK_RATE);
+        #[cfg(not(feature = "hot-reload"))]
+        let watch = None;
+        clock.into_iter().chain(watch).chain(pings).chain(toasts).chain(changes).min()
+    }
+
+    /// Update timed state. Returns a bool signifying whether anything changed and the game should be re-rendered.
+    pub(crate) fn tick(&mut self) -> IoResult<bool> {
+        let shown = self.engine().elapsed().as_secs();
+        self.advance();
+        self.handle_game_events()?;
+        let count = self.pings.len() + self.toasts.len() + self.changes.len();
+        self.pings.retain(|ping| ping.placed.elapsed() < Ping::DURATION);
+        self.toasts.retain(|toast| toast.shown.elapsed() < Toast::DURATION);
+        self.changes.retain(|change| change.made.elapsed() < Change::STEP * Change::FADE.len() as u32);
+        let count_changed = count != self.pings.len() + self.toasts.len() + self.changes.len();
+        let mut changed = count_changed || shown != self.engine().elapsed().as_secs();
+        // Fading highlights change color every few ticks, until they're gone, and the boss screen's log scrolls
+        if !self.config.reduced_motion() {
+            changed |= !self.changes.is_empty();
+            changed |= self.boss.as_mut().is_some_and(BossScreen::tick);
+        }
+        #[cfg(feature = "hot-reload")]
+        if self.config_watcher.as_ref().is_some_and(ConfigWatcher::changed) {
+            self.reload_config();
+            changed = true;
+        }
+
+        if !self.ended()
+            && !self.timer.paused()
+            && self.computer_turn()
+            && self.last_move.elapsed() >= Self::COMPUTER_DELAY
         {
-            self.cursor.0 = pos.0 - self.field_loc.0;
-            self.cursor.1 = pos.1 - self.field_loc.1;
+            self.computer_move()?;
+            changed = true;
         }
+        changed |= self.bot_move()?;
+
+        Ok(changed)
     }
 
-    fn step_cursor(&mut self, direction: Direction) {
-        let new_pos = direction.offset(self.cursor);
-        if new_pos.0 < self.field.width() as u16 && new_pos.1 < self.field.height() as u16 {
-            self.cursor = new_pos;
+    #[allow(dead_code)]
+    fn move_cursor(&mut self, pos: (u16, u16)) {
+        let loc = self.board_loc(self.focus);
+        if pos.0 >= loc.0
+            && pos.1 >= loc.1
+            && pos.0 < loc.0 + self.engine().field.width() as u16
+            && pos.1 < loc.1 + self.engine().field.height() as u16
+        {
+            self.engine_mut().cursor = ((pos.1 - loc.1) as usize, (pos.0 - loc.0) as usize);
         }
     }
+
 }
 
 impl Drop for Game {
@@ -116,135 +1855,551 @@ impl Drop for Game {
                 if panicking() {
                     let _ = writeln!(stderr(), "{}", e);
                 } else {
-                    Err::<(), std::io::Error>(e).unwrap();
+                    panic!("{}", e);
                 }
             }
         }
     }
 }
 
-enum Direction {
+/// A temporary "look here" marker on a cell, drawn in the color of the player who placed it.
+struct Ping {
+    /// Which board the cell is on.
+    board: usize,
+    pos: (u16, u16),
+    color: Color,
+    placed: Instant,
+}
+
+impl Ping {
+    const DURATION: Duration = Duration::from_secs(3);
+}
+
+/// A cell changed by the last move, highlighted for a moment afterwards.
+struct Change {
+    /// Which board the cell is on.
+    board: usize,
+    pos: (u16, u16),
+    made: Instant,
+}
+
+impl Change {
+    /// The highlight's colors as it fades, each shown for a step.
+    const FADE: [Color; 4] = [
+        Color::Rgb { r: 90, g: 90, b: 150 },
+        Color::Rgb { r: 70, g: 70, b: 115 },
+        Color::Rgb { r: 50, g: 50, b: 80 },
+        Color::Rgb { r: 35, g: 35, b: 50 },
+    ];
+    const STEP: Duration = Duration::from_millis(200);
+
+    /// The highlight's color by now: the first one throughout, with reduced motion.
+    fn color(&self, reduced_motion: bool) -> Color {
+        if reduced_motion {
+            return Self::FADE[0];
+        }
+        let step = (self.made.elapsed().as_millis() / Self::STEP.as_millis()) as usize;
+        Self::FADE[step.min(Self::FADE.len() - 1)]
+    }
+}
+
+/// One of the boards in a game, with the TUI's subscription to its engine.
+struct Board {
+    engine: GameEngine,
+    /// What happened in the engine since the TUI last looked.
+    events: Receiver<GameEvent>,
+    /// The player's notes on hidden cells, by (row, col).
+    notes: BTreeMap<(usize, usize), Note>,
+}
+
+impl Board {
+    fn new(field: Field, wrap_cursor: bool) -> Self {
+        let mut engine = GameEngine::new(field);
+        engine.wrap_cursor = wrap_cursor;
+        Self::with_engine(engine)
+    }
+
+    fn with_engine(mut engine: GameEngine) -> Self {
+        Self { events: engine.subscribe(), engine, notes: BTreeMap::new() }
+    }
+
+    /// A new board for the given field, played by the same rules as this one.
+    fn rematch(&self, field: Field, seed: u64) -> Self {
+        Self::with_engine(self.engine.rematch(field, seed))
+    }
+}
+
+/// A marker the player can put on a hidden cell while reasoning about it. Unlike a flag it means whatever the player
+/// wants, and the game ignores it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Note {
+    Dot,
+    Question,
+}
+
+impl Note {
+    fn glyph(self) -> char {
+        match self {
+            Note::Dot => console::glyphs().note,
+            Note::Question => '?',
+        }
+    }
+
+    /// The note after this one when cycling through them, or None to take it off.
+    fn next(self) -> Option<Self> {
+        match self {
+            Note::Dot => Some(Note::Question),
+            Note::Question => None,
+        }
+    }
+}
+
+/// A short-lived message shown below the rest of the screen.
+struct Toast {
+    text: Text,
+    shown: Instant,
+    /// Whether it says which tab the game is in, which goes out of date as soon as tabs are opened or closed.
+    tab: bool,
+}
+
+impl Toast {
+    const DURATION: Duration = Duration::from_secs(5);
+
+    fn new(text: String) -> Self {
+        Self { text: Text::new(vec![text]), shown: Instant::now(), tab: false }
+    }
+}
+
+/// A direction to move in on the board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub enum Direction {
     Left,
     Right,
     Up,
     Down,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    /// To the same cell on the layer above, on a layered board.
+    PrevLayer,
+    /// To the same cell on the layer below, on a layered board.
+    NextLayer,
 }
 
 impl Direction {
-    fn offset(&self, pos: (u16, u16)) -> (u16, u16) {
+    /// The change in (x, y) for one step in this direction.
+    pub(crate) fn delta(&self) -> (i16, i16) {
         match self {
-            Direction::Left => (if pos.0 > 0 { pos.0 - 1 } else { 0 }, pos.1),
-            Direction::Right => (pos.0 + 1, pos.1),
-            Direction::Up => (pos.0, if pos.1 > 0 { pos.1 - 1 } else { 0 }),
-            Direction::Down => (pos.0, pos.1 + 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+            Direction::PrevLayer | Direction::NextLayer => (0, 0),
         }
     }
+
+    /// One step from a (row, col) position within a layer. Returns None if the step would go past the top or left edge.
+    pub(crate) fn offset(&self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy) = self.delta();
+        Some((pos.0.checked_add_signed(dy as isize)?, pos.1.checked_add_signed(dx as isize)?))
+    }
 }
 
+/// A minesweeper board.
 #[derive(Debug)]
-pub(crate) struct Field {
+pub struct Field {
     pub(crate) board: Vec<Vec<Cell>>,
+    pub(crate) topology: Topology,
+    /// Each row as rendered text, cached until a cell in it changes so big boards don't have to be stringified cell by
+    /// cell every frame.
+    pub(crate) rendered: RefCell<Vec<Option<String>>>,
+    /// The solver's deductions, kept up to date as cells change so they don't have to be made from scratch each move.
+    pub(crate) solver: RefCell<solver::Cache>,
+    /// With noisy numbers, the range each revealed number is shown as, as (low, high) by (row, col). None for a board
+    /// that shows its numbers as they are.
+    pub(crate) ranges: Option<BTreeMap<(usize, usize), (u8, u8)>>,
+}
+
+/// The result of revealing a cell.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Reveal {
+    /// Whether the revealed cell was a mine.
+    pub exploded: bool,
+    /// Every cell that was revealed (more than one if it flood filled), as (row, col).
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// How the flags on a board compare with its mines.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FlagReport {
+    /// Flags on mines.
+    pub correct: usize,
+    /// Flags on safe cells.
+    pub wrong: usize,
+    /// Mines with no flag on them, not counting any that went off.
+    pub unflagged: usize,
 }
 
 impl Field {
     /// Returns None if either dimension was zero, or too many mines were specified than can (reasonably)
     /// fit on the board.
-    pub(crate) fn new(size: (usize, usize), mines: usize) -> Option<Self> {
-        if size.0 == 0 || size.1 == 0 || mines > (size.0 * size.1 + 1) / 2 {
+    pub fn new(size: (usize, usize), mines: usize) -> Option<Self> {
+        Self::with_rng(size, mines, &mut thread_rng())
+    }
+
+    /// Like [`Field::new`], but placing mines using the given random number generator, so that a seeded generator gives
+    /// the same board every time.
+    pub fn with_rng(size: (usize, usize), mines: usize, rng: &mut impl Rng) -> Option<Self> {
+        generator::Uniform.generate(size, mines, rng)
+    }
+
+    /// A board with mines at exactly the given (row, col) positions. Returns None if either dimension was zero, or a
+    /// mine is off the board or listed twice.
+    pub fn from_mines(size: (usize, usize), mines: &[(usize, usize)]) -> Option<Self> {
+        Self::with_topology(Topology::Square, size, mines)
+    }
+
+    /// Like [`Field::from_mines`], but with cells that fit together in the given way. Also returns None if the board
+    /// size doesn't fit the topology.
+    pub fn with_topology(topology: Topology, size: (usize, usize), mines: &[(usize, usize)]) -> Option<Self> {
+        if size.0 == 0 || size.1 == 0 || !topology.fits(size) {
             return None;
         }
 
         let mut board = vec![vec![Cell::default(); size.1]; size.0];
 
-        let mut rng = thread_rng();
-        let row_d = Uniform::new(0, size.0);
-        let col_d = Uniform::new(0, size.1);
-
-        let mut placed_mines = 0;
-        while placed_mines < mines {
-            let mine_row = rng.sample(row_d);
-            let mine_col = rng.sample(col_d);
-            if board[mine_row][mine_col].mine {
-                continue;
+        for &(mine_row, mine_col) in mines {
+            if board.get(mine_row)?.get(mine_col)?.mine {
+                return None;
             }
 
             board[mine_row][mine_col].mine = true;
+            for (row, col) in topology.neighbors(size, (mine_row, mine_col)) {
+                board[row][col].neighbors += 1;
+            }
+        }
 
-            let top_edge = mine_row == 0;
-            let bottom_edge = mine_row == board.len() - 1;
-            let left_edge = mine_col == 0;
-            let right_edge = mine_col == board[0].len() - 1;
+        let rendered = RefCell::new(vec![None; board.len()]);
+        Some(Self { rendered, solver: RefCell::default(), ranges: None, board, topology })
+    }
+
+    /// Returns what the reveal uncovered. Returns None if the given cell has already been cleared or
+    /// flagged, or if the given cell is invalid.
+    pub fn clear_cell(&mut self, pos: (usize, usize)) -> Option<Reveal> {
+        let status = self.board.get_mut(pos.0)?
+            .get_mut(pos.1)?
+            .reveal()?;
+        self.invalidate(pos);
+        let mut reveal = Reveal { exploded: false, cells: vec![pos] };
+        match status {
+            RevealStatus::Exploded => {
+                reveal.exploded = true;
+                return Some(reveal);
+            },
+            RevealStatus::Safe => return Some(reveal),
+            RevealStatus::Empty => {}
+        }
 
-            if !left_edge && !top_edge { board[mine_row - 1][mine_col - 1].neighbors += 1 }
-            if !left_edge { board[mine_row][mine_col - 1].neighbors += 1 }
-            if !left_edge && !bottom_edge { board[mine_row + 1][mine_col - 1].neighbors += 1 }
-            if !bottom_edge { board[mine_row + 1][mine_col].neighbors += 1 }
-            if !right_edge && !bottom_edge { board[mine_row + 1][mine_col + 1].neighbors += 1 }
-            if !right_edge { board[mine_row][mine_col + 1].neighbors += 1 }
-            if !right_edge && !top_edge { board[mine_row - 1][mine_col + 1].neighbors += 1 }
-            if !top_edge { board[mine_row - 1][mine_col].neighbors += 1 }
+        let mut check = self.neighbors(pos).collect::<Vec<_>>();
 
-            placed_mines += 1;
+        while let Some((next_row, next_col)) = check.pop() {
+            let status = self.board[next_row][next_col].reveal();
+            if status.is_some() {
+                reveal.cells.push((next_row, next_col));
+                self.invalidate((next_row, next_col));
+            }
+            if matches!(status, Some(RevealStatus::Empty)) {
+                check.extend(self.neighbors((next_row, next_col)));
+            }
         }
 
-        Some(Self { board })
+        Some(reveal)
     }
 
-    /// Returns a bool signifying if a mine has exploded. Returns None if the given cell has already
-    /// been cleared or flagged, or if the given cell is invalid.
-    pub(crate) fn clear_cell(&mut self, pos: (usize, usize)) -> Option<bool> {
-        match self.board.get_mut(pos.0)?
-            .get_mut(pos.1)?
-            .reveal()?
-        {
-            RevealStatus::Exploded => return Some(true),
-            RevealStatus::Safe => return Some(false),
-            RevealStatus::Empty => {}
+    /// Mark a cell as changed, so its row gets rendered again and the solver looks at it again.
+    fn invalidate(&self, pos: (usize, usize)) {
+        self.solver.borrow_mut().changed(pos);
+        if let Some(row) = self.rendered.borrow_mut().get_mut(pos.0) {
+            *row = None;
         }
+    }
 
-        let mut check = Vec::new();
+    /// The board size, as (rows, columns).
+    pub fn size(&self) -> (usize, usize) {
+        (self.board.len(), self.board.first().map_or(0, Vec::len))
+    }
+
+    /// Returns None if the position is out of bounds.
+    pub fn cell(&self, pos: (usize, usize)) -> Option<&Cell> {
+        self.board.get(pos.0)?.get(pos.1)
+    }
 
-        fn add_neighbors(check: &mut Vec<(usize, usize)>, board_size: (usize, usize), pos: (usize, usize)) {
-            let top_edge = pos.0 == 0;
-            let bottom_edge = pos.0 == board_size.0 - 1;
-            let left_edge = pos.1 == 0;
-            let right_edge = pos.1 == board_size.1 - 1;
+    /// The number of mines on the board, found or not.
+    pub fn mine_count(&self) -> usize {
+        self.board.iter()
+            .flatten()
+            .filter(|cell| cell.mine)
+            .count()
+    }
 
-            if !left_edge && !top_edge { check.push((pos.0 - 1, pos.1 - 1)) }
-            if !left_edge { check.push((pos.0, pos.1 - 1)) }
-            if !left_edge && !bottom_edge { check.push((pos.0 + 1, pos.1 - 1)) }
-            if !bottom_edge { check.push((pos.0 + 1, pos.1)) }
-            if !right_edge && !bottom_edge { check.push((pos.0 + 1, pos.1 + 1)) }
-            if !right_edge { check.push((pos.0, pos.1 + 1)) }
-            if !right_edge && !top_edge { check.push((pos.0 - 1, pos.1 + 1)) }
-            if !top_edge { check.push((pos.0 - 1, pos.1)) }
+    /// Check every flag against the mines.
+    pub fn flag_report(&self) -> FlagReport {
+        let mut report = FlagReport::default();
+        for cell in self.board.iter().flatten() {
+            match (cell.mine, cell.state) {
+                (true, CellState::Flagged) => report.correct += 1,
+                (false, CellState::Flagged) => report.wrong += 1,
+                (true, CellState::Exploded) => {},
+                (true, _) => report.unflagged += 1,
+                (false, _) => {},
+            }
         }
+        report
+    }
+
+    /// The cells the player got wrong, as (row, col): the mine that went off, and any flags on safe cells.
+    pub(crate) fn mistakes(&self) -> Vec<(usize, usize)> {
+        let cells = self.board.iter().enumerate()
+            .flat_map(|(row, cells)| cells.iter().enumerate().map(move |(col, cell)| ((row, col), cell)));
+        cells
+            .filter(|(_, cell)| {
+                matches!((cell.mine, cell.state), (true, CellState::Exploded) | (false, CellState::Flagged))
+            })
+            .map(|(pos, _)| pos)
+            .collect()
+    }
 
-        add_neighbors(&mut check, (self.board.len(), self.board[0].len()), pos);
+    /// Returns true if every safe cell has been revealed.
+    pub fn is_cleared(&self) -> bool {
+        self.board.iter()
+            .flatten()
+            .all(|cell| cell.mine || matches!(cell.state, CellState::Revealed | CellState::Empty | CellState::Void))
+    }
+
+    /// The 3BV (Bechtel's Board Benchmark Value) of the field: the minimum number of clicks needed to clear it without
+    /// flagging. Each opening counts once, plus each safe cell that isn't on the edge of an opening.
+    pub(crate) fn bbbv(&self) -> usize {
+        let (rows, cols) = self.size();
+        let mut seen = vec![vec![false; cols]; rows];
+        let mut count = 0;
 
-        while !check.is_empty() {
-            let (next_row, next_col) = check.pop().expect("We shouldn't have started another iteration if the stack is empty");
-            if matches!(self.board[next_row][next_col].reveal(), Some(RevealStatus::Empty)) {
-                add_neighbors(&mut check, (self.board.len(), self.board[0].len()), (next_row, next_col));
+        for (row, cells) in self.board.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.mine || cell.neighbors != 0 || cell.is_void() || seen[row][col] {
+                    continue;
+                }
+                count += 1;
+                seen[row][col] = true;
+                let mut check = vec![(row, col)];
+                while let Some(pos) = check.pop() {
+                    for (r, c) in self.neighbors(pos) {
+                        if !seen[r][c] && !self.board[r][c].is_void() {
+                            seen[r][c] = true;
+                            if self.board[r][c].neighbors == 0 {
+                                check.push((r, c));
+                            }
+                        }
+                    }
+                }
             }
         }
 
-        Some(false)
+        count + self.board.iter()
+            .flatten()
+            .zip(seen.iter().flatten())
+            .filter(|(cell, seen)| !cell.mine && !cell.is_void() && !**seen)
+            .count()
+    }
+
+    /// How the board's cells fit together.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The in-bounds neighbors of a cell, as (row, col).
+    pub fn neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.topology.neighbors(self.size(), pos)
+    }
+
+    /// Where a cell is drawn, as (x, y) from the top left of the board.
+    pub(crate) fn screen_pos(&self, pos: (usize, usize)) -> (u16, u16) {
+        let (x, y) = self.topology.screen_pos(self.size(), pos);
+        ((x * self.cell_width()) as u16, y as u16)
+    }
+
+    /// How many columns each cell is drawn in with noisy numbers: two for a range's lowest and highest numbers, and a
+    /// space so that ranges next to each other can be told apart.
+    pub(crate) const NOISY_CELL_WIDTH: usize = 3;
+
+    /// How many columns each cell is drawn in: [`Field::NOISY_CELL_WIDTH`] with noisy numbers, to fit the ranges, or
+    /// else one.
+    pub(crate) fn cell_width(&self) -> usize {
+        if self.ranges.is_some() { Self::NOISY_CELL_WIDTH } else { 1 }
+    }
+
+    /// Draw a single cell, [`Field::cell_width`] columns wide. With noisy numbers, a number's range is drawn as its
+    /// lowest and highest numbers, hidden cells fill two columns and everything else one, each followed by a space.
+    pub(crate) fn glyph(&self, pos: (usize, usize)) -> String {
+        let cell = &self.board[pos.0][pos.1];
+        let glyph = self.topology.glyph(pos, cell);
+        let Some(ranges) = &self.ranges else { return glyph };
+        match ranges.get(&pos).filter(|_| cell.state == CellState::Revealed) {
+            Some(&(low, high)) if low != high => format!("{}{} ", Cell::number_char(low), Cell::number_char(high)),
+            _ if cell.state == CellState::Unrevealed => format!("{} ", glyph.repeat(2)),
+            _ => format!("{}  ", glyph),
+        }
+    }
+
+    /// With noisy numbers, the range a revealed number is shown as, as (low, high): the number is somewhere in it.
+    /// None for a cell that hasn't been revealed, or a board that shows its numbers as they are.
+    pub fn shown_range(&self, pos: (usize, usize)) -> Option<(u8, u8)> {
+        let range = self.ranges.as_ref()?.get(&pos)?;
+        (self.cell(pos)?.state == CellState::Revealed).then_some(*range)
+    }
+
+    /// Set the range a revealed number is shown as, with noisy numbers.
+    pub(crate) fn set_range(&mut self, pos: (usize, usize), range: (u8, u8)) {
+        if let Some(ranges) = &mut self.ranges {
+            ranges.insert(pos, range);
+            self.invalidate(pos);
+        }
+    }
+
+    /// Put a mine on a hidden cell without one, updating the numbers around it. Returns the revealed cells whose
+    /// number changed, or None if the cell is off the board, revealed, a void or already a mine.
+    pub fn add_mine(&mut self, pos: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let cell = self.cell(pos)?;
+        if cell.mine || !cell.is_hidden() {
+            return None;
+        }
+        let before = self.set_mine(pos, true);
+        Some(self.show_numbers(before))
+    }
+
+    /// Take the mine off a hidden cell, updating the numbers around it. Returns the revealed cells whose number
+    /// changed, or None if the cell is off the board, revealed or not a mine.
+    pub fn remove_mine(&mut self, pos: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let cell = self.cell(pos)?;
+        if !cell.mine || !cell.is_hidden() {
+            return None;
+        }
+        let before = self.set_mine(pos, false);
+        Some(self.show_numbers(before))
+    }
+
+    /// Count the mines around each of the given (row, col) cells again, for when mines were changed without keeping
+    /// the numbers up to date. Cells off the board are skipped. Returns the revealed cells whose number changed.
+    pub fn recompute_neighbors(&mut self, region: impl IntoIterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        let mut before = Vec::new();
+        for pos in region {
+            let Some(cell) = self.cell(pos) else { continue };
+            before.push((pos, cell.neighbors));
+            let count = self.neighbors(pos).filter(|&(row, col)| self.board[row][col].mine).count();
+            self.board[pos.0][pos.1].neighbors = count as u8;
+        }
+        self.show_numbers(before)
+    }
+
+    /// Move a hidden mine to a hidden cell without one, updating the neighbor counts around both. Flagged cells can't be
+    /// moved from or to. Returns the revealed cells whose number changed, or None if the move wasn't possible.
+    pub(crate) fn move_mine(&mut self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let movable = |cell: &Cell, mine: bool| cell.mine == mine && cell.state == CellState::Unrevealed;
+        if !movable(self.cell(from)?, true) || !movable(self.cell(to)?, false) {
+            return None;
+        }
+        let mut before = self.set_mine(from, false);
+        before.extend(self.set_mine(to, true));
+        Some(self.show_numbers(before))
+    }
+
+    /// Set whether a cell is a mine, counting it in or out of the numbers around it. Returns the cells around it, with
+    /// the numbers they had before.
+    fn set_mine(&mut self, pos: (usize, usize), mine: bool) -> Vec<((usize, usize), u8)> {
+        // What the solver deduced from the old numbers may not hold for the new ones
+        self.solver.borrow_mut().reset();
+        self.board[pos.0][pos.1].mine = mine;
+        let around = self.neighbors(pos).collect::<Vec<_>>();
+        around.into_iter()
+            .map(|(row, col)| {
+                let cell = &mut self.board[row][col];
+                let before = cell.neighbors;
+                cell.neighbors = if mine { before + 1 } else { before - 1 };
+                ((row, col), before)
+            })
+            .collect()
+    }
+
+    /// Bring the revealed cells among those given (with the numbers they had before a change) up to date, so an empty
+    /// cell that gained a mine shows its number and a number that lost its last one shows as empty. Returns the
+    /// revealed cells whose number changed.
+    fn show_numbers(&mut self, before: Vec<((usize, usize), u8)>) -> Vec<(usize, usize)> {
+        let mut seen = Vec::new();
+        let mut changed = Vec::new();
+        for (pos, before) in before {
+            // A cell can come up more than once; only the first has the number it had before everything
+            if seen.contains(&pos) {
+                continue;
+            }
+            seen.push(pos);
+            self.invalidate(pos);
+            let cell = &mut self.board[pos.0][pos.1];
+            // Cells next to both ends of a move lose a mine and gain one, coming out the same
+            if cell.neighbors == before || cell.shown_number().is_none() {
+                continue;
+            }
+            cell.state = if cell.neighbors == 0 { CellState::Empty } else { CellState::Revealed };
+            changed.push(pos);
+        }
+        changed
+    }
+
+    /// Make a hidden cell without a mine a void, or a void a hidden cell again. Returns false if the cell is off the
+    /// board, or a mine or revealed.
+    pub(crate) fn set_void(&mut self, pos: (usize, usize), void: bool) -> bool {
+        let Some(cell) = self.board.get_mut(pos.0).and_then(|row| row.get_mut(pos.1)) else { return false };
+        if cell.mine || !(cell.is_void() || cell.state == CellState::Unrevealed) {
+            return false;
+        }
+        cell.state = if void { CellState::Void } else { CellState::Unrevealed };
+        self.invalidate(pos);
+        self.solver.borrow_mut().reset();
+        true
+    }
+
+    /// Cut the given (row, col) positions out of the board, leaving holes that can't be revealed or flagged and don't
+    /// need clearing. Returns None if any of them is off the board or has a mine.
+    pub(crate) fn with_voids(mut self, voids: &[(usize, usize)]) -> Option<Self> {
+        for &pos in voids {
+            if !self.set_void(pos, true) {
+                return None;
+            }
+        }
+        Some(self)
     }
 
     /// Returns a bool signifying that the flag was valid (i.e., that the cell was not already
     /// revealed). Returns None if the cell was invalid.
-    fn toggle_flag(&mut self, pos: (usize, usize)) -> Option<bool> {
-        Some(
-            self.board.get_mut(pos.0)?
-                .get_mut(pos.1)?
-                .toggle_flag()
-        )
+    pub fn toggle_flag(&mut self, pos: (usize, usize)) -> Option<bool> {
+        let valid = self.board.get_mut(pos.0)?
+            .get_mut(pos.1)?
+            .toggle_flag();
+        self.invalidate(pos);
+        Some(valid)
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub(crate) struct Cell {
+/// One cell of a [`Field`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
     state: CellState,
     neighbors: u8,
     mine: bool,
@@ -259,17 +2414,62 @@ impl Default for Cell {
 impl Display for Cell {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self.state {
-            CellState::Unrevealed => "█".to_string(),
+            CellState::Unrevealed => console::glyphs().hidden,
 //            CellState::Unrevealed => "▓".to_string(),
-            CellState::Flagged => "⚑".to_string(),
-            CellState::Revealed => self.neighbors.to_string(),
-            CellState::Exploded => "✲".to_string(),
-            CellState::Empty => "░".to_string(),
+            CellState::Flagged => console::glyphs().flag,
+            CellState::Revealed => Cell::number_char(self.neighbors),
+            CellState::Exploded => console::glyphs().exploded,
+            CellState::Empty => console::glyphs().empty,
+            CellState::Void => ' ',
         })
     }
 }
 
 impl Cell {
+    /// How a number is shown. Layered boards go up to 26, so numbers past 9 carry on as letters to stay one column
+    /// wide.
+    pub(crate) fn number_char(number: u8) -> char {
+        char::from_digit(number as u32, 36).map_or('?', |c| c.to_ascii_uppercase())
+    }
+
+    /// Whether the cell is a mine, whether or not it has been revealed.
+    pub fn is_mine(&self) -> bool {
+        self.mine
+    }
+
+    /// The number of adjacent mines, whether or not it has been revealed.
+    pub fn neighbor_count(&self) -> u8 {
+        self.neighbors
+    }
+
+    /// The neighbor count, if it has been revealed to the player.
+    pub fn shown_number(&self) -> Option<u8> {
+        match self.state {
+            CellState::Revealed | CellState::Empty => Some(self.neighbors),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the cell hasn't been revealed yet, flagged or not.
+    pub fn is_hidden(&self) -> bool {
+        matches!(self.state, CellState::Unrevealed | CellState::Flagged)
+    }
+
+    /// Returns true if the cell has a flag on it.
+    pub fn is_flagged(&self) -> bool {
+        matches!(self.state, CellState::Flagged)
+    }
+
+    /// Returns true if the cell is a hole in the board, rather than part of it.
+    pub fn is_void(&self) -> bool {
+        matches!(self.state, CellState::Void)
+    }
+
+    /// Returns true if the cell is a mine that was revealed.
+    pub fn is_exploded(&self) -> bool {
+        matches!(self.state, CellState::Exploded)
+    }
+
     /// Returns None if the cell has already been cleared or flagged.
     fn reveal(&mut self) -> Option<RevealStatus> {
         match self.state {
@@ -306,13 +2506,14 @@ impl Cell {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum CellState {
     Unrevealed, // Initial state
     Flagged,    // Flagged
     Revealed,   // Clicked on, showing a number
     Exploded,   // Clicked on, was a mine
     Empty,      // Clicked on, no mines
+    Void,       // Not part of the board
 }
 
 enum RevealStatus {
@@ -0,0 +1,297 @@
+use rand::{Rng, RngCore};
+use rand::distributions::Uniform as UniformDistribution;
+use rand::seq::SliceRandom;
+
+use crate::board::{BitBoard, Board};
+use crate::game::Field;
+use crate::solver;
+use crate::timing::{self, Step};
+
+/// Decides where the mines go on a new board. Implement [`BoardGenerator::place_mines`] to plug in a new layout; the
+/// built-in layouts are [`Uniform`], [`NoGuess`], [`Clustered`], [`Symmetric`] and [`Patterned`].
+pub trait BoardGenerator {
+    /// Pick exactly `mines` distinct (row, col) positions on a board of the given size, which is never empty and has
+    /// room for at least twice as many cells as mines. Returns None if this generator can't lay out such a board.
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>>;
+
+    /// Generate a board of the given size. Returns None if either dimension was zero, too many mines were specified
+    /// than can (reasonably) fit on the board, or the layout failed.
+    fn generate(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Field> {
+        if size.0 == 0 || size.1 == 0 || mines > (size.0 * size.1).div_ceil(2) {
+            return None;
+        }
+        Field::from_mines(size, &self.place_mines(size, mines, rng)?)
+    }
+}
+
+/// Every cell is equally likely to be a mine: the classic layout.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Uniform;
+
+impl BoardGenerator for Uniform {
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>> {
+        let mut placed = vec![vec![false; size.1]; size.0];
+        let row_d = UniformDistribution::new(0, size.0);
+        let col_d = UniformDistribution::new(0, size.1);
+
+        let mut positions = Vec::with_capacity(mines);
+        while positions.len() < mines {
+            let pos = (rng.sample(row_d), rng.sample(col_d));
+            if !placed[pos.0][pos.1] {
+                placed[pos.0][pos.1] = true;
+                positions.push(pos);
+            }
+        }
+        Some(positions)
+    }
+}
+
+/// Boards that can be cleared by logic alone, without ever having to guess. The center cell always starts revealed as
+/// an opening, and the solver can clear the rest of the board from there. Layouts are tried until one can be cleared,
+/// for about as long as ten thousand Expert boards take to check; after that the board is given up on.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoGuess;
+
+impl NoGuess {
+    /// How many cells' worth of layouts to check before giving up. Dense boards are rarely solvable by single-cell
+    /// deductions alone, and a big board takes longer to check than a small one, so big boards get fewer tries.
+    const BUDGET: usize = 10_000 * 16 * 30;
+
+    fn opening(size: (usize, usize)) -> (usize, usize) {
+        (size.0 / 2, size.1 / 2)
+    }
+
+    /// Returns true if the solver can clear the board from the opening.
+    fn solvable(size: (usize, usize), mines: &[(usize, usize)]) -> bool {
+        timing::time(Step::NoGuessCheck, || {
+            // The compact board is quicker to play out, and nothing but its cells is needed
+            let Some(mut field) = BitBoard::from_mines(size, mines) else { return false };
+            field.clear_cell(Self::opening(size));
+            loop {
+                let analysis = solver::analyze(&field);
+                if analysis.safe().is_empty() {
+                    return field.is_cleared();
+                }
+                for &pos in analysis.safe() {
+                    field.clear_cell(pos);
+                }
+            }
+        })
+    }
+
+    /// Try the layouts in order, returning the first the solver can clear.
+    #[cfg(not(feature = "parallel"))]
+    fn first_solvable(
+        size: (usize, usize),
+        mut layouts: impl Iterator<Item = Vec<(usize, usize)>>,
+    ) -> Option<Vec<(usize, usize)>> {
+        layouts.find(|layout| Self::solvable(size, layout))
+    }
+
+    /// Try the layouts a batch at a time, on every thread, returning the first the solver can clear. It's the same one
+    /// as trying them one by one would find, so a seeded generator gives the same board either way.
+    #[cfg(feature = "parallel")]
+    fn first_solvable(
+        size: (usize, usize),
+        mut layouts: impl Iterator<Item = Vec<(usize, usize)>>,
+    ) -> Option<Vec<(usize, usize)>> {
+        use rayon::prelude::*;
+
+        /// Enough to keep every thread busy, without drawing too many layouts past the one that's found.
+        const BATCH: usize = 64;
+        loop {
+            let batch = layouts.by_ref().take(BATCH).collect::<Vec<_>>();
+            if batch.is_empty() {
+                return None;
+            }
+            if let Some(layout) = batch.into_par_iter().find_first(|layout| Self::solvable(size, layout)) {
+                return Some(layout);
+            }
+        }
+    }
+}
+
+impl BoardGenerator for NoGuess {
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>> {
+        let opening = Self::opening(size);
+        // Keep the opening and its neighbors clear, so the opening really opens something up
+        let mut cells = (0..size.0)
+            .flat_map(|row| (0..size.1).map(move |col| (row, col)))
+            .filter(|pos| pos.0.abs_diff(opening.0) > 1 || pos.1.abs_diff(opening.1) > 1)
+            .collect::<Vec<_>>();
+        if cells.len() < mines {
+            return None;
+        }
+
+        let attempts = (Self::BUDGET / (size.0 * size.1)).max(10);
+        Self::first_solvable(size, (0..attempts).map(|_| cells.partial_shuffle(rng, mines).0.to_vec()))
+    }
+
+    fn generate(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Field> {
+        if size.0 == 0 || size.1 == 0 || mines > (size.0 * size.1).div_ceil(2) {
+            return None;
+        }
+        let mut field = Field::from_mines(size, &self.place_mines(size, mines, rng)?)?;
+        field.clear_cell(Self::opening(size));
+        Some(field)
+    }
+}
+
+/// Mines bunch up into clumps, leaving wide open areas between them.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Clustered;
+
+impl Clustered {
+    /// The chance that each mine is placed next to one already on the board, rather than anywhere.
+    const CLUMPING: f64 = 0.75;
+    /// How far from an existing mine a clumped mine can land.
+    const SPREAD: isize = 2;
+}
+
+impl BoardGenerator for Clustered {
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>> {
+        let mut placed = vec![vec![false; size.1]; size.0];
+        let mut positions: Vec<(usize, usize)> = Vec::with_capacity(mines);
+        while positions.len() < mines {
+            let pos = match positions.choose(rng) {
+                Some(&(row, col)) if rng.gen_bool(Self::CLUMPING) => {
+                    let mut offset = || rng.gen_range(-Self::SPREAD..=Self::SPREAD);
+                    let (Some(row), Some(col)) = (row.checked_add_signed(offset()), col.checked_add_signed(offset()))
+                    else { continue };
+                    if row >= size.0 || col >= size.1 {
+                        continue;
+                    }
+                    (row, col)
+                },
+                _ => (rng.gen_range(0..size.0), rng.gen_range(0..size.1)),
+            };
+            if !placed[pos.0][pos.1] {
+                placed[pos.0][pos.1] = true;
+                positions.push(pos);
+            }
+        }
+        Some(positions)
+    }
+}
+
+/// Which way a [`Symmetric`] board is symmetric.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Mirrored left to right, across the middle column.
+    #[default]
+    LeftRight,
+    /// Mirrored top to bottom, across the middle row.
+    TopBottom,
+    /// The same when turned upside down (rotated 180°).
+    Rotational,
+}
+
+impl Symmetry {
+    /// Where `pos` lands when reflected or rotated.
+    fn image(self, size: (usize, usize), pos: (usize, usize)) -> (usize, usize) {
+        match self {
+            Symmetry::LeftRight => (pos.0, size.1 - 1 - pos.1),
+            Symmetry::TopBottom => (size.0 - 1 - pos.0, pos.1),
+            Symmetry::Rotational => (size.0 - 1 - pos.0, size.1 - 1 - pos.1),
+        }
+    }
+}
+
+/// Mines laid out with a [`Symmetry`]. If the mine count can't be split evenly (e.g. an odd count on a board with no
+/// middle column to put the odd one in), the last one goes anywhere.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Symmetric {
+    /// Which way the mines are mirrored.
+    pub symmetry: Symmetry,
+}
+
+impl Symmetric {
+    /// Every cell that `pos` maps to under the symmetry, including itself.
+    fn orbit(&self, size: (usize, usize), pos: (usize, usize)) -> Vec<(usize, usize)> {
+        let image = self.symmetry.image(size, pos);
+        if image == pos { vec![pos] } else { vec![pos, image] }
+    }
+}
+
+impl BoardGenerator for Symmetric {
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>> {
+        let mut orbits = (0..size.0)
+            .flat_map(|row| (0..size.1).map(move |col| (row, col)))
+            .map(|pos| self.orbit(size, pos))
+            // Each orbit shows up once for each cell in it; keep the copy listed by its first cell
+            .filter(|orbit| orbit.iter().all(|&pos| pos >= orbit[0]))
+            .collect::<Vec<_>>();
+        orbits.shuffle(rng);
+
+        let mut positions = Vec::with_capacity(mines);
+        let mut leftover = Vec::new();
+        for orbit in orbits {
+            if positions.len() + orbit.len() <= mines {
+                positions.extend(orbit);
+            } else {
+                leftover.extend(orbit);
+            }
+        }
+        let missing = mines - positions.len();
+        positions.extend(leftover.choose_multiple(rng, missing));
+        Some(positions)
+    }
+}
+
+/// A shape for a [`Patterned`] board to draw with its mines.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Pattern {
+    /// Concentric rings around the center of the board.
+    #[default]
+    Rings,
+    /// Diagonal stripes running from the top left to the bottom right.
+    Stripes,
+}
+
+impl Pattern {
+    /// Returns true if the pattern passes through `pos`.
+    fn contains(self, size: (usize, usize), pos: (usize, usize)) -> bool {
+        match self {
+            Pattern::Rings => {
+                // Rings are measured in cells from the center, so they come out square on the grid
+                let distance = pos.0.abs_diff(size.0 / 2).max(pos.1.abs_diff(size.1 / 2));
+                distance % 3 == 1
+            },
+            Pattern::Stripes => (pos.0 + size.1 - pos.1).is_multiple_of(4),
+        }
+    }
+}
+
+/// Mines placed along a [`Pattern`], anywhere on it. If there are more mines than the pattern has room for, the rest
+/// go anywhere off it.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Patterned {
+    /// The shape the mines are drawn along.
+    pub pattern: Pattern,
+}
+
+impl BoardGenerator for Patterned {
+    fn place_mines(&self, size: (usize, usize), mines: usize, rng: &mut dyn RngCore) -> Option<Vec<(usize, usize)>> {
+        let (mut on, mut off): (Vec<_>, Vec<_>) = (0..size.0)
+            .flat_map(|row| (0..size.1).map(move |col| (row, col)))
+            .partition(|&pos| self.pattern.contains(size, pos));
+        on.shuffle(rng);
+        off.shuffle(rng);
+        Some(on.into_iter().chain(off).take(mines).collect())
+    }
+}
+
+/// The built-in generator with the given name, as accepted by `--generator`.
+pub(crate) fn parse(name: &str) -> Option<Box<dyn BoardGenerator>> {
+    match name.to_ascii_lowercase().as_str() {
+        "uniform" => Some(Box::new(Uniform)),
+        "no-guess" => Some(Box::new(NoGuess)),
+        "clustered" => Some(Box::new(Clustered)),
+        "symmetric" => Some(Box::new(Symmetric { symmetry: Symmetry::LeftRight })),
+        "symmetric-vertical" => Some(Box::new(Symmetric { symmetry: Symmetry::TopBottom })),
+        "symmetric-rotational" => Some(Box::new(Symmetric { symmetry: Symmetry::Rotational })),
+        "rings" => Some(Box::new(Patterned { pattern: Pattern::Rings })),
+        "stripes" => Some(Box::new(Patterned { pattern: Pattern::Stripes })),
+        _ => None,
+    }
+}
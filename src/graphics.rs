@@ -0,0 +1,215 @@
+use std::env;
+use std::io::Write;
+
+pub use crate::console::Graphics;
+use crate::export;
+use crate::game::Field;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The size of a cell in pixels, as (width, height), for when the terminal doesn't say. Kitty scales pictures to the
+/// cells they're put in anyway, so it only makes them a little blurry.
+const FALLBACK_CELL: (usize, usize) = (10, 20);
+/// How much of a picture is sent in each of Kitty's escape codes, in bytes of base64. The protocol allows no more.
+const KITTY_CHUNK: usize = 4096;
+
+/// How pictures are sent to the terminal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// Kitty's graphics protocol, where pictures are sent as base64 and scaled to the cells they're put in.
+    Kitty,
+    /// Sixel graphics, where pictures are sent a band of six rows at a time and drawn at the size they're sent.
+    Sixel,
+}
+
+impl Protocol {
+    /// The protocol for the given setting, or None if the boards are drawn as text: always for `off`, and for `auto`
+    /// unless the terminal looks like it can show pictures.
+    pub fn pick(setting: Graphics) -> Option<Self> {
+        let protocol = match setting {
+            Graphics::Auto => Self::detect()?,
+            Graphics::Kitty => Protocol::Kitty,
+            Graphics::Sixel => Protocol::Sixel,
+            Graphics::Off => return None,
+        };
+        // Sixel pictures are drawn at the size they're sent, which can't be worked out without the size of a cell
+        if protocol == Protocol::Sixel && cell_size().is_none() {
+            return None;
+        }
+        Some(protocol)
+    }
+
+    /// Guess from the environment which protocol the terminal understands, if any. Terminals don't all answer the
+    /// queries for this, and waiting on one that doesn't would hold up the start of the game, so they aren't asked.
+    fn detect() -> Option<Self> {
+        let var = |name| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        // Multiplexers pass pictures through badly, if at all
+        if env::var_os("TMUX").is_some() || term.starts_with("screen") {
+            return None;
+        }
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty")
+        {
+            Some(Protocol::Kitty)
+        } else if term.starts_with("foot") || term.starts_with("mlterm") || term == "yaft-256color"
+            || term.contains("sixel")
+        {
+            Some(Protocol::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Draw the board as a picture over its cells, with its top left at the terminal's cursor, and an outline around
+    /// the cell at the given position if there is one. `id` tells the boards' pictures apart, so each replaces the
+    /// last one drawn of the same board.
+    pub fn draw(self, out: &mut impl Write, id: usize, field: &Field, cursor: Option<(usize, usize)>)
+        -> IoResult<()>
+    {
+        let tile = cell_size().unwrap_or(FALLBACK_CELL);
+        let (width, height, pixels) = export::pixels(field, tile, cursor);
+        match self {
+            Protocol::Kitty => kitty(out, id + 1, (width / tile.0, height / tile.1), (width, height), &pixels),
+            Protocol::Sixel => sixel(out, (width, height), &pixels),
+        }
+    }
+
+    /// Take every picture down. Sixel pictures are only taken down by drawing over them, which is left to the caller.
+    pub fn clear(self, out: &mut impl Write) -> IoResult<()> {
+        match self {
+            Protocol::Kitty => write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\"),
+            Protocol::Sixel => Ok(()),
+        }
+    }
+}
+
+/// The size of one of the terminal's cells in pixels, as (width, height), if it says.
+#[cfg(unix)]
+fn cell_size() -> Option<(usize, usize)> {
+    let mut size = libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+    // SAFETY: TIOCGWINSZ only writes a winsize to the pointer it's given.
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+        return None;
+    }
+    // Terminals that don't know their size in pixels leave it at 0
+    if size.ws_col == 0 || size.ws_row == 0 {
+        return None;
+    }
+    let cell = ((size.ws_xpixel / size.ws_col) as usize, (size.ws_ypixel / size.ws_row) as usize);
+    // Nor would a cell too small for a tile be drawn at its size
+    (cell.0 >= export::MIN_TILE.0 && cell.1 >= export::MIN_TILE.1).then_some(cell)
+}
+
+#[cfg(not(unix))]
+fn cell_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Send RGB pixels with Kitty's graphics protocol, shown over the given number of cells (as columns and rows) without
+/// moving the cursor. Any picture with the same id is taken down first.
+pub fn kitty(
+    out: &mut impl Write,
+    id: usize,
+    (cols, rows): (usize, usize),
+    (width, height): (usize, usize),
+    pixels: &[u8],
+) -> IoResult<()> {
+    write!(out, "\x1b_Ga=d,d=I,i={id},q=2\x1b\\")?;
+    let data = base64(pixels);
+    let mut chunks = data.as_bytes().chunks(KITTY_CHUNK).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        let more = u8::from(chunks.peek().is_some());
+        // Only the first chunk says what the picture is; the rest carry on from it
+        if first {
+            write!(out, "\x1b_Gf=24,s={width},v={height},a=T,i={id},c={cols},r={rows},C=1,q=2,m={more};")?;
+            first = false;
+        } else {
+            write!(out, "\x1b_Gm={more};")?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// The bytes in standard base64, padded, as Kitty's protocol takes them.
+pub fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Send RGB pixels as a Sixel picture. They're drawn a band of six rows at a time, one color at a time, so the few
+/// colors a board is drawn in make for a small picture.
+pub fn sixel(out: &mut impl Write, (width, height): (usize, usize), pixels: &[u8]) -> IoResult<()> {
+    let pixel = |x: usize, y: usize| {
+        let i = (y * width + x) * 3;
+        [pixels[i], pixels[i + 1], pixels[i + 2]]
+    };
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let colors = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let color = pixel(x, y);
+            match palette.iter().position(|&c| c == color) {
+                Some(i) => i,
+                None => {
+                    palette.push(color);
+                    palette.len() - 1
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    write!(out, "\x1bPq\"1;1;{width};{height}")?;
+    // Sixel colors are given in percent
+    for (i, color) in palette.iter().enumerate() {
+        let [r, g, b] = color.map(|c| c as u32 * 100 / 255);
+        write!(out, "#{i};2;{r};{g};{b}")?;
+    }
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        for color in 0..palette.len() {
+            let sixels = (0..width)
+                .map(|x| {
+                    rows.clone()
+                        .filter(|&y| colors[y * width + x] == color)
+                        .fold(0u8, |bits, y| bits | 1 << (y - band))
+                })
+                .collect::<Vec<_>>();
+            if sixels.iter().all(|&bits| bits == 0) {
+                continue;
+            }
+            write!(out, "#{color}")?;
+            // Runs of the same sixel are sent once, with how many there are
+            let mut x = 0;
+            while x < width {
+                let run = sixels[x..].iter().take_while(|&&bits| bits == sixels[x]).count();
+                let ch = (b'?' + sixels[x]) as char;
+                if run > 3 {
+                    write!(out, "!{run}{ch}")?;
+                } else {
+                    (0..run).try_for_each(|_| write!(out, "{ch}"))?;
+                }
+                x += run;
+            }
+            // Back to the start of the band, for the next color
+            write!(out, "$")?;
+        }
+        write!(out, "-")?;
+    }
+    write!(out, "\x1b\\")
+}
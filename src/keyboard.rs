@@ -0,0 +1,93 @@
+use std::io::stdout;
+use crossterm::{
+    execute,
+    event::{KeyboardEnhancementFlags, PushKeyboardEnhancementFlags, PopKeyboardEnhancementFlags},
+};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The kitty keyboard protocol ("CSI u") features termsweeper asks for: unambiguous keys (so the keypad can be told
+/// apart from the rest of the keyboard), and press/repeat/release events (so held keys can be tracked).
+fn flags() -> KeyboardEnhancementFlags {
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+        | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+}
+
+/// Turn on enhanced keyboard reporting if the terminal supports it. Returns whether it was turned on, in which case
+/// [`disable`] should be called before exiting. Raw mode must already be enabled.
+pub(crate) fn enable() -> IoResult<bool> {
+    if !supported()? {
+        return Ok(false);
+    }
+    execute!(stdout(), PushKeyboardEnhancementFlags(flags()))?;
+    Ok(true)
+}
+
+pub(crate) fn disable() -> IoResult<()> {
+    execute!(stdout(), PopKeyboardEnhancementFlags)
+}
+
+/// Ask the terminal for its current keyboard protocol flags, followed by its primary device attributes. Every terminal
+/// answers the latter, so if it comes back without an answer to the former, the protocol isn't supported.
+#[cfg(unix)]
+fn supported() -> IoResult<bool> {
+    use std::io::Write;
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: isatty only inspects the file descriptor.
+    if unsafe { libc::isatty(fd) } != 1 {
+        return Ok(false);
+    }
+
+    write!(stdout(), "\x1b[?u\x1b[c")?;
+    stdout().flush()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut response = Vec::new();
+    while !has_reply(&response, b'c') {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+
+        let mut pollfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+        // SAFETY: pollfd is a valid, initialized pollfd struct, and we pass a count of 1.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready < 0 {
+            return Err(std::io::Error::last_os_error());
+        } else if ready == 0 {
+            break;
+        }
+
+        let mut buf = [0u8; 64];
+        // SAFETY: buf is valid for writes of its full length.
+        let read = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if read <= 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..read as usize]);
+    }
+
+    Ok(has_reply(&response, b'u'))
+}
+
+#[cfg(not(unix))]
+fn supported() -> IoResult<bool> {
+    Ok(false)
+}
+
+/// Whether the bytes contain a private-mode CSI reply (`ESC [ ? <params> <end>`) ending in the given byte.
+#[cfg(unix)]
+fn has_reply(bytes: &[u8], end: u8) -> bool {
+    bytes.windows(3)
+        .enumerate()
+        .filter(|(_, window)| *window == b"\x1b[?")
+        .any(|(start, _)| bytes[start + 3..].iter()
+            .find(|b| !b.is_ascii_digit() && **b != b';')
+            .is_some_and(|b| *b == end)
+        )
+}
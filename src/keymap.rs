@@ -0,0 +1,261 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventState, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::console;
+use crate::game::Direction;
+use crate::locale;
+use crate::tui;
+
+/// Something the player can do with a key press during a game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+    Move(Direction),
+    Reveal,
+    Flag,
+    /// Cycle the note on a hidden cell.
+    Note,
+    Ping,
+    Quit,
+    /// Show or hide the frontier: the hidden cells the numbers say something about.
+    Frontier,
+}
+
+impl Action {
+    /// What the action is called in the `--verbose` timings, which aren't translated.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Action::Move(_) => "move",
+            Action::Reveal => "reveal",
+            Action::Flag => "flag",
+            Action::Note => "note",
+            Action::Ping => "ping",
+            Action::Quit => "quit",
+            Action::Frontier => "frontier",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        locale::tr(match self {
+            Action::Move(_) => "action-move",
+            Action::Reveal => "action-reveal",
+            Action::Flag => "action-flag",
+            Action::Note => "action-note",
+            Action::Ping => "action-ping",
+            Action::Quit => "action-quit",
+            Action::Frontier => "action-frontier",
+        })
+    }
+}
+
+/// The built-in sets of key bindings, selectable by name in the config file.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ControlPreset {
+    /// Arrow keys to move (with shift to turn 45° clockwise), space to reveal, f to flag.
+    #[default]
+    Arrows,
+    /// WASD to move, j to reveal, k to flag.
+    Wasd,
+    /// hjkl to move and yubn for diagonals (like roguelikes), space to reveal, f to flag.
+    Vim,
+    /// The numeric keypad as a grid: 1-9 to move in all eight directions, 5 to reveal, 0 to flag.
+    Numpad,
+    /// ijkl to move, ; to reveal, ' to flag, keeping the left hand free for the mouse.
+    LeftHanded,
+}
+
+impl ControlPreset {
+    pub(crate) const ALL: [ControlPreset; 5] = [
+        ControlPreset::Arrows,
+        ControlPreset::Wasd,
+        ControlPreset::Vim,
+        ControlPreset::Numpad,
+        ControlPreset::LeftHanded,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        locale::tr(match self {
+            ControlPreset::Arrows => "preset-arrows",
+            ControlPreset::Wasd => "preset-wasd",
+            ControlPreset::Vim => "preset-vim",
+            ControlPreset::Numpad => "preset-numpad",
+            ControlPreset::LeftHanded => "preset-left-handed",
+        })
+    }
+
+    pub(crate) fn keymap(self) -> Keymap {
+        use Direction::*;
+        let chars = |keys: [char; 8]| {
+            let directions = [Left, Down, Up, Right].map(Action::Move);
+            let actions = directions.into_iter().chain([Action::Reveal, Action::Flag, Action::Ping, Action::Note]);
+            keys.into_iter()
+                .map(|c| Key::Code(KeyCode::Char(c)))
+                .zip(actions)
+                .chain([
+                    (Key::Code(KeyCode::Char('q')), Action::Quit),
+                    (Key::Code(KeyCode::Char('v')), Action::Frontier),
+                ])
+                .collect()
+        };
+        let bindings = match self {
+            ControlPreset::Arrows => vec![
+                (Key::Code(KeyCode::Left), Action::Move(Left)),
+                (Key::Code(KeyCode::Down), Action::Move(Down)),
+                (Key::Code(KeyCode::Up), Action::Move(Up)),
+                (Key::Code(KeyCode::Right), Action::Move(Right)),
+                (Key::Shift(KeyCode::Left), Action::Move(UpLeft)),
+                (Key::Shift(KeyCode::Up), Action::Move(UpRight)),
+                (Key::Shift(KeyCode::Right), Action::Move(DownRight)),
+                (Key::Shift(KeyCode::Down), Action::Move(DownLeft)),
+                (Key::Code(KeyCode::Char(' ')), Action::Reveal),
+                (Key::Code(KeyCode::Char('f')), Action::Flag),
+                (Key::Code(KeyCode::Char('n')), Action::Note),
+                (Key::Code(KeyCode::Char('p')), Action::Ping),
+                (Key::Code(KeyCode::Char('q')), Action::Quit),
+                (Key::Code(KeyCode::Char('v')), Action::Frontier),
+            ],
+            ControlPreset::Wasd => chars(['a', 's', 'w', 'd', 'j', 'k', 'p', 'l']),
+            ControlPreset::Vim => {
+                let mut bindings = chars(['h', 'j', 'k', 'l', ' ', 'f', 'p', 'm']);
+                let diagonals = [('y', UpLeft), ('u', UpRight), ('b', DownLeft), ('n', DownRight)];
+                bindings.extend(diagonals.map(|(c, direction)| (Key::Code(KeyCode::Char(c)), Action::Move(direction))));
+                bindings
+            },
+            ControlPreset::Numpad => {
+                let grid = [
+                    ('1', KeyCode::End, Action::Move(DownLeft)),
+                    ('2', KeyCode::Down, Action::Move(Down)),
+                    ('3', KeyCode::PageDown, Action::Move(DownRight)),
+                    ('4', KeyCode::Left, Action::Move(Left)),
+                    ('5', KeyCode::KeypadBegin, Action::Reveal),
+                    ('6', KeyCode::Right, Action::Move(Right)),
+                    ('7', KeyCode::Home, Action::Move(UpLeft)),
+                    ('8', KeyCode::Up, Action::Move(Up)),
+                    ('9', KeyCode::PageUp, Action::Move(UpRight)),
+                    ('0', KeyCode::Insert, Action::Flag),
+                    ('.', KeyCode::Delete, Action::Ping),
+                ];
+                // With Num Lock off the keypad sends navigation keys instead of digits, so bind those too
+                grid.iter()
+                    .map(|(digit, _, action)| (Key::Keypad(KeyCode::Char(*digit)), *action))
+                    .chain(grid.iter().map(|(_, navigation, action)| (Key::Keypad(*navigation), *action)))
+                    .chain([
+                        (Key::Keypad(KeyCode::Char('+')), Action::Note),
+                        (Key::Code(KeyCode::Char('q')), Action::Quit),
+                        (Key::Code(KeyCode::Char('v')), Action::Frontier),
+                    ])
+                    .collect()
+            },
+            ControlPreset::LeftHanded => chars(['j', 'k', 'i', 'l', ';', '\'', 'p', 'o']),
+        };
+        Keymap { bindings }
+    }
+}
+
+/// A key that can be bound to an action.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Key {
+    /// The key, wherever it is on the keyboard.
+    Code(KeyCode),
+    /// The key on the numeric keypad. Only terminals with enhanced keyboard reporting can tell these apart, so on
+    /// others they match the same key anywhere, unless it's bound separately.
+    Keypad(KeyCode),
+    /// The key with shift held.
+    Shift(KeyCode),
+}
+
+/// A mapping from keys to actions.
+#[derive(Clone, Debug)]
+pub(crate) struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Keymap {
+    pub(crate) fn action(&self, event: &KeyEvent) -> Option<Action> {
+        let find = |key: Key| self.bindings.iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action);
+        if event.modifiers.contains(KeyModifiers::SHIFT) {
+            if let Some(action) = find(Key::Shift(event.code)) {
+                return Some(action);
+            }
+        }
+        if event.state.contains(KeyEventState::KEYPAD) {
+            find(Key::Keypad(event.code)).or_else(|| find(Key::Code(event.code)))
+        } else {
+            find(Key::Code(event.code)).or_else(|| find(Key::Keypad(event.code)))
+        }
+    }
+
+    /// Each kind of action, with the keys bound to it. Lay them out with [`help_lines`].
+    pub(crate) fn help(&self) -> Vec<(&'static str, String)> {
+        self.help_with(|action| Some(action.description()))
+    }
+
+    /// Like [`Keymap::help`], but describing each action in its own way, for screens where the keys do something else
+    /// (like the editor). Actions described as None are left out.
+    pub(crate) fn help_with(&self, describe: impl Fn(Action) -> Option<&'static str>) -> Vec<(&'static str, String)> {
+        let mut lines = Vec::<(&str, String)>::new();
+        for (key, action) in &self.bindings {
+            let Some(description) = describe(*action) else { continue };
+            let key = match key {
+                Key::Code(code) => key_name(*code),
+                Key::Shift(code) => format!("{}{}", console::glyphs().shift, key_name(*code)),
+                // Only list the digits; the Num Lock off alternatives would just be noise
+                Key::Keypad(KeyCode::Char(c)) => format!("kp{}", c),
+                Key::Keypad(_) => continue,
+            };
+            match lines.iter_mut().find(|(listed, _)| *listed == description) {
+                Some((_, keys)) => {
+                    keys.push(' ');
+                    keys.push_str(&key);
+                },
+                None => lines.push((description, key)),
+            }
+        }
+        lines
+    }
+}
+
+/// A line for each description and its keys, with the keys lined up in a column after the longest description.
+pub(crate) fn help_lines(help: &[(&str, String)]) -> Vec<String> {
+    let width = help.iter().map(|(description, _)| tui::text_width(description)).max().unwrap_or(0) + 1;
+    help.iter()
+        .map(|(description, keys)| format!("{}{}", tui::pad_end(description, width), keys))
+        .collect()
+}
+
+fn key_name(code: KeyCode) -> String {
+    let [left, right, up, down] = console::glyphs().arrows;
+    match code {
+        KeyCode::Left => left.to_string(),
+        KeyCode::Right => right.to_string(),
+        KeyCode::Up => up.to_string(),
+        KeyCode::Down => down.to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::F(n) => format!("f{}", n),
+        _ => "?".to_string(),
+    }
+}
+
+/// The key with the given name, as the config file names it: a character, or one of `space`, `esc`, `enter`, `tab` or
+/// `f1` to `f12`.
+pub(crate) fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "space" => Some(KeyCode::Char(' ')),
+        "esc" => Some(KeyCode::Esc),
+        "enter" => Some(KeyCode::Enter),
+        "tab" => Some(KeyCode::Tab),
+        name => name.strip_prefix('f')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (1..=12).contains(n))
+            .map(KeyCode::F),
+    }
+}
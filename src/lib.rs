@@ -0,0 +1,563 @@
+//! Minesweeper in the terminal.
+//!
+//! Besides the `termsweeper` binary, the engine is usable as a library, for bots, other frontends and tools:
+//!
+//! - [`game::Field`] is a board: generating it, revealing and flagging cells, and inspecting what's on it.
+//! - [`topology::Topology`] is how the cells of a board fit together, from the classic grid to triangles and 3D layers.
+//! - [`engine::GameEngine`] plays a game on a field, and reports everything that happens as [`engine::GameEvent`]s.
+//! - [`generator::BoardGenerator`] lays out the mines on new boards, and can be implemented for new layouts.
+//! - [`solver::analyze`] deduces which hidden cells are certainly safe or certainly mines.
+//! - [`game::Game`] is a whole game in the terminal UI, which can also run headless against any writer.
+//! - [`board_file`] saves and loads boards as text, for custom puzzles.
+//! - [`config::Config`] and [`stats::Stats`] read termsweeper's config and stats files.
+//!
+//! # Stability
+//!
+//! Everything documented here follows semantic versioning: while the version is 0.x, breaking changes to it only
+//! happen in minor releases, and are listed in the changelog. Anything not reachable from the public modules is
+//! internal and may change in any release. Types marked `#[non_exhaustive]` may gain fields or variants in a minor
+//! release.
+
+#![warn(missing_docs)]
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use clap::Parser;
+use crossterm::event::Event;
+
+use crate::bookmark::Bookmarks;
+use crate::bot::{Bot, BotRole};
+use crate::cli::{Cli, Command, GameArgs};
+use crate::config::Config;
+use crate::custom::{Choice, CustomGame};
+use crate::editor::Editor;
+use crate::engine::{Challenge, GameEngine};
+use crate::game::{Field, Game};
+use crate::pack::{Pack, Progress};
+use crate::palette::ColorDepth;
+use crate::preset::Preset;
+use crate::replay::Replay;
+use crate::session::Session;
+use crate::stats::{ExportFormat, LadderRecord, Stats};
+use crate::timing::Step;
+use crate::topology::Topology;
+use crate::tui::{BoxedComponent, Component};
+use crate::versus::{MinePenalty, Rules, Versus};
+
+mod achievements;
+/// Boards that follow how the player does, and suggestions of which to play.
+pub mod adaptive;
+mod ai;
+/// Ways of storing a board's cells.
+pub mod board;
+/// Saving and loading boards as text.
+pub mod board_file;
+mod bookmark;
+mod boss;
+/// Engines that play games, or give hints, from another program.
+pub mod bot;
+mod cli;
+mod command_line;
+/// User settings.
+pub mod config;
+mod console;
+/// The custom game menu, and the boards picked in it.
+pub mod custom;
+/// The daily challenge, on the same board for everyone each day.
+pub mod daily;
+/// Laying out custom boards by hand.
+pub mod editor;
+mod end_menu;
+/// Pictures of boards.
+pub mod export;
+/// The rules of a game, and the events it produces.
+pub mod engine;
+/// Boards and games.
+pub mod game;
+/// Mine layouts for new boards.
+pub mod generator;
+/// Boards drawn as pictures, on terminals that can show them.
+#[cfg(feature = "graphics")]
+pub mod graphics;
+mod keyboard;
+mod keymap;
+mod locale;
+#[cfg(feature = "notifications")]
+mod notification;
+mod pack;
+mod palette;
+mod paths;
+mod platform;
+/// The standard boards.
+pub mod preset;
+mod profile;
+mod rating;
+mod repeat;
+/// Recordings of games.
+pub mod replay;
+mod ruler;
+mod script;
+/// Several games open at once, as tabs.
+pub mod session;
+mod settings;
+/// Logical deductions about a board.
+pub mod solver;
+mod spinner;
+/// The history of finished games.
+pub mod stats;
+mod timer;
+mod timing;
+/// How the cells of a board fit together.
+pub mod topology;
+mod tui;
+mod versus;
+#[cfg(feature = "hot-reload")]
+mod watch;
+
+/// Run termsweeper with the command line arguments it was started with.
+pub fn run() {
+    let cli = Cli::parse();
+    // Everything loaded from here on is the profile's
+    profile::init(cli.global.profile.as_deref());
+    // A config file that fails to load is reported once there's a game to load it for
+    let config = Config::load().unwrap_or_default();
+    console::init(config.glyphs);
+    config.pick_borders(ColorDepth::detect());
+    locale::init(cli.global.lang);
+    // Timings are printed when termsweeper exits, for finding what's slow
+    if cli.global.verbose {
+        timing::enable();
+    }
+
+    let (args, versus_args, custom) = match cli.command {
+        Some(Command::Stats { export: Some(format), .. }) => return export_stats(format),
+        Some(Command::Stats { import: Some(path), .. }) => return import_stats(&path),
+        Some(Command::Stats { .. }) => return print_panel(tui::stats_panel),
+        Some(Command::Achievements) => return print_panel(tui::achievements_panel),
+        Some(Command::Edit { file, size }) => return edit(file, size.unwrap_or(Editor::DEFAULT_SIZE)),
+        Some(Command::Pack { dir }) => return play_pack(&dir),
+        Some(Command::Ladder) => return play_ladder(),
+        Some(Command::Bookmarks) => return play_bookmarks(),
+        Some(Command::Daily) => return play_daily(None),
+        Some(Command::Replay { file, export_cast, export_gif }) => return export_replay(&file, export_cast, export_gif),
+        Some(Command::Profiles) => return profile::choose().expect("couldn't show the profile menu"),
+        Some(Command::Completions { shell }) => return cli::completions(shell, &mut std::io::stdout().lock()),
+        Some(Command::Man { command }) => {
+            let written = cli::man(command.as_deref(), &mut std::io::stdout().lock());
+            return until_closed(written).expect("couldn't write the man page");
+        },
+        Some(Command::Custom { game }) => (game, None, true),
+        Some(Command::Versus { versus, game }) => (game, Some(versus), false),
+        None => (cli.game, None, false),
+    };
+
+    // A day's daily challenge has its own board and rules, so it ignores the rest of the game's flags
+    if let Some(date) = &args.daily {
+        return play_daily(Some(date));
+    }
+
+    // The custom game menu picks the board and rules in place of their flags, and a game with none of them is a chance
+    // to try a suggested preset
+    let (custom_game, suggested) = match custom {
+        true => match CustomGame::choose().expect("couldn't show the custom game menu") {
+            Some(Choice::Custom(custom)) => (Some(custom), None),
+            Some(Choice::Preset(preset)) => (None, Some(preset)),
+            None => return,
+        },
+        false if versus_args.is_none() && args == GameArgs::default() => {
+            (None, adaptive::offer(&config).expect("couldn't show the suggestion"))
+        },
+        false => (None, None),
+    };
+
+    // Presets are the standard ones, or games saved from the custom game menu
+    let preset = args.preset.as_deref().and_then(Preset::parse).or(suggested);
+    let custom_game = custom_game.or_else(|| {
+        let name = args.preset.as_ref().filter(|_| preset.is_none())?;
+        let saved = Config::load().expect("failed to load the config file").presets;
+        let custom = saved.get(name).copied();
+        Some(custom.unwrap_or_else(|| {
+            cli::fail(format!("there's no preset called {name:?}: it must be beginner, intermediate, expert, or a \
+                saved game's name"))
+        }))
+    });
+    let generator_name = args.generator.as_deref()
+        .unwrap_or(if custom_game.is_some_and(|custom| custom.no_guess) { "no-guess" } else { "uniform" });
+    let generator = generator::parse(generator_name).expect("--generator only takes the generators' names");
+    let no_guess = generator_name == "no-guess";
+    // Adaptive mode picks the board itself, based on how recent adaptive games went
+    let adaptive_level = args.adaptive
+        .then(|| adaptive::next_level(&Stats::load().expect("failed to load the stats file").games));
+    let boards = args.boards.unwrap_or(1);
+    let layers = args.layers.unwrap_or(1);
+    let topology = match (args.layers, args.triangles) {
+        (Some(layers), _) => Topology::Layered { layers },
+        (None, true) => Topology::Triangular,
+        (None, false) => Topology::Square,
+    };
+    // The other generators lay out boards for the square grid
+    if topology != Topology::Square && generator_name != "uniform" {
+        cli::fail("--layers and --triangles only work with the uniform generator");
+    }
+    let (size, mines) = match (adaptive_level, preset, custom_game) {
+        (Some(level), _, _) => adaptive::board(level),
+        (None, Some(preset), _) => (preset.size(), preset.mines()),
+        (None, None, Some(custom)) => (custom.size, custom.mines),
+        // Several boards or layers only fit side by side if they're small, and other topologies are harder to read
+        (None, None, None) if boards > 1 || topology != Topology::Square => {
+            (Preset::Beginner.size(), Preset::Beginner.mines())
+        },
+        (None, None, None) => ((25, 25), 40),
+    };
+    // Each layer is a whole board of that size
+    let (size, mines) = ((size.0, size.1 * layers), mines * layers);
+    let generate = move || timing::time(Step::Generation, || if topology != Topology::Square {
+        let mines = generator.place_mines(size, mines, &mut rand::thread_rng());
+        Field::with_topology(topology, size, &mines.expect("couldn't lay out a board"))
+            .expect("couldn't lay out a board")
+    } else {
+        generator.generate(size, mines, &mut rand::thread_rng()).unwrap_or_else(|| match no_guess {
+            // No-guess boards are given up on once plenty of layouts have been tried
+            true => cli::fail("couldn't find a no-guess layout: try fewer mines, or a bigger board"),
+            false => cli::fail("couldn't lay out a board with this generator"),
+        })
+    });
+    // A board file replaces the generated board
+    let board = args.board.as_ref().map(|path| std::fs::read_to_string(path).expect("couldn't read the board"));
+    let custom = board.is_some();
+    let generate = move || match &board {
+        Some(board) => board_file::parse(board).expect("couldn't read the board"),
+        None => generate(),
+    };
+    let (notify, reduced_motion) = (config.notifications, config.reduced_motion());
+    let mut fields = lay_out(notify, reduced_motion, || (0..boards).map(|_| generate()).collect::<Vec<_>>());
+    let field = fields.remove(0);
+
+    let versus = versus_args.map(|versus_args| {
+        if boards != 1 {
+            cli::fail("versus mode is played on a single board");
+        }
+        let rules = if versus_args.flags {
+            Rules::Flags
+        } else if versus_args.sudden_death {
+            Rules::Reveal(MinePenalty::Eliminated)
+        } else {
+            Rules::Reveal(MinePenalty::Points(10))
+        };
+        let players = versus_args.players;
+        let versus = Versus::new(players, rules, field.mine_count()).expect("versus mode supports 2 to 4 players");
+        match versus_args.ai {
+            Some(difficulty) => versus.with_computer(players - 1, difficulty),
+            None => versus,
+        }
+    });
+
+    let challenge = if args.sprint {
+        Some(Challenge::Sprint(Challenge::DEFAULT_LIMIT))
+    } else if args.countdown {
+        Some(Challenge::Countdown(Challenge::DEFAULT_LIMIT))
+    } else {
+        None
+    };
+
+    if args.script {
+        let engine = GameEngine::new(field);
+        let engine = match challenge {
+            Some(challenge) => engine.with_challenge(challenge),
+            None => engine,
+        };
+        script::run(engine, std::io::stdin().lock(), std::io::stdout().lock()).unwrap();
+        return timing::report();
+    }
+
+    // An engine plays the game, or with --hints just suggests moves
+    let bot = args.engine.as_ref().map(|command| {
+        if versus.is_some() || boards != 1 {
+            cli::fail("--engine only works for single-player games on one board");
+        }
+        let role = if args.hints { BotRole::Hint } else { BotRole::Play };
+        (Bot::spawn(command, &field).expect("couldn't start the engine"), role)
+    });
+
+    // Other boards don't have room for the ranges, and an engine or another player would see the real numbers
+    let alone = versus.is_none() && args.engine.is_none() && boards == 1 && topology == Topology::Square;
+    if args.noisy.is_some() && !alone {
+        cli::fail("--noisy only works for single-player games on one square board");
+    }
+    let hardcore = args.hardcore || custom_game.is_some_and(|custom| custom.hardcore);
+    let moving_mines = args.moving_mines || custom_game.is_some_and(|custom| custom.moving_mines);
+    // Replays hold the board, the moves and any penalties, but nothing else about the game would play back the same
+    let plain = versus.is_none() && boards == 1 && topology == Topology::Square && !moving_mines && challenge.is_none();
+    if args.record.is_some() && !plain {
+        cli::fail("--record only works for single-player games on one square board, without a time limit or moving \
+            mines");
+    }
+
+    let config = Config::load().expect("failed to load the config file");
+    let mut game = Game::new(field, config).unwrap().with_boards(fields);
+    if let Some((bot, role)) = bot {
+        game = game.with_bot(bot, role);
+    }
+    if hardcore {
+        game = game.with_hardcore();
+    }
+    if moving_mines {
+        game = game.with_moving_mines();
+    }
+    if let Some(noise) = args.noisy {
+        game = game.with_noisy_numbers(noise, rand::random());
+    }
+    if let Some(target) = &args.json_events {
+        game = game.with_json_events(open_event_stream(target).expect("couldn't open the event stream"));
+    }
+    if let Some(path) = args.record {
+        game = game.with_recording(path);
+    }
+    if let Some(versus) = versus {
+        game = game.with_versus(versus);
+    } else if let Some(challenge) = challenge {
+        game = game.with_challenge(challenge);
+    } else if boards > 1 || topology != Topology::Square || hardcore || moving_mines {
+        // These aren't comparable with a single flat board played by the usual rules, so they're neither ranked nor
+        // part of adaptive mode
+    } else if let Some(level) = adaptive_level {
+        game = game.with_adaptive_level(level);
+    } else if let Some(preset) = preset.filter(|_| generator_name == "uniform" && !custom) {
+        // Only the classic layout is ranked, since the others (and custom boards) change how hard a board is
+        game = game.with_preset(preset);
+    }
+
+    // New tabs are plain games on the same kind of board
+    let mut session = Session::new(game, crossterm::terminal::size().unwrap(), move |terminal_size| {
+        // The spinner goes on the bottom line, which is drawn over when the new tab is
+        crossterm::execute!(std::io::stdout(), crossterm::cursor::MoveTo(0, terminal_size.1.saturating_sub(1)))?;
+        let field = lay_out(notify, reduced_motion, &generate);
+        Ok(Game::headless(field, Config::load()?, terminal_size, std::io::stdout()))
+    });
+    session.render().unwrap();
+
+    loop {
+        if let Some(event) = next_event(session.tick_rate()) {
+            let coalesce = session.coalesce_events();
+            if !handle_events(event, coalesce, |event| session.handle_event(event).unwrap()) {
+                break;
+            }
+        } else if !session.tick().unwrap() {
+            continue;
+        }
+        session.render().unwrap();
+    }
+    // The terminal is only back to normal once every game is closed
+    drop(session);
+    timing::report();
+}
+
+/// Open where `--json-events` should go: a file, created or truncated, or `fd:N` for a file descriptor inherited
+/// from the parent process (on Unix).
+fn open_event_stream(target: &str) -> std::io::Result<std::fs::File> {
+    match target.strip_prefix("fd:") {
+        #[cfg(unix)]
+        Some(fd) => {
+            use std::os::fd::FromRawFd;
+            let fd = fd.parse().map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            // Safety: the fd is open (checked just before), and only used as this file from here on, as asked
+            if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(unsafe { std::fs::File::from_raw_fd(fd) })
+        },
+        _ => std::fs::File::create(target),
+    }
+}
+
+/// Run the board editor on a board file, with the given size if it's a new board.
+fn edit(path: PathBuf, size: (usize, usize)) {
+    let config = Config::load().expect("failed to load the config file");
+    Editor::open(path, size, &config)
+        .and_then(Editor::run)
+        .expect("couldn't edit the board");
+}
+
+/// Turn a replay file into an asciinema cast, and/or a GIF (which needs the `agg` cast renderer installed).
+fn export_replay(path: &Path, cast: Option<PathBuf>, gif: Option<PathBuf>) {
+    let replay = Replay::load(path).expect("couldn't read the replay");
+    // A GIF is rendered from a cast, so make one to start from if it isn't wanted for itself
+    let cast = cast.unwrap_or_else(|| std::env::temp_dir().join("termsweeper-replay.cast"));
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&cast).expect("couldn't create the cast file"));
+    replay.export_cast(&mut file).and_then(|_| std::io::Write::flush(&mut file)).expect("couldn't write the cast");
+    if let Some(gif) = gif {
+        let status = std::process::Command::new("agg")
+            .arg(&cast)
+            .arg(gif)
+            .status()
+            .expect("couldn't run agg, which renders casts as GIFs");
+        assert!(status.success(), "agg failed to render the GIF");
+    }
+}
+
+/// Play the puzzle pack in the given directory, going back to its menu after each puzzle.
+fn play_pack(dir: &Path) {
+    let pack = Pack::load(dir).expect("couldn't load the puzzle pack");
+    loop {
+        let progress = Progress::load().expect("failed to load the puzzle progress file");
+        let Some(i) = pack.choose(&progress).unwrap() else { break };
+        let puzzle = &pack.puzzles[i];
+        let field = puzzle.field().expect("couldn't read the puzzle");
+        let config = Config::load().expect("failed to load the config file");
+        let mut game = Game::new(field, config).unwrap()
+            .with_puzzle(puzzle.id.clone(), puzzle.name.clone())
+            .with_constraints(puzzle.constraints);
+        if let Some(challenge) = puzzle.challenge() {
+            game = game.with_challenge(challenge);
+        }
+        play(&mut game);
+    }
+}
+
+/// Play bookmarked boards from a menu of them, until it's quit.
+fn play_bookmarks() {
+    loop {
+        let mut bookmarks = Bookmarks::load().expect("failed to load the bookmarks file");
+        let Some(i) = bookmarks.choose().unwrap() else { break };
+        let field = bookmarks.field(i).expect("couldn't read the bookmarked board");
+        let config = Config::load().expect("failed to load the config file");
+        play(&mut Game::new(field, config).unwrap());
+    }
+}
+
+/// Play today's daily challenge, unless it's already been played to the end. A game that was quit part way through
+/// starts again from the beginning, on the same board.
+///
+/// A past day's challenge, given by its date, is played from the archive instead: as often as wanted, with its results
+/// kept apart from those played on the day.
+fn play_daily(date: Option<&str>) {
+    let today = daily::today();
+    let day = date.map_or(today, |date| {
+        daily::parse_day(date).expect("--daily must be a date like 2024-03-01, and not one still to come")
+    });
+    let archive = day != today;
+    if !archive {
+        let status = daily::status(day, false).expect("failed to load the daily challenge results");
+        if status.finished() {
+            let streak = daily::streak().expect("failed to load the daily challenge results");
+            let next = daily::countdown();
+            println!("{}", locale::tr_with(
+                "daily-done",
+                &[("status", &status.describe()), ("streak", &streak), ("next", &next)],
+            ));
+            return;
+        }
+        daily::start(day).expect("couldn't save the daily challenge results");
+    }
+    let config = Config::load().expect("failed to load the config file");
+    let game = Game::new(daily::field(day), config).unwrap().with_daily(day, archive);
+    // Only ranked on its day, since the archive's boards are known in advance and can be played again
+    play(&mut if archive { game } else { game.with_preset(daily::PRESET) });
+}
+
+/// Play the time trial ladder: each preset in turn, from Beginner to Expert, with the times added up. The run is
+/// saved once all of them are won; losing or quitting one ends it there.
+fn play_ladder() {
+    let mut times = Vec::new();
+    for (rung, preset) in Preset::ALL.into_iter().enumerate() {
+        let field = Field::new(preset.size(), preset.mines()).expect("couldn't lay out a board");
+        let config = Config::load().expect("failed to load the config file");
+        let mut game = Game::new(field, config).unwrap()
+            .with_preset(preset)
+            .with_ladder(rung, times.iter().sum());
+        play(&mut game);
+        let Some(time) = game.won_in() else { return };
+        times.push(time);
+    }
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let ladder = LadderRecord { timestamp, times_ms: times.iter().map(|time| time.as_millis() as u64).collect() };
+    Stats::record_ladder(&ladder).expect("couldn't save the ladder run");
+    let total = tui::format_time(Duration::from_millis(ladder.total_ms()));
+    println!("{}", locale::tr_with("ladder-total", &[("total", &total)]));
+}
+
+/// Play a game until it's quit.
+fn play(game: &mut Game) {
+    game.render().unwrap();
+    loop {
+        if let Some(event) = next_event(game.tick_rate()) {
+            if !handle_events(event, game.coalesce_events(), |event| game.handle_event(event).unwrap()) {
+                break;
+            }
+        } else if !game.tick().unwrap() {
+            continue;
+        }
+        game.render().unwrap();
+    }
+}
+
+/// Wait for the next terminal event, for up to the given time, or for as long as it takes if there isn't one. Returns
+/// None if the time ran out first.
+fn next_event(timeout: Option<Duration>) -> Option<Event> {
+    if timeout.is_some_and(|timeout| !crossterm::event::poll(timeout).unwrap()) {
+        return None;
+    }
+    Some(crossterm::event::read().unwrap())
+}
+
+/// Handle an event, then any others that came in with it if they're coalesced, so they're all drawn as one frame.
+/// Returns false once one of them says to exit.
+fn handle_events(mut event: Event, coalesce: bool, mut handle: impl FnMut(Event) -> bool) -> bool {
+    loop {
+        if !handle(event) {
+            return false;
+        }
+        if !coalesce || !crossterm::event::poll(Duration::ZERO).unwrap() {
+            return true;
+        }
+        event = crossterm::event::read().unwrap();
+    }
+}
+
+/// Write the stats history to stdout in the given format.
+fn export_stats(format: ExportFormat) {
+    let stats = Stats::load().expect("failed to load the stats file");
+    stats.export(format, std::io::stdout().lock()).expect("couldn't write the stats");
+}
+
+/// Add the games from another minesweeper client's CSV export to the stats.
+fn import_stats(path: &Path) {
+    let file = std::fs::File::open(path).expect("couldn't open the file to import");
+    let (imported, skipped) = Stats::import(std::io::BufReader::new(file)).expect("couldn't import the games");
+    println!("{}", locale::tr_with("stats-imported", &[("imported", &imported), ("skipped", &skipped)]));
+}
+
+/// Lay out boards with the spinner up, sending a notification once they're ready if it took a while (and `notify`).
+fn lay_out<T>(notify: bool, reduced_motion: bool, work: impl FnOnce() -> T) -> T {
+    let work = || spinner::spin_while(locale::tr("generating-board"), reduced_motion, work);
+    #[cfg(feature = "notifications")]
+    if notify {
+        return notification::when_slow(work);
+    }
+    #[cfg(not(feature = "notifications"))]
+    let _ = notify;
+    work()
+}
+
+/// Print a panel built from the stats history to stdout.
+fn print_panel<T: Component>(panel: impl FnOnce(&Stats) -> T) {
+    let panel = panel(&Stats::load().expect("failed to load the stats file"));
+    let boxed = BoxedComponent::new(&panel);
+    let mut buffer = vec![String::new(); boxed.height()];
+    boxed.render_at(&mut buffer);
+    let mut out = std::io::stdout().lock();
+    let written = buffer.iter().try_for_each(|line| writeln!(out, "{}", line));
+    until_closed(written).expect("couldn't print the panel");
+}
+
+/// The result of writing to stdout, counting it as done if whatever was reading it stopped, as `head` does once it has
+/// all it wants.
+fn until_closed(written: std::io::Result<()>) -> std::io::Result<()> {
+    match written {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        written => written,
+    }
+}
+
+// TODO: Make relocatable
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// A language termsweeper's text has been translated into. Each has a catalog of messages in `locales/`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Lang {
+    #[default]
+    English,
+    German,
+    Spanish,
+}
+
+impl Lang {
+    pub(crate) const ALL: [Lang; 3] = [Lang::English, Lang::German, Lang::Spanish];
+
+    /// The language's code, like `en`.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Lang::English => "en",
+            Lang::German => "de",
+            Lang::Spanish => "es",
+        }
+    }
+
+    /// Parse a language code. Regions and encodings are ignored, so `de_AT.UTF-8` is German.
+    pub(crate) fn parse(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_', '.']).next()?;
+        Self::ALL.into_iter().find(|lang| lang.code().eq_ignore_ascii_case(language))
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Lang::English => include_str!("../locales/en.ftl"),
+            Lang::German => include_str!("../locales/de.ftl"),
+            Lang::Spanish => include_str!("../locales/es.ftl"),
+        }
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        static CATALOGS: [OnceLock<Catalog>; Lang::ALL.len()] = [const { OnceLock::new() }; Lang::ALL.len()];
+        CATALOGS[self as usize].get_or_init(|| Catalog::parse(self.source()))
+    }
+}
+
+/// Messages by ID, read from a catalog in a small part of Fluent's syntax: a message per line, as `id = text`, with
+/// `{ $name }` where an argument goes. Blank lines and `#` comments are skipped.
+struct Catalog(HashMap<&'static str, &'static str>);
+
+impl Catalog {
+    fn parse(source: &'static str) -> Self {
+        Self(source.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(id, text)| (id.trim(), text.trim()))
+            .collect())
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Choose the language for the rest of the run. Called once, at startup; until then, everything is in English.
+pub(crate) fn init(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// The text of a message in the chosen language, or in English if it hasn't been translated. A message missing from
+/// English too is shown as its ID, so it stands out rather than taking the game down.
+pub(crate) fn tr(id: &'static str) -> &'static str {
+    let lang = LANG.get().copied().unwrap_or_default();
+    [lang, Lang::English].into_iter()
+        .find_map(|lang| lang.catalog().0.get(id).copied())
+        .unwrap_or(id)
+}
+
+/// Like [`tr`], filling in the message's arguments, given by name.
+pub(crate) fn tr_with(id: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(id).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), &value.to_string());
+    }
+    text
+}
@@ -0,0 +1,26 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::locale;
+
+/// How long laying out boards has to take before it's worth a notification, since the player has likely gone off to
+/// another window by then.
+const SLOW: Duration = Duration::from_secs(5);
+
+/// Show a desktop notification. Sent from its own thread, since reaching the notification daemon can take a while,
+/// and failing to (as without one) only means there's no notification.
+pub(crate) fn send(body: String) {
+    thread::spawn(move || {
+        let _ = notify_rust::Notification::new().appname("termsweeper").summary("termsweeper").body(&body).show();
+    });
+}
+
+/// Do the given work (laying out boards), with a notification that the board is ready if it took a while.
+pub(crate) fn when_slow<T>(work: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = work();
+    if start.elapsed() >= SLOW {
+        send(locale::tr("notify-board-ready").to_string());
+    }
+    result
+}
@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{stdout, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crossterm::{
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    cursor::MoveTo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::board_file;
+use crate::console;
+use crate::engine::{Challenge, Constraints};
+use crate::game::Field;
+use crate::locale;
+use crate::paths;
+use crate::tui::{BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// A puzzle pack: a directory of board files, played in order from a menu. An optional `pack.toml` manifest names the
+/// pack and lists its puzzles in order, along with any limits on how each is played and whether they unlock one at a
+/// time; without one, every `.txt` file in the directory is a puzzle, in name order.
+pub(crate) struct Pack {
+    pub(crate) name: String,
+    pub(crate) puzzles: Vec<Puzzle>,
+    /// Each puzzle is locked until the one before it is solved.
+    sequential: bool,
+}
+
+/// One puzzle in a pack.
+pub(crate) struct Puzzle {
+    pub(crate) name: String,
+    path: PathBuf,
+    pub(crate) id: PuzzleId,
+    /// The board must be cleared within this time, or the puzzle is failed.
+    pub(crate) time_limit: Option<Duration>,
+    pub(crate) constraints: Constraints,
+}
+
+/// The `pack.toml` manifest.
+#[derive(Deserialize)]
+struct Manifest {
+    name: Option<String>,
+    #[serde(default)]
+    sequential: bool,
+    #[serde(default, rename = "puzzle")]
+    puzzles: Vec<PuzzleEntry>,
+}
+
+/// What a puzzle's progress is kept under: the pack's directory and the puzzle's board file in it, so that packs (or
+/// puzzles) that happen to share a name are kept apart.
+#[derive(Clone, Debug)]
+pub(crate) struct PuzzleId {
+    pack: String,
+    file: String,
+}
+
+#[derive(Deserialize)]
+struct PuzzleEntry {
+    /// The board file, relative to the pack directory.
+    file: PathBuf,
+    /// Shown in the menu instead of the file name.
+    name: Option<String>,
+    /// In seconds.
+    time_limit: Option<u64>,
+    #[serde(default)]
+    no_flags: bool,
+    max_clicks: Option<usize>,
+}
+
+impl Pack {
+    const MANIFEST: &'static str = "pack.toml";
+
+    /// Load the pack in the given directory. Every puzzle's board file has to be in the directory, so a pack can't
+    /// reach outside it (with `..`, an absolute path, or a link).
+    pub(crate) fn load(dir: &Path) -> IoResult<Self> {
+        let dir = &dir.canonicalize()?;
+        let file_stem = |path: &Path| path.file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+        let puzzle = |path: &Path, name, time_limit, constraints| {
+            let path = path.canonicalize()?;
+            let Ok(file) = path.strip_prefix(dir) else {
+                let message = format!("{} is outside the pack", path.display());
+                return Err(std::io::Error::new(ErrorKind::InvalidData, message));
+            };
+            let id = PuzzleId { pack: dir.to_string_lossy().into_owned(), file: file.to_string_lossy().into_owned() };
+            Ok(Puzzle { name, path, id, time_limit, constraints })
+        };
+        let manifest = match fs::read_to_string(dir.join(Self::MANIFEST)) {
+            Ok(text) => toml::from_str(&text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Manifest { name: None, sequential: false, puzzles: Vec::new() }
+            },
+            Err(e) => return Err(e),
+        };
+
+        let puzzles: Vec<_> = if manifest.puzzles.is_empty() {
+            let mut paths = fs::read_dir(dir)?
+                .map(|entry| Ok(entry?.path()))
+                .filter(|path| path.as_ref().map_or(true, |path| path.extension().is_some_and(|ext| ext == "txt")))
+                .collect::<IoResult<Vec<_>>>()?;
+            paths.sort();
+            paths.iter()
+                .map(|path| puzzle(path, file_stem(path), None, Constraints::default()))
+                .collect::<IoResult<_>>()?
+        } else {
+            manifest.puzzles.into_iter()
+                .map(|entry| {
+                    let constraints = Constraints { no_flags: entry.no_flags, max_clicks: entry.max_clicks };
+                    let name = entry.name.unwrap_or_else(|| file_stem(&entry.file));
+                    puzzle(&dir.join(entry.file), name, entry.time_limit.map(Duration::from_secs), constraints)
+                })
+                .collect::<IoResult<_>>()?
+        };
+        if puzzles.is_empty() {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "the pack has no puzzles"));
+        }
+
+        let name = manifest.name.unwrap_or_else(|| file_stem(dir));
+        Ok(Self { name, puzzles, sequential: manifest.sequential })
+    }
+
+    /// Returns true if the puzzle at the given index can be played yet.
+    fn unlocked(&self, progress: &Progress, i: usize) -> bool {
+        !self.sequential || i == 0 || progress.completed(&self.puzzles[i - 1])
+    }
+
+    /// Show the pack's menu until a puzzle is picked. Returns None if the player quit instead.
+    pub(crate) fn choose(&self, progress: &Progress) -> IoResult<Option<usize>> {
+        execute!(stdout(), EnterAlternateScreen)?;
+        terminal::enable_raw_mode()?;
+        let mut selected = self.puzzles.iter()
+            .enumerate()
+            .position(|(i, puzzle)| !progress.completed(puzzle) && self.unlocked(progress, i))
+            .unwrap_or(0);
+        let result = loop {
+            self.render_menu(progress, selected)?;
+            let Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event::read()?
+            else { continue };
+            match code {
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Down => selected = (selected + 1).min(self.puzzles.len() - 1),
+                KeyCode::Enter if self.unlocked(progress, selected) => break Some(selected),
+                KeyCode::Esc | KeyCode::Char('q') => break None,
+                _ => {},
+            }
+        };
+        terminal::disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        Ok(result)
+    }
+
+    fn render_menu(&self, progress: &Progress, selected: usize) -> IoResult<()> {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+        let done = self.puzzles.iter().filter(|puzzle| progress.completed(puzzle)).count();
+        let mut lines = vec![
+            locale::tr_with("pack-title", &[("name", &self.name), ("done", &done), ("total", &self.puzzles.len())]),
+            locale::tr("pack-help").to_string(),
+            String::new(),
+        ];
+        lines.extend(self.puzzles.iter().enumerate().map(|(i, puzzle)| {
+            let mut line = format!(
+                "{} {} {}",
+                if i == selected { console::glyphs().pointer } else { ' ' },
+                if progress.completed(puzzle) { console::glyphs().solved } else { ' ' },
+                puzzle.name,
+            );
+            let limits = puzzle.limits();
+            if !limits.is_empty() {
+                line.push_str(&format!(" ({})", limits.join(", ")));
+            }
+            if !self.unlocked(progress, i) {
+                line.push(' ');
+                line.push_str(locale::tr("pack-locked"));
+            }
+            line
+        }));
+        BoxedComponent::new(&Text::new(lines)).render_at(&mut buffer);
+
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        out.flush()
+    }
+}
+
+impl Puzzle {
+    pub(crate) fn field(&self) -> IoResult<Field> {
+        board_file::parse(&fs::read_to_string(&self.path)?)
+    }
+
+    /// The time limit to play against, if the puzzle has one.
+    pub(crate) fn challenge(&self) -> Option<Challenge> {
+        self.time_limit.map(Challenge::Countdown)
+    }
+
+    /// The puzzle's limits, described for the menu.
+    fn limits(&self) -> Vec<String> {
+        let mut limits = Vec::new();
+        limits.extend(self.time_limit.map(|limit| format!("{}s", limit.as_secs())));
+        if self.constraints.no_flags {
+            limits.push(locale::tr("pack-no-flags").to_string());
+        }
+        limits.extend(self.constraints.max_clicks.map(|max| locale::tr_with("pack-clicks", &[("max", &max)])));
+        limits
+    }
+}
+
+/// Which puzzles have been solved, by pack directory and board file, stored as TOML in the platform data directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Progress {
+    #[serde(default)]
+    solved: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Progress {
+    fn path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("puzzles.toml"))
+    }
+
+    /// Load the progress file. A missing file (or no known data directory) means nothing has been solved yet.
+    pub(crate) fn load() -> IoResult<Self> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn completed(&self, puzzle: &Puzzle) -> bool {
+        self.solved.get(&puzzle.id.pack).is_some_and(|solved| solved.contains(&puzzle.id.file))
+    }
+
+    /// Mark a puzzle as solved, and save the progress file.
+    pub(crate) fn complete(puzzle: &PuzzleId) -> IoResult<()> {
+        let mut progress = Self::load()?;
+        progress.solved.entry(puzzle.pack.clone()).or_default().insert(puzzle.file.clone());
+
+        let Some(path) = Self::path() else { return Ok(()) };
+        let text = toml::to_string(&progress).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        paths::write_safely(&path, text.as_bytes())
+    }
+}
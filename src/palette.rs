@@ -0,0 +1,103 @@
+use std::env::var;
+use crossterm::style::Color;
+
+/// How many colors the terminal can show, so colors it can't are swapped for the nearest ones it can.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ColorDepth {
+    /// Any 24-bit color.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// Only the 16 named colors.
+    Ansi16,
+}
+
+/// The 16 named colors, and roughly what they look like in xterm's default palette.
+const NAMED: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::DarkRed, [205, 0, 0]),
+    (Color::DarkGreen, [0, 205, 0]),
+    (Color::DarkYellow, [205, 205, 0]),
+    (Color::DarkBlue, [0, 0, 238]),
+    (Color::DarkMagenta, [205, 0, 205]),
+    (Color::DarkCyan, [0, 205, 205]),
+    (Color::Grey, [229, 229, 229]),
+    (Color::DarkGrey, [127, 127, 127]),
+    (Color::Red, [255, 0, 0]),
+    (Color::Green, [0, 255, 0]),
+    (Color::Yellow, [255, 255, 0]),
+    (Color::Blue, [92, 92, 255]),
+    (Color::Magenta, [255, 0, 255]),
+    (Color::Cyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// The levels of each channel in the 256-color palette's 6x6x6 color cube.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Guess from the environment: `COLORTERM` is set to `truecolor` or `24bit` by terminals with 24-bit color, and
+    /// terminals with 256 colors say so in the name of their terminfo entry (`TERM`), like `xterm-256color`.
+    pub(crate) fn detect() -> Self {
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let term = var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// The color itself if the terminal can show it, or the nearest one it can.
+    pub(crate) fn fit(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => Color::AnsiValue(nearest_256([r, g, b])),
+            (ColorDepth::Ansi16, Color::Rgb { r, g, b }) => nearest_named([r, g, b]),
+            (ColorDepth::Ansi16, Color::AnsiValue(index)) => nearest_named(rgb_of_256(index)),
+            _ => color,
+        }
+    }
+}
+
+/// Parse a color as a name (like `yellow` or `dark_cyan`), `#rrggbb` hex, or an index into the 256-color palette.
+pub(crate) fn parse(text: &str) -> Option<Color> {
+    if let Some(hex) = text.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            6 => Some(Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? }),
+            _ => None,
+        };
+    }
+    if let Ok(index) = text.parse() {
+        return Some(Color::AnsiValue(index));
+    }
+    Color::try_from(text).ok()
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter().zip(b).map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32).sum()
+}
+
+fn nearest_named(rgb: [u8; 3]) -> Color {
+    NAMED.iter().min_by_key(|(_, named)| distance(*named, rgb)).map_or(Color::White, |(color, _)| *color)
+}
+
+/// The closest color in the 256-color palette's color cube or its grays, leaving out the first 16, which terminals
+/// often change.
+fn nearest_256(rgb: [u8; 3]) -> u8 {
+    (16..=255).min_by_key(|&index| distance(rgb_of_256(index), rgb)).unwrap_or(16)
+}
+
+fn rgb_of_256(index: u8) -> [u8; 3] {
+    match index {
+        0..=15 => NAMED[index as usize].1,
+        16..=231 => {
+            let cube = index - 16;
+            [CUBE[(cube / 36) as usize], CUBE[(cube / 6 % 6) as usize], CUBE[(cube % 6) as usize]]
+        },
+        _ => [8 + (index - 232) * 10; 3],
+    }
+}
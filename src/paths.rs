@@ -0,0 +1,63 @@
+use std::env::var_os;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::profile;
+
+/// The directory termsweeper keeps its saved data (stats, etc.) in, following each platform's convention. Each profile
+/// but the default one has a directory of its own inside it. Returns None if the relevant environment variables aren't
+/// set.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        PathBuf::from(var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(var_os("HOME")?).join("Library/Application Support")
+    } else {
+        var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(var_os("HOME")?).join(".local/share")))?
+    };
+    let dir = base.join("termsweeper");
+    Some(match profile::current() {
+        Some(profile) => dir.join("profiles").join(profile),
+        None => dir,
+    })
+}
+
+/// The directory termsweeper keeps its config file in, following each platform's convention. Returns None if the
+/// relevant environment variables aren't set.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        PathBuf::from(var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(var_os("HOME")?).join("Library/Application Support")
+    } else {
+        var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(var_os("HOME")?).join(".config")))?
+    };
+    Some(base.join("termsweeper"))
+}
+
+/// Write a file so it's never left half-written, even by a crash or power loss partway through: the contents go in a
+/// temporary file beside it, which is moved over it once it's on disk. The version it replaces is kept as a backup,
+/// with `.bak` after its name. The directory is created if it isn't there yet.
+pub(crate) fn write_safely(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let with_suffix = |suffix: &str| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
+    let temp = with_suffix(".tmp");
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    if path.exists() {
+        fs::copy(path, with_suffix(".bak"))?;
+    }
+    fs::rename(temp, path)
+}
@@ -0,0 +1,46 @@
+#[cfg(windows)]
+pub(crate) use windows::{code_page, enable_escape_codes};
+#[cfg(not(windows))]
+pub(crate) use stub::{code_page, enable_escape_codes};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Windows consoles need setting up, and may not be able to show Unicode.
+#[cfg(windows)]
+mod windows {
+    use super::IoResult;
+
+    /// Older Windows consoles only understand escape codes (for colors, moving the cursor and so on) once asked to.
+    pub(crate) fn enable_escape_codes() -> IoResult<()> {
+        use crossterm_winapi::{ConsoleMode, Handle};
+
+        const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+        let mode = ConsoleMode::from(Handle::current_out_handle()?);
+        mode.set_mode(mode.mode()? | ENABLE_VIRTUAL_TERMINAL_PROCESSING)
+    }
+
+    /// The console's output code page, or None if there's no console.
+    pub(crate) fn code_page() -> Option<u32> {
+        // SAFETY: GetConsoleOutputCP takes no arguments, and returns 0 if there's no console.
+        match unsafe { winapi::um::consoleapi::GetConsoleOutputCP() } {
+            0 => None,
+            code_page => Some(code_page),
+        }
+    }
+}
+
+/// Terminals everywhere else need neither.
+#[cfg(not(windows))]
+mod stub {
+    use super::IoResult;
+
+    /// Other terminals understand escape codes from the start.
+    pub(crate) fn enable_escape_codes() -> IoResult<()> {
+        Ok(())
+    }
+
+    /// Other terminals don't have code pages; they're assumed to show Unicode.
+    pub(crate) fn code_page() -> Option<u32> {
+        None
+    }
+}
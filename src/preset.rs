@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale;
+
+/// The standard board sizes. Games on these boards are ranked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// 9x9, with 10 mines.
+    Beginner,
+    /// 16x16, with 40 mines.
+    Intermediate,
+    /// 16x30, with 99 mines.
+    Expert,
+}
+
+impl Preset {
+    /// Every preset, from the easiest to the hardest.
+    pub const ALL: [Preset; 3] = [Preset::Beginner, Preset::Intermediate, Preset::Expert];
+
+    /// The preset with the given name, in any case.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name().eq_ignore_ascii_case(s))
+    }
+
+    /// The preset's name, as it's typed on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Beginner => "Beginner",
+            Preset::Intermediate => "Intermediate",
+            Preset::Expert => "Expert",
+        }
+    }
+
+    /// The name to show, in the chosen language. [`Preset::name`] is the one to type on the command line.
+    pub fn label(self) -> &'static str {
+        locale::tr(match self {
+            Preset::Beginner => "preset-beginner",
+            Preset::Intermediate => "preset-intermediate",
+            Preset::Expert => "preset-expert",
+        })
+    }
+
+    /// The board size, as (rows, columns).
+    pub fn size(self) -> (usize, usize) {
+        match self {
+            Preset::Beginner => (9, 9),
+            Preset::Intermediate => (16, 16),
+            Preset::Expert => (16, 30),
+        }
+    }
+
+    /// How many mines there are on the board.
+    pub fn mines(self) -> usize {
+        match self {
+            Preset::Beginner => 10,
+            Preset::Intermediate => 40,
+            Preset::Expert => 99,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::io::{stdout, ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use crossterm::{
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    cursor::MoveTo,
+};
+
+use crate::console;
+use crate::locale;
+use crate::paths;
+use crate::tui::{BoxedComponent, Component, Text};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The profiles' config overrides are kept as `profiles/NAME.toml` in the config directory, and their stats and
+/// puzzle progress in `profiles/NAME` in the data directory. The one picked in the menu is remembered in `profile`.
+fn dir() -> Option<PathBuf> {
+    Some(paths::config_dir()?.join("profiles"))
+}
+
+fn remembered_path() -> Option<PathBuf> {
+    Some(paths::config_dir()?.join("profile"))
+}
+
+/// Whether a profile can be called this: it's used as a file name, so it can't be a path.
+pub(crate) fn valid(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\', ':'])
+}
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Choose the profile for the rest of the run: the one given, or else the one last picked in the menu. Called once, at
+/// startup, before anything is loaded; until then, everything is the default profile's.
+pub(crate) fn init(name: Option<&str>) {
+    let name = name.map(str::to_string).or_else(|| {
+        let name = fs::read_to_string(remembered_path()?).ok()?;
+        Some(name.trim().to_string()).filter(|name| valid(name))
+    });
+    let _ = PROFILE.set(name);
+}
+
+/// The chosen profile, or None for the default one.
+pub(crate) fn current() -> Option<&'static str> {
+    PROFILE.get()?.as_deref()
+}
+
+/// The file the chosen profile's config overrides are kept in, if it isn't the default profile.
+pub(crate) fn config_path() -> Option<PathBuf> {
+    Some(dir()?.join(format!("{}.toml", current()?)))
+}
+
+/// The names of the profiles that have been made, in order.
+fn list() -> IoResult<Vec<String>> {
+    let Some(dir) = dir() else { return Ok(Vec::new()) };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "toml") {
+            names.extend(path.file_stem().and_then(|name| name.to_str()).map(str::to_string));
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Make a profile, with nothing overridden yet.
+fn create(name: &str) -> IoResult<()> {
+    let Some(dir) = dir() else { return Ok(()) };
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.toml", name));
+    if !path.exists() {
+        fs::write(path, "")?;
+    }
+    Ok(())
+}
+
+/// Use the given profile (or the default one, for None) from the next run on, until another is picked.
+fn remember(name: Option<&str>) -> IoResult<()> {
+    let Some(path) = remembered_path() else { return Ok(()) };
+    match name {
+        Some(name) => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, name)
+        },
+        None => match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Show the profile menu, where a profile can be made or picked to play as from then on.
+pub(crate) fn choose() -> IoResult<()> {
+    execute!(stdout(), EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    let result = Menu::new(list()?).run();
+    terminal::disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen)?;
+    match result? {
+        Some(picked) => remember(picked.as_deref()),
+        None => Ok(()),
+    }
+}
+
+/// The profile menu: the default profile, then the others by name.
+struct Menu {
+    profiles: Vec<String>,
+    /// The selected line, the default profile being the first.
+    selected: usize,
+    /// The name being typed for a new profile, once making one has started.
+    naming: Option<String>,
+    /// Why the last name typed couldn't be used, shown until anything changes.
+    status: Option<String>,
+}
+
+impl Menu {
+    fn new(profiles: Vec<String>) -> Self {
+        let mut menu = Self { profiles, selected: 0, naming: None, status: None };
+        menu.selected = menu.line_of(current());
+        menu
+    }
+
+    /// The line a profile is on.
+    fn line_of(&self, name: Option<&str>) -> usize {
+        name.and_then(|name| self.profiles.iter().position(|profile| profile == name)).map_or(0, |i| i + 1)
+    }
+
+    /// Returns the profile picked (None being the default one), or None if the player quit instead.
+    fn run(mut self) -> IoResult<Option<Option<String>>> {
+        loop {
+            self.render()?;
+            let Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event::read()?
+            else { continue };
+            if let Some(name) = &mut self.naming {
+                match code {
+                    KeyCode::Char(c) if !c.is_control() => name.push(c),
+                    KeyCode::Backspace => _ = name.pop(),
+                    KeyCode::Enter => self.create()?,
+                    KeyCode::Esc => self.naming = None,
+                    _ => {},
+                }
+                continue;
+            }
+
+            self.status = None;
+            match code {
+                KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                KeyCode::Down => self.selected = (self.selected + 1).min(self.profiles.len()),
+                KeyCode::Enter => return Ok(Some(self.selected.checked_sub(1).map(|i| self.profiles[i].clone()))),
+                KeyCode::Char('n') => self.naming = Some(String::new()),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                _ => {},
+            }
+        }
+    }
+
+    /// Make a profile with the name typed, and select it.
+    fn create(&mut self) -> IoResult<()> {
+        let Some(name) = self.naming.take().filter(|name| !name.trim().is_empty()) else { return Ok(()) };
+        let name = name.trim().to_string();
+        if !valid(&name) {
+            self.status = Some(locale::tr_with("profile-bad-name", &[("name", &name)]));
+            return Ok(());
+        }
+        create(&name)?;
+        self.profiles = list()?;
+        self.selected = self.line_of(Some(&name));
+        Ok(())
+    }
+
+    fn render(&self) -> IoResult<()> {
+        let (_, height) = terminal::size()?;
+        let mut buffer = vec![String::new(); height as usize];
+        let names = [locale::tr("profile-default")].into_iter().chain(self.profiles.iter().map(String::as_str));
+        let playing = self.line_of(current());
+
+        let mut lines = vec![
+            locale::tr("profile-title").to_string(),
+            locale::tr("profile-help").to_string(),
+            String::new(),
+        ];
+        lines.extend(names.enumerate().map(|(i, name)| {
+            let pointer = if i == self.selected { console::glyphs().pointer } else { ' ' };
+            let chosen = console::glyphs().chosen[if i == playing { 0 } else { 1 }];
+            format!("{} {} {}", pointer, chosen, name)
+        }));
+        lines.push(String::new());
+        lines.push(match (&self.naming, &self.status) {
+            (Some(name), _) => locale::tr_with("profile-name", &[("name", name)]),
+            (None, Some(status)) => status.clone(),
+            (None, None) => String::new(),
+        });
+        BoxedComponent::new(&Text::new(lines)).render_at(&mut buffer);
+
+        let mut out = stdout();
+        for (i, line) in buffer.iter().enumerate() {
+            execute!(out, MoveTo(0, i as u16))?;
+            write!(out, "{}", line)?;
+            execute!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        out.flush()
+    }
+}
@@ -0,0 +1,49 @@
+use crate::preset::Preset;
+use crate::stats::GameRecord;
+
+/// The rating a player starts with before any ranked games.
+pub(crate) const INITIAL: f64 = 1200.0;
+
+/// How far a single game can move the rating.
+const K: f64 = 32.0;
+
+/// The rating of the "opponent" each preset represents: a player with this rating is expected to score 0.5 on it.
+fn preset_rating(preset: Preset) -> f64 {
+    match preset {
+        Preset::Beginner => 1000.0,
+        Preset::Intermediate => 1300.0,
+        Preset::Expert => 1600.0,
+    }
+}
+
+/// The speed, in 3BV per second, at which a win on the preset counts as a perfect result.
+fn par_speed(preset: Preset) -> f64 {
+    match preset {
+        Preset::Beginner => 2.0,
+        Preset::Intermediate => 1.5,
+        Preset::Expert => 1.2,
+    }
+}
+
+/// How well a game went, from 0 to 1. A loss scores 0, and a win scores between 0.5 and 1 depending on how close it
+/// came to par speed.
+fn score(preset: Preset, game: &GameRecord) -> f64 {
+    if !game.won {
+        return 0.0;
+    }
+    let seconds = (game.time_ms as f64 / 1000.0).max(0.001);
+    let speed = game.bbbv as f64 / seconds;
+    0.5 + 0.5 * (speed / par_speed(preset)).min(1.0)
+}
+
+/// The rating after playing the given game. Unranked games leave it unchanged.
+pub(crate) fn update(rating: f64, game: &GameRecord) -> f64 {
+    let Some(preset) = game.preset else { return rating };
+    let expected = 1.0 / (1.0 + 10f64.powf((preset_rating(preset) - rating) / 400.0));
+    rating + K * (score(preset, game) - expected)
+}
+
+/// The rating after playing all the given games in order.
+pub(crate) fn rating<'a>(games: impl IntoIterator<Item = &'a GameRecord>) -> f64 {
+    games.into_iter().fold(INITIAL, update)
+}
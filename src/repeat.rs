@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::game::Direction;
+
+/// Tuning for cursor acceleration while a movement key is held.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RepeatConfig {
+    pub(crate) enabled: bool,
+    /// How long a key has to be held before the cursor starts speeding up.
+    pub(crate) delay_ms: u64,
+    /// How long it takes to speed up by one more cell per step.
+    pub(crate) ramp_ms: u64,
+    /// The most cells the cursor moves per step.
+    pub(crate) max_step: u16,
+}
+
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self { enabled: true, delay_ms: 300, ramp_ms: 150, max_step: 4 }
+    }
+}
+
+/// Tracks whether a movement key is being held, by watching for a run of repeated presses in the same direction.
+#[derive(Debug, Default)]
+pub(crate) struct KeyRepeat {
+    held: Option<Held>,
+}
+
+#[derive(Debug)]
+struct Held {
+    direction: Direction,
+    since: Instant,
+    last: Instant,
+    /// Whether presses have come in at the autorepeat rate yet, rather than only as fast as someone can tap.
+    repeating: bool,
+}
+
+impl KeyRepeat {
+    /// How long the first repeat can take to come after the press. Terminals that don't report releases send taps the
+    /// same way as a held key, so until presses come faster than this they still only move one cell each.
+    const FIRST_GAP: Duration = Duration::from_millis(600);
+    /// Presses further apart than this once they've started repeating aren't part of the same hold. Autorepeat is
+    /// usually 25-50ms apart.
+    const GAP: Duration = Duration::from_millis(100);
+
+    /// Register a movement key press, and return how many cells the cursor should move for it.
+    pub(crate) fn press(&mut self, direction: Direction, config: &RepeatConfig) -> u16 {
+        let now = Instant::now();
+        let (since, repeating) = match &self.held {
+            Some(held) if held.direction == direction => {
+                let gap = now - held.last;
+                match held.repeating {
+                    true if gap < Self::GAP => (held.since, true),
+                    false if gap < Self::FIRST_GAP => (held.since, gap < Self::GAP),
+                    _ => (now, false),
+                }
+            },
+            _ => (now, false),
+        };
+        self.held = Some(Held { direction, since, last: now, repeating });
+
+        if !config.enabled || !repeating {
+            return 1;
+        }
+        let held = (now - since).as_millis() as u64;
+        match held.checked_sub(config.delay_ms) {
+            Some(accelerating) => (1 + accelerating / config.ramp_ms.max(1)).min(config.max_step.max(1) as u64) as u16,
+            None => 1,
+        }
+    }
+
+    /// Register that the key was released (only reported by terminals with enhanced keyboard reporting), or that
+    /// something else was pressed.
+    pub(crate) fn release(&mut self) {
+        self.held = None;
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::board_file;
+use crate::config::Config;
+use crate::engine::Command;
+use crate::game::{Field, Game};
+use crate::tui::Component;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// A recording of a single-player game: the board it was played on, and every command in the order they happened.
+/// Saved as JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Replay {
+    /// The board, in the board file format.
+    board: String,
+    /// A hash of where the mines are, so that a board that reads back differently from how it was recorded (because
+    /// the file was edited, or the board file format changed) is caught before it's played back. Older replays don't
+    /// have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    board_hash: Option<String>,
+    /// How noisy the numbers were, and the seed their ranges were picked from, for a game with noisy numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    noisy_numbers: Option<NoisyNumbers>,
+    /// The time added for each flag on a safe cell, for a game in hardcore mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flag_penalty_ms: Option<u64>,
+    moves: Vec<Move>,
+    /// The penalties the game added to the clock, for checking that playing it back adds the same ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    penalties: Vec<Penalty>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NoisyNumbers {
+    noise: u8,
+    seed: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Move {
+    /// When the move was made, in milliseconds of play since the game started.
+    at: u64,
+    command: Command,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Penalty {
+    /// When it was added, in milliseconds of play since the game started.
+    at: u64,
+    /// The safe cell that was flagged, as (row, col).
+    pos: (usize, usize),
+    time_ms: u64,
+}
+
+/// Collects everything a game writes, so it can be cut into frames.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl Output {
+    /// Everything written since the last call.
+    fn take(&self) -> String {
+        String::from_utf8_lossy(&std::mem::take(&mut *self.0.borrow_mut())).into_owned()
+    }
+}
+
+impl Replay {
+    /// How often frames are drawn between moves, to keep the clock ticking in the recording.
+    const FRAME_INTERVAL: Duration = Duration::from_secs(1);
+    /// The longest pause between moves that the clock is shown ticking through. Longer ones skip to the next move.
+    const MAX_PAUSE: Duration = Duration::from_secs(10 * 60);
+
+    /// Start recording a game on the given board.
+    pub(crate) fn new(field: &Field) -> Self {
+        Self {
+            board: board_file::write(field),
+            board_hash: Some(board_file::hash(field)),
+            noisy_numbers: None,
+            flag_penalty_ms: None,
+            moves: Vec::new(),
+            penalties: Vec::new(),
+        }
+    }
+
+    /// Record a game with noisy numbers, whose ranges are picked from the given seed.
+    pub(crate) fn with_noisy_numbers(mut self, noise: u8, seed: u64) -> Self {
+        self.noisy_numbers = Some(NoisyNumbers { noise, seed });
+        self
+    }
+
+    /// Record a game in hardcore mode, where each flag on a safe cell adds the given time.
+    pub(crate) fn with_flag_penalty(mut self, penalty: Duration) -> Self {
+        self.flag_penalty_ms = Some(penalty.as_millis() as u64);
+        self
+    }
+
+    pub(crate) fn record(&mut self, at: Duration, command: Command) {
+        self.moves.push(Move { at: at.as_millis() as u64, command });
+    }
+
+    /// Record a penalty the game added to the clock.
+    pub(crate) fn penalty(&mut self, at: Duration, pos: (usize, usize), time: Duration) {
+        self.penalties.push(Penalty { at: at.as_millis() as u64, pos, time_ms: time.as_millis() as u64 });
+    }
+
+    /// Load a replay, checking that its board is the one it was recorded on.
+    pub(crate) fn load(path: &Path) -> IoResult<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Read a replay from its JSON, checking that its board is the one it was recorded on and that its moves are in
+    /// order. Anything that doesn't is an [`ErrorKind::InvalidData`] error.
+    pub fn parse(json: &str) -> IoResult<Self> {
+        let invalid = |error: String| std::io::Error::new(ErrorKind::InvalidData, error);
+        let replay: Self = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        let field = board_file::parse(&replay.board)?;
+        if let Some(hash) = &replay.board_hash {
+            let found = board_file::hash(&field);
+            if *hash != found {
+                let error = format!("the board isn't the one the replay was recorded on (hash {found}, not {hash})");
+                return Err(invalid(error));
+            }
+        }
+        // Wider cells with noisy numbers, and room for the border
+        if (field.width() * Field::NOISY_CELL_WIDTH + 2).max(field.height() + 24) > u16::MAX as usize {
+            return Err(invalid("the board is too big to play back".to_string()));
+        }
+        if replay.noisy_numbers.as_ref().is_some_and(|noisy| !(1..=3).contains(&noisy.noise)) {
+            return Err(invalid("the numbers can only be 1 to 3 off".to_string()));
+        }
+        if replay.moves.windows(2).any(|moves| moves[0].at > moves[1].at) {
+            return Err(invalid("the moves aren't in the order they were made in".to_string()));
+        }
+        Ok(replay)
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> IoResult<()> {
+        let json = serde_json::to_string(self).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    /// Play the game back headless, and write what it draws as an asciinema v2 cast. Moves happen at the same pace
+    /// they were made in. Fails if playing it back doesn't add the same penalties as the game did.
+    pub fn export_cast(&self, out: &mut impl Write) -> IoResult<()> {
+        let field = board_file::parse(&self.board)?;
+        // Room for the board, with the panels below it. Noisy numbers draw each cell several columns wide
+        let width = field.width() * if self.noisy_numbers.is_some() { Field::NOISY_CELL_WIDTH } else { 1 };
+        let size = ((width + 2).max(80) as u16, (field.height() + 24) as u16);
+        let output = Output::default();
+        let mut game = Game::headless(field, Config::default(), size, output.clone()).with_playback();
+        if let Some(NoisyNumbers { noise, seed }) = self.noisy_numbers {
+            game = game.with_noisy_numbers(noise, seed);
+        }
+        if let Some(penalty) = self.flag_penalty_ms {
+            game = game.with_flag_penalty(Duration::from_millis(penalty));
+        }
+
+        let header = serde_json::json!({ "version": 2, "width": size.0, "height": size.1 });
+        writeln!(out, "{}", header)?;
+        let mut frame = |game: &mut Game, at: Duration| -> IoResult<()> {
+            game.render()?;
+            let data = output.take();
+            if data.is_empty() {
+                return Ok(());
+            }
+            writeln!(out, "{}", serde_json::json!([at.as_secs_f64(), "o", data]))
+        };
+
+        let mut now = Duration::ZERO;
+        frame(&mut game, now)?;
+        for Move { at, command } in &self.moves {
+            let at = Duration::from_millis(*at);
+            // The clock only changes while the game is running
+            let ticks_until = at.min(now + Self::MAX_PAUSE);
+            while game.running() && now + Self::FRAME_INTERVAL < ticks_until {
+                now += Self::FRAME_INTERVAL;
+                game.advance_by(Self::FRAME_INTERVAL)?;
+                frame(&mut game, now)?;
+            }
+            game.advance_by(at.saturating_sub(now))?;
+            now = now.max(at);
+            game.apply(*command)?;
+            frame(&mut game, now)?;
+        }
+        let recorded = self.penalties.iter().map(|penalty| penalty.time_ms).sum::<u64>();
+        if game.penalties().as_millis() as u64 != recorded {
+            let error = "playing the replay back doesn't add the same penalties as the game did";
+            return Err(std::io::Error::new(ErrorKind::InvalidData, error));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::game::Field;
+use crate::tui::Component;
+
+/// A column's name on the rulers: A to Z, then AA, AB and so on, as in a spreadsheet.
+pub(crate) fn column_name(col: usize) -> String {
+    let mut name = Vec::new();
+    let mut col = col + 1;
+    while col > 0 {
+        name.push(b'A' + ((col - 1) % 26) as u8);
+        col = (col - 1) / 26;
+    }
+    name.iter().rev().map(|&c| c as char).collect()
+}
+
+/// A row's name on the rulers, counting from 1.
+pub(crate) fn row_name(row: usize) -> String {
+    (row + 1).to_string()
+}
+
+/// Parse a cell named as on the rulers, column then row, like `C7` (in either case). Returns it as (row, col).
+pub(crate) fn parse_cell(name: &str) -> Option<(usize, usize)> {
+    let split = name.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = name.split_at(split);
+    if letters.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let col = letters.bytes().try_fold(0usize, |col, c| {
+        col.checked_mul(26)?.checked_add((c.to_ascii_uppercase() - b'A') as usize + 1)
+    })?;
+    let row = digits.parse::<usize>().ok()?.checked_sub(1)?;
+    Some((row, col - 1))
+}
+
+/// The lines of the ruler over the board, with each column's name written down from the top under where the column is
+/// drawn. Names longer than a letter take a line for each.
+pub(crate) fn columns(field: &Field) -> Vec<String> {
+    let (_, cols) = field.size();
+    let names = (0..cols).map(|col| (field.screen_pos((0, col)).0 as usize, column_name(col))).collect::<Vec<_>>();
+    let height = names.iter().map(|(_, name)| name.len()).max().unwrap_or(0);
+    (0..height)
+        .map(|line| {
+            let mut text = vec![' '; field.width()];
+            for (x, name) in &names {
+                // Shorter names line up with the last letters of the longer ones
+                if let Some(c) = (line + name.len()).checked_sub(height).and_then(|i| name.chars().nth(i)) {
+                    text[*x] = c;
+                }
+            }
+            text.into_iter().collect()
+        })
+        .collect()
+}
+
+/// How wide the ruler beside the board is: the widest row name, and a space.
+pub(crate) fn gutter_width(field: &Field) -> usize {
+    row_name(field.size().0.saturating_sub(1)).len() + 1
+}
@@ -0,0 +1,71 @@
+use std::io::{BufRead, Write};
+
+use crate::engine::{Command, GameEngine};
+use crate::timer::Timer;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Play a game by commands read line by line from `input`, instead of from the keyboard, answering each one on
+/// `output`. This is for scripts, tests and bots, so everything is plain text:
+///
+/// - The game starts by writing `size ROWS COLS MINES`.
+/// - `reveal R C`, `flag R C` and `chord R C` act on the cell at row R, column C (counting from 0), and are answered
+///   with `ok`, or `unchanged` if they did nothing. When a command ends the game, `won` or `lost` follows.
+/// - `board` writes the board as the terminal shows it, a line per row.
+/// - `quit` (or the end of the input) stops reading.
+///
+/// Blank lines and lines starting with `#` are skipped. Anything else is answered with `error` and a reason, and the
+/// game carries on.
+pub(crate) fn run(mut engine: GameEngine, input: impl BufRead, mut output: impl Write) -> IoResult<()> {
+    let (rows, cols) = engine.field().size();
+    writeln!(output, "size {} {} {}", rows, cols, engine.field().mine_count())?;
+    // The game clock runs in real time, as it would with a player at the keyboard
+    let mut timer = Timer::default();
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        engine.advance(timer.lap());
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [] => {},
+            _ if line.starts_with('#') => {},
+            ["quit"] => break,
+            ["board"] => {
+                for (row, cells) in engine.field().board.iter().enumerate() {
+                    writeln!(output, "{}", engine.field().topology().render_row(row, cells))?;
+                }
+            },
+            ref words => match parse(words) {
+                Ok(Command::Reveal(pos) | Command::Flag(pos) | Command::Chord(pos))
+                    if engine.field().cell(pos).is_none() => writeln!(output, "error {:?} is off the board", pos)?,
+                Ok(command) => {
+                    let ended = engine.ended();
+                    writeln!(output, "{}", if engine.apply(command) { "ok" } else { "unchanged" })?;
+                    if engine.ended() && !ended {
+                        writeln!(output, "{}", if engine.won() { "won" } else { "lost" })?;
+                    }
+                },
+                Err(e) => writeln!(output, "error {}", e)?,
+            },
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Parse the words of a command line, like `reveal 3 4`.
+pub(crate) fn parse(words: &[&str]) -> Result<Command, String> {
+    let pos = |args: &[&str]| match args {
+        [row, col] => Ok((
+            row.parse().map_err(|_| format!("{} isn't a row", row))?,
+            col.parse().map_err(|_| format!("{} isn't a column", col))?,
+        )),
+        _ => Err("expected a row and a column".to_string()),
+    };
+    match words {
+        ["reveal", args @ ..] => Ok(Command::Reveal(pos(args)?)),
+        ["flag", args @ ..] => Ok(Command::Flag(pos(args)?)),
+        ["chord", args @ ..] => Ok(Command::Chord(pos(args)?)),
+        [command, ..] => Err(format!("unknown command {}", command)),
+        [] => Err("expected a command".to_string()),
+    }
+}
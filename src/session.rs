@@ -0,0 +1,134 @@
+use std::time::Duration;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::game::Game;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Several games open at once, as tabs. Only the current game is shown and gets input; the others are suspended, with
+/// their clocks stopped, until they're switched back to.
+pub struct Session {
+    games: Vec<Game>,
+    current: usize,
+    /// The game that set up the terminal, if its tab has been closed. It's kept until the session ends, since it puts
+    /// the terminal back as it was when it's dropped.
+    terminal_owner: Option<Game>,
+    terminal_size: (u16, u16),
+    /// Starts the game for a new tab, sized for the given terminal.
+    new_game: Box<dyn FnMut((u16, u16)) -> IoResult<Game>>,
+}
+
+impl Session {
+    /// The most tabs that can be open at once.
+    const MAX_GAMES: usize = 9;
+
+    /// A session with the one game open, which opens new tabs with `new_game`.
+    pub fn new(
+        game: Game,
+        terminal_size: (u16, u16),
+        new_game: impl FnMut((u16, u16)) -> IoResult<Game> + 'static,
+    ) -> Self {
+        Self { games: vec![game], current: 0, terminal_owner: None, terminal_size, new_game: Box::new(new_game) }
+    }
+
+    /// The number of tabs open.
+    pub fn tabs(&self) -> usize {
+        self.games.len()
+    }
+
+    /// The tab being shown, counting from 0.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Draw a frame of the current game.
+    pub fn render(&mut self) -> IoResult<()> {
+        self.games[self.current].render()
+    }
+
+    /// React to a terminal event. Ctrl+T opens a new tab, Ctrl+Tab and Ctrl+Shift+Tab switch between them, and Ctrl+W
+    /// closes the current one; everything else goes to the current game. Quitting the game closes its tab. Returns
+    /// false if the session should exit, once the last tab is closed.
+    pub fn handle_event(&mut self, event: Event) -> IoResult<bool> {
+        match event {
+            Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. })
+                if modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                match code {
+                    KeyCode::Char('t') if self.games.len() < Self::MAX_GAMES => {
+                        let game = (self.new_game)(self.terminal_size)?;
+                        self.games.push(game);
+                        self.switch(self.games.len() - 1)?;
+                        return Ok(true);
+                    },
+                    KeyCode::Tab => {
+                        self.switch((self.current + 1) % self.games.len())?;
+                        return Ok(true);
+                    },
+                    KeyCode::BackTab => {
+                        self.switch((self.current + self.games.len() - 1) % self.games.len())?;
+                        return Ok(true);
+                    },
+                    KeyCode::Char('w') => return self.close_current(),
+                    _ => {},
+                }
+            },
+            Event::Resize(width, height) => self.terminal_size = (width, height),
+            _ => {},
+        }
+        match self.games[self.current].handle_event(event)? {
+            true => Ok(true),
+            false => self.close_current(),
+        }
+    }
+
+    /// How long to wait for input before ticking the current game, or None to wait for as long as it takes.
+    pub(crate) fn tick_rate(&self) -> Option<Duration> {
+        self.games[self.current].tick_rate()
+    }
+
+    /// Returns true if events that come in together should be drawn as one frame.
+    pub(crate) fn coalesce_events(&self) -> bool {
+        self.games[self.current].coalesce_events()
+    }
+
+    /// Update the current game's timed state. Returns true if it should be re-rendered.
+    pub(crate) fn tick(&mut self) -> IoResult<bool> {
+        self.games[self.current].tick()
+    }
+
+    /// Close the current tab, and show the one after it (or before it, if it was the last). Returns false if it was
+    /// the only one.
+    fn close_current(&mut self) -> IoResult<bool> {
+        if self.games.len() == 1 {
+            return Ok(false);
+        }
+        let mut game = self.games.remove(self.current);
+        game.suspend()?;
+        if game.owns_terminal() {
+            self.terminal_owner = Some(game);
+        }
+        // The tab that takes its place was suspended when it was left, so it only needs bringing back
+        self.current = self.current.min(self.games.len() - 1);
+        self.show(self.current)?;
+        Ok(true)
+    }
+
+    fn switch(&mut self, to: usize) -> IoResult<()> {
+        if to == self.current {
+            return Ok(());
+        }
+        self.games[self.current].suspend()?;
+        self.current = to;
+        self.show(to)
+    }
+
+    /// Bring back a suspended game, and say which tab it is.
+    fn show(&mut self, to: usize) -> IoResult<()> {
+        let count = self.games.len();
+        let game = &mut self.games[to];
+        game.resume(self.terminal_size)?;
+        game.toast_tab(to + 1, count);
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crossterm::event::KeyCode;
+
+use crate::keymap::ControlPreset;
+use crate::tui::Handled;
+
+/// The settings screen, where the control preset can be chosen.
+pub(crate) struct Settings {
+    pub(crate) selected: usize,
+    pub(crate) current: ControlPreset,
+    /// The preset just picked, until the game switches to it and saves it to the config file.
+    picked: Option<ControlPreset>,
+}
+
+impl Settings {
+    pub(crate) fn new(current: ControlPreset) -> Self {
+        Self {
+            selected: ControlPreset::ALL.iter().position(|preset| *preset == current).unwrap_or(0),
+            current,
+            picked: None,
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> Handled {
+        match code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(ControlPreset::ALL.len() - 1),
+            KeyCode::Enter => {
+                self.current = ControlPreset::ALL[self.selected];
+                self.picked = Some(self.current);
+            },
+            KeyCode::Esc => return Handled::Closed,
+            _ => {},
+        }
+        Handled::Consumed
+    }
+
+    /// The preset picked since the last time this was called, if one was.
+    pub(crate) fn take_picked(&mut self) -> Option<ControlPreset> {
+        self.picked.take()
+    }
+}
@@ -0,0 +1,191 @@
+use crate::board::Board;
+use crate::game::Field;
+use crate::timing::{self, Step};
+
+/// What the solver could deduce about the hidden cells of a field, using only what a player can see.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Hidden cells that are certainly safe, as (row, col).
+    pub(crate) safe: Vec<(usize, usize)>,
+    /// Hidden cells that are certainly mines, as (row, col).
+    pub(crate) mines: Vec<(usize, usize)>,
+}
+
+impl Analysis {
+    /// Hidden cells that are certainly safe, as (row, col).
+    pub fn safe(&self) -> &[(usize, usize)] {
+        &self.safe
+    }
+
+    /// Hidden cells that are certainly mines, as (row, col).
+    pub fn mines(&self) -> &[(usize, usize)] {
+        &self.mines
+    }
+}
+
+/// The frontier: the hidden cells next to a revealed number, which are the only ones the numbers say anything about.
+/// Returned as a grid the size of the field, true for each cell on the frontier.
+pub fn frontier(field: &impl Board) -> Vec<Vec<bool>> {
+    let (rows, cols) = field.size();
+    let mut frontier = vec![vec![false; cols]; rows];
+    for pos in cells(field) {
+        if field.shown_number(pos).is_some_and(|number| number > 0) {
+            for (r, c) in field.neighbors(pos).filter(|&neighbor| field.is_hidden(neighbor)) {
+                frontier[r][c] = true;
+            }
+        }
+    }
+    frontier
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Knowledge {
+    Unknown,
+    Safe,
+    Mine,
+}
+
+/// What the solver deduced about a field the last time it was analyzed, so that it can pick up from there as cells are
+/// revealed instead of starting over. Every field keeps one; it's brought up to date by [`analyze_cached`].
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    /// What's known about each cell, or None if the deductions have to be started over.
+    knowledge: Option<Vec<Vec<Knowledge>>>,
+    /// The cells that changed since, around which there may be something new to deduce.
+    changed: Vec<(usize, usize)>,
+}
+
+impl Cache {
+    /// Note that a cell was revealed, flagged or unflagged. What was deduced before still holds, since the player can
+    /// only ever see more of the board.
+    pub(crate) fn changed(&mut self, pos: (usize, usize)) {
+        if self.knowledge.is_some() {
+            self.changed.push(pos);
+        }
+    }
+
+    /// Start the deductions over, as when mines are moved and what was deduced may no longer hold.
+    pub(crate) fn reset(&mut self) {
+        self.knowledge = None;
+        self.changed.clear();
+    }
+}
+
+/// Apply the basic single-cell rules until nothing changes: a number whose remaining hidden neighbors are exactly as
+/// many as its missing mines has only mines around it, and a number that already touches all its mines has only safe
+/// cells around it. Flags are ignored, since they may be wrong; exploded cells count as known mines.
+pub fn analyze(field: &impl Board) -> Analysis {
+    let mut knowledge = start(field);
+    deduce(field, &mut knowledge, cells(field));
+    collect(field, &knowledge)
+}
+
+/// Like [`analyze`], but picking up from the field's last analysis, so that only the numbers around cells that changed
+/// since are looked at again. Asking after every move stays quick, even on big boards.
+pub fn analyze_cached(field: &Field) -> Analysis {
+    timing::time(Step::Solver, || analyze_from_cache(field))
+}
+
+fn analyze_from_cache(field: &Field) -> Analysis {
+    let mut cache = field.solver.borrow_mut();
+    let Cache { knowledge, changed } = &mut *cache;
+    let knowledge = knowledge.get_or_insert_with(|| {
+        changed.clear();
+        changed.extend(cells(field));
+        start(field)
+    });
+
+    let mut around = Vec::new();
+    for (row, col) in changed.drain(..) {
+        if field.board[row][col].is_exploded() {
+            knowledge[row][col] = Knowledge::Mine;
+        }
+        around.push((row, col));
+        around.extend(field.neighbors((row, col)));
+    }
+    deduce(field, knowledge, around);
+    collect(field, knowledge)
+}
+
+/// Every cell of the field, as (row, col).
+fn cells(field: &impl Board) -> impl Iterator<Item = (usize, usize)> {
+    let (rows, cols) = field.size();
+    (0..rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+}
+
+/// What's known before anything is deduced: only that the exploded cells are mines.
+fn start(field: &impl Board) -> Vec<Vec<Knowledge>> {
+    let (rows, cols) = field.size();
+    (0..rows)
+        .map(|row| (0..cols)
+            .map(|col| if field.is_exploded((row, col)) { Knowledge::Mine } else { Knowledge::Unknown })
+            .collect()
+        )
+        .collect()
+}
+
+/// Apply the rules to the numbers among the given cells, and then to the numbers around anything deduced, until
+/// nothing changes. The rules only ever add to what's known, so it doesn't matter which number goes first.
+fn deduce(field: &impl Board, knowledge: &mut [Vec<Knowledge>], cells: impl IntoIterator<Item = (usize, usize)>) {
+    let (rows, cols) = field.size();
+    let mut queued = vec![vec![false; cols]; rows];
+    let mut queue = Vec::new();
+    for (row, col) in cells {
+        if !queued[row][col] {
+            queued[row][col] = true;
+            queue.push((row, col));
+        }
+    }
+
+    while let Some((row, col)) = queue.pop() {
+        queued[row][col] = false;
+        let Some(number) = field.shown_number((row, col)) else { continue };
+
+        let hidden = field.neighbors((row, col))
+            .filter(|&neighbor| field.is_hidden(neighbor) || field.is_exploded(neighbor))
+            .collect::<Vec<_>>();
+        let mines = hidden.iter().filter(|&&(r, c)| knowledge[r][c] == Knowledge::Mine).count();
+        let unknown = hidden.into_iter()
+            .filter(|&(r, c)| knowledge[r][c] == Knowledge::Unknown)
+            .collect::<Vec<_>>();
+        if unknown.is_empty() {
+            continue;
+        }
+
+        let deduced = if number as usize == mines {
+            Knowledge::Safe
+        } else if number as usize - mines == unknown.len() {
+            Knowledge::Mine
+        } else {
+            continue;
+        };
+        for (r, c) in unknown {
+            knowledge[r][c] = deduced;
+            // The numbers around a deduced cell might now say something new
+            for (nr, nc) in field.neighbors((r, c)) {
+                if !queued[nr][nc] {
+                    queued[nr][nc] = true;
+                    queue.push((nr, nc));
+                }
+            }
+        }
+    }
+}
+
+/// Read what's known about the hidden cells off the knowledge grid.
+fn collect(field: &impl Board, knowledge: &[Vec<Knowledge>]) -> Analysis {
+    let mut analysis = Analysis::default();
+    for (row, cells) in knowledge.iter().enumerate() {
+        for (col, known) in cells.iter().enumerate() {
+            if !field.is_hidden((row, col)) {
+                continue;
+            }
+            match known {
+                Knowledge::Safe => analysis.safe.push((row, col)),
+                Knowledge::Mine => analysis.mines.push((row, col)),
+                Knowledge::Unknown => {},
+            }
+        }
+    }
+    analysis
+}
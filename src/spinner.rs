@@ -0,0 +1,57 @@
+use std::io::{stdout, IsTerminal, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use crossterm::{
+    queue,
+    terminal::{Clear, ClearType},
+};
+
+use crate::tui::ProgressBar;
+
+/// How long the work can take before the spinner turns up, so that quick work doesn't flash one on screen.
+const DELAY: Duration = Duration::from_millis(200);
+/// How long each frame of the spinner is shown.
+const FRAME: Duration = Duration::from_millis(100);
+/// How long the spinner's bar is.
+const WIDTH: usize = 10;
+
+/// Do the given work, with a spinning progress bar and the message at the start of the cursor's line if it takes a
+/// while (as laying out a dense no-guess board can). The line is cleared again once the work is done. Nothing is shown
+/// if the output isn't a terminal, and with `reduced_motion` only the message is, without the bar moving.
+pub(crate) fn spin_while<T>(message: &str, reduced_motion: bool, work: impl FnOnce() -> T) -> T {
+    if !stdout().is_terminal() {
+        return work();
+    }
+    let (done, finished) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            if finished.recv_timeout(DELAY) != Err(RecvTimeoutError::Timeout) {
+                return;
+            }
+            // The spinner is only there to show something is happening, so failing to draw it isn't worth reporting
+            let mut out = stdout();
+            if reduced_motion {
+                let _ = write!(out, "\r{}", message);
+                let _ = queue!(out, Clear(ClearType::UntilNewLine));
+                let _ = out.flush();
+                let _ = finished.recv();
+            } else {
+                for frame in 0.. {
+                    let _ = write!(out, "\r{} {}", ProgressBar::indeterminate(frame, WIDTH).line(), message);
+                    let _ = queue!(out, Clear(ClearType::UntilNewLine));
+                    let _ = out.flush();
+                    if finished.recv_timeout(FRAME) != Err(RecvTimeoutError::Timeout) {
+                        break;
+                    }
+                }
+            }
+            let _ = write!(out, "\r");
+            let _ = queue!(out, Clear(ClearType::CurrentLine));
+            let _ = out.flush();
+        });
+        let result = work();
+        drop(done);
+        result
+    })
+}
@@ -0,0 +1,415 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+use crate::preset::Preset;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The result of one finished single-player game.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct GameRecord {
+    /// When the game ended, in seconds since the Unix epoch.
+    pub(crate) timestamp: u64,
+    /// The preset the board was generated from, or None for a custom board.
+    pub(crate) preset: Option<Preset>,
+    /// The board size, as (rows, columns).
+    pub(crate) size: (usize, usize),
+    pub(crate) mines: usize,
+    pub(crate) won: bool,
+    /// Time from the first reveal to the end of the game, plus any penalties.
+    pub(crate) time_ms: u64,
+    pub(crate) bbbv: usize,
+    pub(crate) clicks: usize,
+    /// How many times a flag was placed, if known (older records don't have it).
+    #[serde(default)]
+    pub(crate) flags: Option<usize>,
+    /// Where the mine the game was lost on was, as (row, col), if it was lost on one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) death: Option<(usize, usize)>,
+    /// The difficulty level the board was picked at, for games played in adaptive mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) adaptive_level: Option<f64>,
+    /// Time added to `time_ms` as penalties, in hardcore mode.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub(crate) penalty_ms: u64,
+    /// The daily challenge the game was, if it was one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) daily: Option<DailyRun>,
+}
+
+/// Which day's daily challenge a game was. Only those played on their day are kept, as past days' are played from the
+/// archive on boards known in advance.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct DailyRun {
+    /// The day, like `2024-03-01`.
+    pub(crate) date: String,
+}
+
+fn is_zero(ms: &u64) -> bool {
+    *ms == 0
+}
+
+impl GameRecord {
+    /// Ranked games count towards the rating: those played on a standard preset.
+    pub(crate) fn ranked(&self) -> bool {
+        self.preset.is_some()
+    }
+}
+
+/// A finished run of the time trial ladder: every preset won in turn, from Beginner to Expert.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LadderRecord {
+    /// When the last game ended, in seconds since the Unix epoch.
+    pub(crate) timestamp: u64,
+    /// The time on each preset, in order, penalties and all.
+    pub(crate) times_ms: Vec<u64>,
+}
+
+impl LadderRecord {
+    pub(crate) fn total_ms(&self) -> u64 {
+        self.times_ms.iter().sum()
+    }
+}
+
+/// The history of finished games, stored as one JSON record per line, and of ladder runs, stored the same way in a
+/// file of their own.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub(crate) games: Vec<GameRecord>,
+    pub(crate) ladders: Vec<LadderRecord>,
+    /// How many records couldn't be read (as when a crash cut one off partway through writing it), and were skipped.
+    pub(crate) damaged: usize,
+}
+
+impl Stats {
+    fn path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("stats.jsonl"))
+    }
+
+    fn ladder_path() -> Option<PathBuf> {
+        Some(paths::data_dir()?.join("ladder.jsonl"))
+    }
+
+    /// Load the stats files. A missing file (or no known data directory) is an empty history. Records that can't be
+    /// read are skipped, and counted in `damaged`.
+    pub(crate) fn load() -> IoResult<Self> {
+        let (games, damaged_games) = recover(Self::path())?;
+        let (ladders, damaged_ladders) = recover(Self::ladder_path())?;
+        Ok(Self { games, ladders, damaged: damaged_games + damaged_ladders })
+    }
+
+    /// Parse the contents of a stats file. Malformed records are an [`ErrorKind::InvalidData`] error.
+    pub fn parse(reader: impl BufRead) -> IoResult<Self> {
+        let games = records(reader)?.into_iter().map(|(_, record)| record).collect::<Result<_, _>>()?;
+        Ok(Self { games, ladders: Vec::new(), damaged: 0 })
+    }
+
+    /// Add a finished game to the stats file, creating it if needed. Returns how many damaged records were dropped
+    /// from it (they're kept in a copy of the file as it was).
+    pub(crate) fn record(game: &GameRecord) -> IoResult<usize> {
+        append(Self::path(), std::slice::from_ref(game))
+    }
+
+    /// Add a finished ladder run to the ladder file, like [`Stats::record`].
+    pub(crate) fn record_ladder(ladder: &LadderRecord) -> IoResult<usize> {
+        append(Self::ladder_path(), std::slice::from_ref(ladder))
+    }
+
+    /// Add the games in a CSV export from another minesweeper client (like Minesweeper Arbiter or Viennasweeper) to the
+    /// stats file. Columns are found by their header, in any order, so the way each client names them is all that
+    /// matters:
+    ///
+    /// - the level (`level`, `mode` or `difficulty`), as `beginner`, `intermediate` or `expert` (or the start of one),
+    /// - the time in seconds (`time`, `seconds` or `rtime`),
+    /// - optionally the 3BV (`3bv` or `bbbv`), clicks (`clicks` or `cl`), when it was played (`date`, `timestamp` or
+    ///   `played`, as `YYYY-MM-DD` or `DD.MM.YYYY`, with a time of day after it or not) and whether it was won
+    ///   (`result`, `won` or `status`). Games count as won without one, since most clients only keep the games that
+    ///   were.
+    ///
+    /// Other levels, and games already in the stats (played at the same time, on the same level, in the same time),
+    /// are skipped. Games with no date can't be told apart like that, so they're all imported. Returns how many games
+    /// were imported and how many rows were skipped.
+    pub(crate) fn import(reader: impl BufRead) -> IoResult<(usize, usize)> {
+        let mut lines = reader.lines();
+        let Some(header) = lines.next().transpose()? else { return Ok((0, 0)) };
+        // Clients set up for languages that write decimals with commas separate their columns with semicolons
+        let separator = if header.contains(';') && !header.contains(',') { ';' } else { ',' };
+        // Windows programs often start their files with a byte order mark
+        let header = split_csv(header.trim_start_matches('\u{feff}'), separator);
+        let column = |names: &[&str]| header.iter().position(|name| names.contains(&name.to_ascii_lowercase().trim()));
+        let invalid = |message: &str| std::io::Error::new(ErrorKind::InvalidData, message.to_string());
+        let level = column(&["level", "mode", "difficulty"]).ok_or_else(|| invalid("there's no level column"))?;
+        let time = column(&["time", "seconds", "rtime"]).ok_or_else(|| invalid("there's no time column"))?;
+        let (bbbv, clicks) = (column(&["3bv", "bbbv"]), column(&["clicks", "cl"]));
+        let (date, result) = (column(&["date", "timestamp", "played"]), column(&["result", "won", "status"]));
+
+        let existing = Self::load()?.games;
+        let (mut games, mut skipped) = (Vec::new(), 0);
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let row = split_csv(&line, separator);
+            let field = |i: Option<usize>| i.and_then(|i| row.get(i)).map(|field| field.trim());
+            let preset = field(Some(level)).and_then(|level| Preset::ALL.into_iter().find(|preset| {
+                let level = level.to_ascii_lowercase();
+                level.len() >= 3 && preset.name().to_ascii_lowercase().starts_with(&level)
+            }));
+            let time_ms = field(Some(time))
+                .and_then(|time| time.replace(',', ".").parse::<f64>().ok())
+                .filter(|time| time.is_finite() && *time >= 0.0)
+                .map(|time| (time * 1000.0).round() as u64);
+            let (Some(preset), Some(time_ms)) = (preset, time_ms) else {
+                skipped += 1;
+                continue;
+            };
+            let game = GameRecord {
+                timestamp: field(date).and_then(parse_date).unwrap_or(0),
+                preset: Some(preset),
+                size: preset.size(),
+                mines: preset.mines(),
+                won: field(result).is_none_or(|result| {
+                    ["won", "win", "yes", "true", "1"].contains(&result.to_ascii_lowercase().as_str())
+                }),
+                time_ms,
+                bbbv: field(bbbv).and_then(|bbbv| bbbv.parse().ok()).unwrap_or(0),
+                clicks: field(clicks).and_then(|clicks| clicks.parse().ok()).unwrap_or(0),
+                flags: None,
+                death: None,
+                adaptive_level: None,
+                penalty_ms: 0,
+                daily: None,
+            };
+            let imported = |other: &&GameRecord| {
+                game.timestamp != 0
+                    && other.timestamp == game.timestamp
+                    && other.preset == game.preset
+                    && other.time_ms == game.time_ms
+            };
+            if existing.iter().chain(&games).any(|other| imported(&other)) {
+                skipped += 1;
+                continue;
+            }
+            games.push(game);
+        }
+        append(Self::path(), &games)?;
+        Ok((games.len(), skipped))
+    }
+
+    pub(crate) fn ranked(&self) -> impl Iterator<Item = &GameRecord> {
+        self.games.iter().filter(|game| game.ranked())
+    }
+
+    /// Write every game as a row of a table, oldest first, for looking at in a spreadsheet or other tools.
+    pub(crate) fn export(&self, format: ExportFormat, mut out: impl Write) -> IoResult<()> {
+        let rows = self.games.iter().map(ExportRow::new);
+        match format {
+            ExportFormat::Csv => {
+                writeln!(out, "date,preset,rows,columns,mines,result,time_ms,3bv,clicks")?;
+                for row in rows {
+                    writeln!(
+                        out,
+                        "{},{},{},{},{},{},{},{},{}",
+                        row.date, row.preset, row.rows, row.columns, row.mines, row.result, row.time_ms, row.bbbv,
+                        row.clicks,
+                    )?;
+                }
+            },
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &rows.collect::<Vec<_>>())?;
+                writeln!(out)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Read one JSON record per line, skipping blank lines, with the line each came from. Lines are read as bytes, since a
+/// crash can leave anything at all in a file.
+fn records<T: for<'de> Deserialize<'de>>(reader: impl BufRead) -> IoResult<Vec<(Vec<u8>, serde_json::Result<T>)>> {
+    let mut records = Vec::new();
+    for line in reader.split(b'\n') {
+        let line = line?;
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_slice(&line);
+        records.push((line, record));
+    }
+    Ok(records)
+}
+
+/// Read the records in the file at the given path, if there's one there, with how many were skipped for being
+/// malformed.
+fn recover<T: for<'de> Deserialize<'de>>(path: Option<PathBuf>) -> IoResult<(Vec<T>, usize)> {
+    let Some(path) = path else { return Ok((Vec::new(), 0)) };
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e),
+    };
+    let (read, damaged): (Vec<_>, Vec<_>) = records(BufReader::new(file))?.into_iter()
+        .map(|(_, record)| record)
+        .partition(Result::is_ok);
+    Ok((read.into_iter().flatten().collect(), damaged.len()))
+}
+
+/// Add records to the end of the file at the given path as lines of JSON, creating it if needed. The file is written
+/// afresh with [`paths::write_safely`], so a crash can't leave it half-written, and malformed records (from before
+/// there was a backup) are dropped from it. Returns how many were. The file as it was is copied to one named for when
+/// they were dropped first, as the backup only lasts until the next save. Nothing is written if there's no path (as
+/// when there's no known data directory).
+///
+/// The file isn't locked, so if two termsweepers add to it at once, the records of whichever writes it first can be
+/// lost: each reads it, then writes it back with only its own added.
+fn append<T: Serialize + for<'de> Deserialize<'de>>(path: Option<PathBuf>, new: &[T]) -> IoResult<usize> {
+    let Some(path) = path else { return Ok(0) };
+    let existing = match File::open(&path) {
+        Ok(file) => records::<T>(BufReader::new(file))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    // Records are kept as they were written, so fields from newer versions of termsweeper aren't lost
+    let mut contents = Vec::new();
+    let mut dropped = 0;
+    for (line, read) in existing {
+        match read {
+            Ok(_) => {
+                contents.extend(line);
+                contents.push(b'\n');
+            },
+            Err(_) => dropped += 1,
+        }
+    }
+    for record in new {
+        writeln!(contents, "{}", serde_json::to_string(record)?)?;
+    }
+    if dropped > 0 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        let mut copy = path.clone().into_os_string();
+        copy.push(format!(".damaged-{}", now));
+        fs::copy(&path, copy)?;
+    }
+    paths::write_safely(&path, &contents)?;
+    Ok(dropped)
+}
+
+/// What `stats --export` writes the history as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Csv,
+    /// An array with an object per game.
+    Json,
+}
+
+impl ExportFormat {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A game, as exported.
+#[derive(Serialize)]
+struct ExportRow {
+    /// When the game ended, as an ISO 8601 date and time in UTC.
+    date: String,
+    /// The preset's name, or `custom`.
+    preset: String,
+    rows: usize,
+    columns: usize,
+    mines: usize,
+    /// `won` or `lost`.
+    result: &'static str,
+    time_ms: u64,
+    #[serde(rename = "3bv")]
+    bbbv: usize,
+    clicks: usize,
+}
+
+impl ExportRow {
+    fn new(game: &GameRecord) -> Self {
+        Self {
+            date: utc_date(game.timestamp),
+            preset: game.preset.map_or("custom".to_string(), |preset| preset.name().to_ascii_lowercase()),
+            rows: game.size.0,
+            columns: game.size.1,
+            mines: game.mines,
+            result: if game.won { "won" } else { "lost" },
+            time_ms: game.time_ms,
+            bbbv: game.bbbv,
+            clicks: game.clicks,
+        }
+    }
+}
+
+/// The fields of a row of CSV, with quotes around a field taken off (and doubled quotes inside one made single).
+fn split_csv(line: &str, separator: char) -> Vec<String> {
+    let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// A date like `2024-03-01`, or `01.03.2024` as it's written in much of Europe, optionally followed by a time of day
+/// like `12:00:00` (after a space or a `T`), as a Unix timestamp. Taken to be in UTC, since exports rarely say.
+pub(crate) fn parse_date(date: &str) -> Option<u64> {
+    let (date, time) = date.split_once([' ', 'T']).unwrap_or((date, ""));
+    let parts = date.split(['-', '/', '.']).map(|part| part.parse::<u64>().ok()).collect::<Option<Vec<_>>>()?;
+    let (year, month, day) = match parts[..] {
+        // Dates with dots are usually written the other way round, though not always
+        [day, month, year] if date.contains('.') && day <= 31 => (year, month, day),
+        [year, month, day] => (year, month, day),
+        _ => return None,
+    };
+    if !(1970..10_000).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut time = time.trim_end_matches('Z').split(':').map(|part| part.parse::<f64>().ok().unwrap_or(0.0));
+    let seconds = time.next().unwrap_or(0.0) * 3600.0 + time.next().unwrap_or(0.0) * 60.0 + time.next().unwrap_or(0.0);
+    // The days-to-civil algorithm in `utc_date`, run backwards
+    let year = if month <= 2 { year - 1 } else { year };
+    let (era, year_of_era) = (year / 400, year % 400);
+    let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86400 + seconds as u64)
+}
+
+/// A Unix timestamp as an ISO 8601 date and time in UTC, like `2024-03-01T12:00:00Z`.
+pub(crate) fn utc_date(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86400, timestamp % 86400);
+    // Howard Hinnant's days-to-civil algorithm, counting in 400-year eras from 0000-03-01
+    let days = days + 719_468;
+    let (era, day_of_era) = (days / 146_097, days % 146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60,
+    )
+}
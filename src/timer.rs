@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+
+/// Measures the wall-clock time that passes between laps, for feeding to the engine's game clock. Time spent paused
+/// doesn't count.
+#[derive(Debug)]
+pub(crate) struct Timer {
+    last_lap: Instant,
+    paused: bool,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self { last_lap: Instant::now(), paused: false }
+    }
+}
+
+impl Timer {
+    /// Time since the last lap, or zero while paused.
+    pub(crate) fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let lap = if self.paused { Duration::ZERO } else { now - self.last_lap };
+        self.last_lap = now;
+        lap
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub(crate) fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.last_lap = Instant::now();
+        }
+    }
+
+    pub(crate) fn paused(&self) -> bool {
+        self.paused
+    }
+}
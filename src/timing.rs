@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// The steps that are timed with `--verbose`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Step {
+    /// Laying out a whole board, with whichever generator.
+    Generation,
+    /// Checking whether a candidate no-guess layout can be cleared by logic alone.
+    NoGuessCheck,
+    /// Asking the solver about a board in play, for the assists, the mistake warning or the computer opponent.
+    Solver,
+}
+
+impl Step {
+    fn name(self) -> &'static str {
+        match self {
+            Step::Generation => "board generation",
+            Step::NoGuessCheck => "no-guess check",
+            Step::Solver => "solver query",
+        }
+    }
+}
+
+/// How often a step was done, and how long it took.
+#[derive(Copy, Clone, Default)]
+struct Timings {
+    count: u32,
+    total: Duration,
+    longest: Duration,
+}
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// Shared between threads, since no-guess layouts can be checked on several at once.
+static TIMINGS: Mutex<BTreeMap<Step, Timings>> = Mutex::new(BTreeMap::new());
+/// How long each key press took to show on screen, by the kind of action it was.
+static LATENCIES: Mutex<BTreeMap<&'static str, Vec<Duration>>> = Mutex::new(BTreeMap::new());
+
+/// Start timing the steps, for `--verbose`. Until then, nothing is timed.
+pub(crate) fn enable() {
+    VERBOSE.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if timing is on.
+pub(crate) fn enabled() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Count how long a key press of the given kind (like `reveal`) took to show: from the game getting the key to the
+/// frame it changed being flushed to the terminal. Nothing is counted unless timing is on.
+pub(crate) fn latency(action: &'static str, took: Duration) {
+    if !enabled() {
+        return;
+    }
+    LATENCIES.lock().unwrap_or_else(|e| e.into_inner()).entry(action).or_default().push(took);
+}
+
+/// The median and 99th percentile key press latency so far, over every kind of action, if there's been a key press.
+pub(crate) fn latency_percentiles() -> Option<(Duration, Duration)> {
+    let latencies = LATENCIES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all = latencies.values().flatten().copied().collect::<Vec<_>>();
+    all.sort();
+    Some((percentile(&all, 50)?, percentile(&all, 99)?))
+}
+
+/// The latency that the given percentage of the sorted latencies are at or under, unless there are none.
+fn percentile(sorted: &[Duration], percent: usize) -> Option<Duration> {
+    let rank = (sorted.len() * percent).div_ceil(100);
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+/// Do the given work, timing it as the given step if timing is on.
+pub(crate) fn time<T>(step: Step, work: impl FnOnce() -> T) -> T {
+    if !VERBOSE.load(Ordering::Relaxed) {
+        return work();
+    }
+    let start = Instant::now();
+    let result = work();
+    let took = start.elapsed();
+    let mut timings = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let timings = timings.entry(step).or_default();
+    timings.count += 1;
+    timings.total += took;
+    timings.longest = timings.longest.max(took);
+    result
+}
+
+/// Print how long each step took to stderr, if timing is on. Called once the terminal is back to normal, since the
+/// game is drawn over anything printed while it's running.
+pub(crate) fn report() {
+    if !VERBOSE.load(Ordering::Relaxed) {
+        return;
+    }
+    let timings = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    eprintln!("{:<16} {:>6} {:>10} {:>10} {:>10}", "step", "count", "total", "mean", "longest");
+    for (step, timings) in timings.iter() {
+        let mean = timings.total / timings.count;
+        eprintln!(
+            "{:<16} {:>6} {:>10} {:>10} {:>10}",
+            step.name(), timings.count,
+            format!("{:.2?}", timings.total), format!("{:.2?}", mean), format!("{:.2?}", timings.longest),
+        );
+    }
+
+    let mut latencies = LATENCIES.lock().unwrap_or_else(|e| e.into_inner());
+    if latencies.is_empty() {
+        return;
+    }
+    eprintln!();
+    eprintln!("{:<16} {:>6} {:>10} {:>10} {:>10}", "key press", "count", "p50", "p99", "longest");
+    for (action, latencies) in latencies.iter_mut() {
+        latencies.sort();
+        let [p50, p99] = [50, 99].map(|percent| format!("{:.2?}", percentile(latencies, percent).unwrap_or_default()));
+        let longest = format!("{:.2?}", latencies.last().copied().unwrap_or_default());
+        eprintln!("{:<16} {:>6} {:>10} {:>10} {:>10}", action, latencies.len(), p50, p99, longest);
+    }
+}
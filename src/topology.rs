@@ -0,0 +1,148 @@
+use crate::console;
+use crate::game::{Cell, Direction};
+
+/// How the cells of a board fit together: which of them are neighbors, and how they're drawn. Every board is stored as
+/// a grid of (row, col) positions either way; the topology decides what those positions mean.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Topology {
+    /// The classic square grid, where each cell touches the (up to) 8 around it.
+    #[default]
+    Square,
+    /// A 3D board of stacked layers, stored side by side in the grid: each layer is an equal share of the columns,
+    /// with the top layer on the left. Each cell touches the (up to) 26 around it, including on the layers directly
+    /// above and below.
+    Layered {
+        /// How many layers the board has.
+        layers: usize,
+    },
+    /// A tiling of triangles, pointing up and down in turn: the cell at (row, col) points up if row + col is even.
+    /// Each cell touches the (up to) 12 that share an edge or a corner with it. The cursor moves around the grid as on
+    /// a square board, since the cells above, below and beside a triangle always touch it.
+    Triangular,
+}
+
+impl Topology {
+    /// Returns true if a board of the given size can have this topology.
+    pub fn fits(self, size: (usize, usize)) -> bool {
+        match self {
+            Topology::Square | Topology::Triangular => true,
+            Topology::Layered { layers } => layers > 0 && size.1.is_multiple_of(layers),
+        }
+    }
+
+    /// How many layers the board has: just the one, unless it's layered.
+    fn layers(self) -> usize {
+        match self {
+            Topology::Square | Topology::Triangular => 1,
+            Topology::Layered { layers } => layers,
+        }
+    }
+
+    /// Returns true if the cell is a triangle pointing up, rather than down.
+    pub(crate) fn points_up(pos: (usize, usize)) -> bool {
+        (pos.0 + pos.1).is_multiple_of(2)
+    }
+
+    /// The in-bounds neighbors of a cell on a board of the given size, as (row, col).
+    pub fn neighbors(self, size: (usize, usize), pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let layers = self.layers();
+        let width = size.1 / layers;
+        let (layer, col) = (pos.1 / width, pos.1 % width);
+        let depth = if layers > 1 { -1isize..=1 } else { 0..=0 };
+        // A triangle's neighbors reach two columns out along its own row and its flat side, but only one column out on
+        // the side it points towards
+        let reach = move |dr: isize| match self {
+            Topology::Triangular if dr != 0 && (dr == -1) == Self::points_up(pos) => 1,
+            Topology::Triangular => 2,
+            _ => 1,
+        };
+        depth
+            .flat_map(move |dl| (-1isize..=1).flat_map(move |dr| (-reach(dr)..=reach(dr)).map(move |dc| (dl, dr, dc))))
+            .filter(|&offset| offset != (0, 0, 0))
+            .filter_map(move |(dl, dr, dc)| {
+                let row = pos.0.checked_add_signed(dr).filter(|&row| row < size.0)?;
+                let layer = layer.checked_add_signed(dl).filter(|&layer| layer < layers)?;
+                let col = col.checked_add_signed(dc).filter(|&col| col < width)?;
+                Some((row, layer * width + col))
+            })
+    }
+
+    /// Where one step in the given direction goes from `pos`. Returns None if it would leave the board, or the layer
+    /// (except when moving between layers).
+    pub(crate) fn step(
+        self,
+        size: (usize, usize),
+        pos: (usize, usize),
+        direction: Direction,
+    ) -> Option<(usize, usize)> {
+        let width = size.1 / self.layers();
+        let next = match direction {
+            Direction::PrevLayer => (pos.0, pos.1.checked_sub(width)?),
+            Direction::NextLayer => (pos.0, pos.1 + width),
+            _ => direction.offset(pos).filter(|next| next.1 / width == pos.1 / width)?,
+        };
+        (next.0 < size.0 && next.1 < size.1).then_some(next)
+    }
+
+    /// Like [`Topology::step`], but a step past an edge of the layer comes round to the opposite edge instead. Moving
+    /// between layers doesn't wrap.
+    pub(crate) fn wrapping_step(
+        self,
+        size: (usize, usize),
+        pos: (usize, usize),
+        direction: Direction,
+    ) -> Option<(usize, usize)> {
+        if let Direction::PrevLayer | Direction::NextLayer = direction {
+            return self.step(size, pos, direction);
+        }
+        let width = size.1 / self.layers();
+        let (dx, dy) = direction.delta();
+        let wrap = |at: usize, by: i16, len: usize| (at as isize + by as isize).rem_euclid(len as isize) as usize;
+        let (layer, col) = (pos.1 / width, pos.1 % width);
+        Some((wrap(pos.0, dy, size.0), layer * width + wrap(col, dx, width)))
+    }
+
+    /// Where a cell is drawn, as (x, y) from the top left of the board.
+    pub(crate) fn screen_pos(self, size: (usize, usize), pos: (usize, usize)) -> (usize, usize) {
+        // Layers are drawn one column apart
+        (pos.1 + pos.1 / (size.1 / self.layers()), pos.0)
+    }
+
+    /// How much room a board of the given size takes up when drawn, as (width, height).
+    pub(crate) fn screen_size(self, size: (usize, usize)) -> (usize, usize) {
+        (size.1 + self.layers() - 1, size.0)
+    }
+
+    /// Draw a single cell.
+    pub(crate) fn glyph(self, pos: (usize, usize), cell: &Cell) -> String {
+        match self {
+            // Hidden triangles show which way they point; everything else looks the same as on a square board
+            Topology::Triangular if cell.is_hidden() && !cell.is_flagged() => {
+                let [up, down] = console::glyphs().triangles;
+                if Self::points_up(pos) { up } else { down }.to_string()
+            },
+            _ => cell.to_string(),
+        }
+    }
+
+    /// Draw the given row of cells.
+    pub(crate) fn render_row(self, row: usize, cells: &[Cell]) -> String {
+        self.render_row_with(row, cells, |_, glyph| glyph)
+    }
+
+    /// Draw the given row of cells, passing each cell's glyph through `style` (e.g. to color it). Styles can add
+    /// escape codes, but shouldn't change how many columns a glyph takes up.
+    pub(crate) fn render_row_with(self, row: usize, cells: &[Cell], style: impl Fn(&Cell, String) -> String) -> String {
+        let layer_width = cells.len() / self.layers();
+        cells.chunks(layer_width)
+            .enumerate()
+            .map(|(layer, cells)| cells.iter()
+                .enumerate()
+                .map(|(col, cell)| style(cell, self.glyph((row, layer * layer_width + col), cell)))
+                .collect::<String>()
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
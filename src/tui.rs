@@ -1,8 +1,61 @@
-use std::iter::repeat;
+use std::collections::HashMap;
+use std::iter::repeat_n;
+use std::time::Duration;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Colored, Stylize};
 use figlet_rs::FIGfont;
-use once_cell::unsync::OnceCell;
+use unicode_width::UnicodeWidthStr;
 
-use crate::game::Field;
+use crate::achievements::{self, Achievement};
+use crate::adaptive;
+use crate::boss::BossScreen;
+use crate::console;
+use crate::end_menu::EndMenu;
+use crate::game::{Cell, Field};
+use crate::keymap::{self, ControlPreset, Keymap};
+use crate::locale;
+use crate::preset::Preset;
+use crate::rating;
+use crate::solver;
+use crate::settings::Settings;
+use crate::stats::{GameRecord, LadderRecord, Stats};
+use crate::versus::Versus;
+
+/// How many columns the text takes up on screen: full-width characters (as in CJK text) take two, and combining
+/// characters and escape codes (like the colors borders are drawn in) none, so this is what lines things up rather than
+/// the number of characters.
+pub(crate) fn text_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = &rest[start + escape_len(&rest[start..])..];
+    }
+    width + rest.width()
+}
+
+/// How long the escape code the text starts with is: `ESC [`, then its parameters, up to the letter that ends it.
+fn escape_len(text: &str) -> usize {
+    match text.strip_prefix("\x1b[") {
+        Some(body) => body.find(|c: char| ('@'..='~').contains(&c)).map_or(text.len(), |end| end + 3),
+        None => 1,
+    }
+}
+
+/// The text, followed by enough spaces to take up `width` columns.
+pub(crate) fn pad_end(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text_width(text))))
+}
+
+/// The text, after enough spaces to take up `width` columns.
+pub(crate) fn pad_start(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(text_width(text))), text)
+}
+
+/// A time as minutes and seconds, like the game clock shows it.
+pub(crate) fn format_time(time: Duration) -> String {
+    format!("{}:{:02}", time.as_secs() / 60, time.as_secs() % 60)
+}
 
 pub(crate) trait Component {
     /// Render the component into the buffer, starting at the start of the slice and at the end of the Strings. If the
@@ -11,9 +64,59 @@ pub(crate) trait Component {
     fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String];
     fn width(&self) -> usize;
     fn height(&self) -> usize;
+
+    /// React to an event while the component has focus. Most components only show things, and ignore everything.
+    fn handle_event(&mut self, _event: &Event) -> Handled {
+        Handled::Ignored
+    }
+}
+
+/// What a component did with an event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Handled {
+    /// It wasn't for the component, so it goes on to whatever is under it.
+    Ignored,
+    /// The component used it up.
+    Consumed,
+    /// The component is done, and should be closed.
+    Closed,
+}
+
+/// A component with a box drawn around it, in the border style and color from the config, and with a title in the top
+/// border if it's given one.
+pub(crate) struct BoxedComponent<'a, T: Component> {
+    inner: &'a T,
+    title: Option<&'a str>,
+}
+
+impl<'a, T: Component> BoxedComponent<'a, T> {
+    pub(crate) fn new(inner: &'a T) -> Self {
+        Self { inner, title: None }
+    }
+
+    /// Put the title in the top border. The box is widened to fit it, if it has to be.
+    pub(crate) fn titled(self, title: &'a str) -> Self {
+        Self { title: Some(title), ..self }
+    }
+
+    /// How wide the inside of the box is: as wide as the component, or as the title with a border either side of it.
+    fn inner_width(&self) -> usize {
+        let title_width = self.title.map_or(0, |title| text_width(title) + 3);
+        self.inner.width().max(title_width)
+    }
 }
 
-pub(crate) struct BoxedComponent<'a, T: Component>(pub(crate) &'a T);
+/// The border characters, in the border color if there is one. Only the text color is reset after them, so that they
+/// can be dimmed along with everything else.
+fn paint(border: impl Iterator<Item = char>) -> String {
+    let border = border.collect::<String>();
+    match console::border_color() {
+        Some(color) => format!(
+            "\x1b[{}m{}\x1b[{}m", Colored::ForegroundColor(color), border, Colored::ForegroundColor(Color::Reset),
+        ),
+        None => border,
+    }
+}
 
 impl<'a, T: Component> Component for BoxedComponent<'a, T> {
     fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
@@ -23,166 +126,1088 @@ impl<'a, T: Component> Component for BoxedComponent<'a, T> {
             return buffer;
         }
 
-        let inner_width = self.0.width();
+        let inner_width = self.inner_width();
 
-        buffer[0].push('╭');
-        buffer[0].extend(repeat('─').take(inner_width));
-        buffer[0].push('╮');
+        let [top_left, top_right, bottom_right, bottom_left, horizontal, vertical] = console::borders();
+        match self.title {
+            Some(title) => {
+                buffer[0].push_str(&paint([top_left, horizontal].into_iter()));
+                buffer[0].push_str(&format!(" {} ", title));
+                let rest = inner_width - text_width(title) - 3;
+                buffer[0].push_str(&paint(repeat_n(horizontal, rest).chain([top_right])));
+            },
+            None => {
+                let top = [top_left].into_iter().chain(repeat_n(horizontal, inner_width)).chain([top_right]);
+                buffer[0].push_str(&paint(top));
+            },
+        }
 
-        let inner_height = self.0.height();
+        let inner_height = self.inner.height();
 
         for line in buffer.iter_mut().skip(1).take(inner_height) {
-            line.push('│');
+            line.push_str(&paint([vertical].into_iter()));
         }
 
-        self.0.render_at(&mut buffer[1..buffer_len.min(inner_height + 1)]);
+        self.inner.render_at(&mut buffer[1..buffer_len.min(inner_height + 1)]);
 
+        // A title wider than the component leaves room to its right
+        let padding = inner_width - self.inner.width();
         for line in buffer.iter_mut().skip(1).take(inner_height) {
-            line.push('│');
+            line.extend(repeat_n(' ', padding));
+            line.push_str(&paint([vertical].into_iter()));
         }
 
         if buffer_len > inner_height + 1 {
-            buffer[inner_height + 1].push('╰');
-            buffer[inner_height + 1].extend(repeat('─').take(inner_width));
-            buffer[inner_height + 1].push('╯');
+            let bottom = [bottom_left].into_iter().chain(repeat_n(horizontal, inner_width)).chain([bottom_right]);
+            buffer[inner_height + 1].push_str(&paint(bottom));
         }
 
-        if buffer.len() <= inner_height + 2 {
-            buffer
-        } else {
-            &mut buffer[inner_height + 2..]
-        }
+        &mut buffer[buffer_len.min(inner_height + 2)..]
     }
 
     fn width(&self) -> usize {
-        self.0.width() + 2
+        self.inner_width() + 2
     }
 
     fn height(&self) -> usize {
-        self.0.height() + 2
+        self.inner.height() + 2
+    }
+}
+
+
+/// A frame, drawn in layers: the boards and the panels around them first, into [`Layers::base`], then an overlay (like
+/// a question or the help screen) boxed on top of them in the middle of the screen. Anything under the overlay is
+/// covered up, rather than pushed aside, so the rest of the frame stays where it was.
+pub(crate) struct Layers {
+    base: Vec<String>,
+    width: usize,
+    overlay: Option<(Vec<String>, Area, bool)>,
+}
+
+/// A rectangle on screen, in columns and lines from the top left.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Area {
+    pub(crate) x: usize,
+    pub(crate) y: usize,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+}
+
+impl Area {
+    pub(crate) fn contains(&self, (x, y): (usize, usize)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+impl Layers {
+    /// An empty frame for a terminal of the given size, in columns and lines.
+    pub(crate) fn new((width, height): (u16, u16)) -> Self {
+        Self { base: vec![String::new(); height as usize], width: width as usize, overlay: None }
+    }
+
+    /// The lines the boards and panels are drawn into, one after another.
+    pub(crate) fn base(&mut self) -> &mut [String] {
+        &mut self.base
+    }
+
+    /// Put the boxed component over the middle of the screen, replacing any overlay already there. If `dim` is set, the
+    /// rest of the frame is dimmed, to make it clear that the overlay is what's taking input.
+    pub(crate) fn overlay<T: Component>(&mut self, boxed: BoxedComponent<'_, T>, dim: bool) {
+        let height = boxed.height().min(self.base.len());
+        let mut lines = vec![String::new(); height];
+        boxed.render_at(&mut lines);
+        let area = Area {
+            x: self.width.saturating_sub(boxed.width()) / 2,
+            y: (self.base.len() - height) / 2,
+            width: boxed.width(),
+            height,
+        };
+        self.overlay = Some((lines, area, dim));
+    }
+
+    /// What the overlay covers, if there is one, so nothing else is drawn there. One that dims the rest of the frame
+    /// covers all of it, since all of it is behind the overlay.
+    pub(crate) fn covered(&self) -> Option<Area> {
+        self.overlay.as_ref().map(|(_, area, dim)| match dim {
+            true => Area { x: 0, y: 0, width: self.width, height: self.base.len() },
+            false => *area,
+        })
+    }
+
+    /// The finished lines, with the overlay drawn over the base.
+    pub(crate) fn into_lines(self) -> Vec<String> {
+        let Some((overlay, area, dim)) = self.overlay else {
+            return self.base;
+        };
+        let style = |text: String| if dim && !text.is_empty() { text.dim().to_string() } else { text };
+        // An overlay too wide for the screen is cut off at its edge, rather than wrapping onto the next line
+        let room = self.width - area.x;
+        self.base.into_iter()
+            .enumerate()
+            .map(|(row, line)| match row.checked_sub(area.y).and_then(|i| overlay.get(i)) {
+                Some(over) => {
+                    let (before, rest) = split_at_width(&line, area.x);
+                    let (_, after) = split_at_width(&rest, area.width);
+                    format!("{}{}{}", style(pad_end(&before, area.x)), split_at_width(over, room).0, style(after))
+                },
+                None => style(line),
+            })
+            .collect()
     }
 }
 
+/// Split the text at the given column. A full-width character that the column would cut in half is swapped for a space
+/// on each side of it. The last escape code before the column is repeated after it, so a color carries on past it.
+fn split_at_width(text: &str, width: usize) -> (String, String) {
+    let mut before = String::new();
+    let mut escape = "";
+    let mut columns = 0;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            (escape, rest) = rest.split_at(escape_len(rest));
+            before.push_str(escape);
+            continue;
+        }
+        let c_width = text_width(c.encode_utf8(&mut [0; 4]));
+        if columns + c_width > width {
+            return match columns < width {
+                true => (before + " ", format!("{}{}{}", escape, " ".repeat(columns + c_width - width), &rest[1..])),
+                false => (before, format!("{}{}", escape, rest)),
+            };
+        }
+        before.push(c);
+        columns += c_width;
+        rest = &rest[c.len_utf8()..];
+    }
+    (before, String::new())
+}
 
 impl Component for Field {
     fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
-        for (dest, src) in buffer.iter_mut().zip(self.board.iter()) {
-            dest.extend(src.iter().map(|cell| cell.to_string()))
+        let mut rendered = self.rendered.borrow_mut();
+        let rows = buffer.iter_mut().zip(self.board.iter()).zip(rendered.iter_mut()).enumerate();
+        for (row, ((dest, src), cached)) in rows {
+            dest.push_str(cached.get_or_insert_with(|| match self.ranges {
+                None => self.topology.render_row_with(row, src, high_count),
+                Some(_) => (0..src.len()).map(|col| self.glyph((row, col))).collect(),
+            }));
         }
         let buffer_len = buffer.len();
         &mut buffer[buffer_len.min(self.board.len())..]
     }
 
     fn width(&self) -> usize {
-        self.board.get(0).map(|row| row.len()).unwrap_or(0)
+        self.topology.screen_size(self.size()).0 * self.cell_width()
     }
 
     fn height(&self) -> usize {
-        self.board.len()
+        self.topology.screen_size(self.size()).1
     }
 }
 
+/// Color counts past 9, which only come up on boards where cells have more neighbors, so the letters they're shown as
+/// stand out from the digits (and from each other, in bands).
+fn high_count(cell: &Cell, glyph: String) -> String {
+    match cell.shown_number() {
+        Some(10..=14) => glyph.magenta().to_string(),
+        Some(15..=19) => glyph.red().to_string(),
+        Some(20..) => glyph.dark_red().to_string(),
+        _ => glyph,
+    }
+}
 
-pub(crate) struct Controls;
+/// The field with the frontier picked out, to help focus on what matters: the hidden cells next to revealed numbers
+/// are highlighted, the numbers next to them are drawn as usual, and everything else is dimmed.
+pub(crate) struct Frontier<'a> {
+    field: &'a Field,
+    frontier: Vec<Vec<bool>>,
+}
 
-impl Controls {
-    const TEXT: &str = "test";
-
-    const WIDTH: usize = {
-        let mut longest = 0;
-        let mut current = 0;
-        let bytes = Self::TEXT.as_bytes();
-        let mut i = 0;
-        while i < bytes.len() {
-            if bytes[i] == b'\n' {
-                if current > longest {
-                    longest = current;
+impl<'a> Frontier<'a> {
+    pub(crate) fn new(field: &'a Field) -> Self {
+        Self { field, frontier: solver::frontier(field) }
+    }
+}
+
+impl<'a> Component for Frontier<'a> {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let field = self.field;
+        for (row, (dest, cells)) in buffer.iter_mut().zip(field.board.iter()).enumerate() {
+            let mut x = 0;
+            for (col, cell) in cells.iter().enumerate() {
+                // Cells aren't always drawn right next to each other, e.g. between the layers of a layered board
+                let (cell_x, _) = field.screen_pos((row, col));
+                dest.extend(repeat_n(' ', cell_x as usize - x));
+                x = cell_x as usize + field.cell_width();
+
+                let glyph = field.glyph((row, col));
+                let relevant = !cell.is_hidden() && field.neighbors((row, col)).any(|(r, c)| self.frontier[r][c]);
+                if self.frontier[row][col] {
+                    dest.push_str(&glyph.yellow().bold().to_string());
+                } else if relevant {
+                    dest.push_str(&glyph);
+                } else {
+                    dest.push_str(&glyph.dim().to_string());
                 }
-                current = 0;
-            } else if bytes[i] != b'\r' {
-                current += 1;
             }
-            i += 1;
         }
-        if current > longest { current } else { longest }
-    };
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(field.board.len())..]
+    }
 
-    const HEIGHT: usize = {
-        let mut count = if Self::TEXT.is_empty() { 0 } else { 1 };
-        let bytes = Self::TEXT.as_bytes();
-        let mut i = 0;
-        while i < bytes.len() {
-            if bytes[i] == b'\n' {
-                count += 1;
-            }
-            i += 1;
+    fn width(&self) -> usize {
+        self.field.width()
+    }
+
+    fn height(&self) -> usize {
+        self.field.height()
+    }
+}
+
+
+/// The key bindings in use, plus the fixed keys that work everywhere.
+pub(crate) struct Controls(Text);
+
+impl Controls {
+    pub(crate) fn new(keymap: &Keymap, boards: bool, layers: bool, hints: bool) -> Self {
+        let mut help = keymap.help();
+        let mut add = |description, keys: &str| help.push((locale::tr(description), keys.to_string()));
+        if hints {
+            add("control-hint", "?");
         }
-        count
-    };
+        if layers {
+            add("control-layer", "< >");
+        }
+        if boards {
+            add("control-board", "tab");
+        }
+        add("control-export", "ctrl+e");
+        add("control-menu", "esc");
+        add("control-help", "f1");
+        Self(Text::new(keymap::help_lines(&help)))
+    }
 }
 
 impl Component for Controls {
     fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
-        for (dest, src) in buffer.iter_mut().zip(Self::TEXT.lines()) {
+        self.0.render_at(buffer)
+    }
+
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+}
+
+
+impl Component for BossScreen {
+    /// The end of the log, as much as fits.
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let shown = self.lines.len().min(buffer.len());
+        for (dest, line) in buffer.iter_mut().zip(self.lines.range(self.lines.len() - shown..)) {
+            dest.push_str(line);
+        }
+        &mut buffer[shown..]
+    }
+
+    fn width(&self) -> usize {
+        self.lines.iter().map(|line| text_width(line)).max().unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Only the boss key (which the game watches for itself) brings the game back, so everything else is swallowed.
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        match event {
+            Event::Key(_) | Event::FocusGained => Handled::Consumed,
+            _ => Handled::Ignored,
+        }
+    }
+}
+
+/// Stands in for the field while the game is paused, at the same size.
+pub(crate) struct Paused {
+    size: (usize, usize),
+}
+
+impl Paused {
+    pub(crate) fn new(field: &Field) -> Self {
+        Self { size: (field.width(), field.height()) }
+    }
+}
+
+impl Component for Paused {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let (width, height) = self.size;
+        let text = locale::tr("paused");
+        let text_columns = text_width(text);
+        for (i, dest) in buffer.iter_mut().take(height).enumerate() {
+            if i == height / 2 && width >= text_columns {
+                let left = (width - text_columns) / 2;
+                dest.extend(repeat_n(' ', left));
+                dest.push_str(text);
+                dest.extend(repeat_n(' ', width - left - text_columns));
+            } else {
+                dest.extend(repeat_n(' ', width));
+            }
+        }
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(height)..]
+    }
+
+    fn width(&self) -> usize {
+        self.size.0
+    }
+
+    fn height(&self) -> usize {
+        self.size.1
+    }
+}
+
+
+/// The font [`Title`]s are drawn in, or None if they're drawn as plain text.
+pub(crate) struct TitleFont(Option<FIGfont>);
+
+impl TitleFont {
+    /// Load the font the config names: `standard` for the one built in, `plain` for none, or else the path of a FIGlet
+    /// font file. Titles are drawn as plain text if it can't be loaded.
+    pub(crate) fn load(name: &str) -> Self {
+        Self(match name {
+            "plain" => None,
+            "standard" => FIGfont::standard().ok(),
+            path => FIGfont::from_file(path).ok(),
+        })
+    }
+}
+
+/// The fonts titles have been drawn in, by name (as in the config), each loaded the first time it's used.
+#[derive(Default)]
+pub(crate) struct TitleFonts(HashMap<String, TitleFont>);
+
+impl TitleFonts {
+    pub(crate) fn get(&mut self, name: &str) -> &TitleFont {
+        if !self.0.contains_key(name) {
+            self.0.insert(name.to_string(), TitleFont::load(name));
+        }
+        &self.0[name]
+    }
+}
+
+/// Big text drawn as FIGlet art, like the end screen's "Game Over".
+pub(crate) struct Title {
+    lines: Vec<String>,
+}
+
+impl Title {
+    /// A title drawn in the given font, if it has every character of the text and the art fits in `width` columns.
+    /// Otherwise it's plain text, centered in them.
+    pub(crate) fn new(text: &str, font: &TitleFont, width: usize) -> Self {
+        let art = font.0.as_ref()
+            // The font leaves out characters it doesn't have, which would garble the text
+            .filter(|font| text.chars().all(|c| font.fonts.contains_key(&(c as u32))))
+            .and_then(|font| font.convert(text))
+            .map(|art| {
+                let mut lines = art.to_string().lines().map(|line| line.trim_end().to_string()).collect::<Vec<_>>();
+                while lines.last().is_some_and(String::is_empty) {
+                    lines.pop();
+                }
+                lines
+            })
+            .filter(|lines| lines.iter().all(|line| text_width(line) <= width));
+        let lines = art.unwrap_or_else(|| {
+            let padding = width.saturating_sub(text_width(text)) / 2;
+            vec![format!("{}{}", " ".repeat(padding), text)]
+        });
+        Self { lines }
+    }
+}
+
+impl Component for Title {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        for (dest, src) in buffer.iter_mut().zip(&self.lines) {
             dest.push_str(src);
         }
         let buffer_len = buffer.len();
-        &mut buffer[buffer_len.min(Self::HEIGHT)..]
+        &mut buffer[buffer_len.min(self.lines.len())..]
+    }
+
+    fn width(&self) -> usize {
+        self.lines.iter().map(|line| text_width(line)).max().unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+
+impl Component for Versus {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let width = self.width();
+        for (i, (dest, player)) in buffer.iter_mut().zip(self.players.iter()).enumerate() {
+            let marker = if player.eliminated {
+                console::glyphs().eliminated
+            } else if i == self.turn {
+                console::glyphs().pointer
+            } else {
+                ' '
+            };
+            let score = player.score.to_string();
+            let name = format!("{} {}", marker, player.name);
+            let padding = width - text_width(&name) - score.len();
+            dest.push_str(&name.with(player.color).to_string());
+            dest.extend(repeat_n(' ', padding));
+            dest.push_str(&score);
+        }
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(self.players.len())..]
     }
 
     fn width(&self) -> usize {
-        Self::WIDTH
+        self.players.iter()
+            .map(|player| text_width(&player.name) + player.score.to_string().len() + 3)
+            .max()
+            .unwrap_or(0)
     }
 
     fn height(&self) -> usize {
-        Self::HEIGHT
+        self.players.len()
     }
 }
 
 
-pub(crate) struct Title<'a> {
-    text: &'a str,
-    title: OnceCell<String>,
+/// Plain lines of text, padded to the width of the longest one.
+pub(crate) struct Text {
+    lines: Vec<String>,
 }
 
-impl<'a> Title<'a> {
-    pub(crate) fn new(text: &'a str) -> Self {
-        Self { text, title: OnceCell::new() }
+impl Text {
+    pub(crate) fn new(lines: Vec<String>) -> Self {
+        Self { lines }
     }
 
-    fn lazy_title(&self) -> &str {
-        self.title.get_or_init(|| FIGfont::standard()
-            .unwrap()
-            .convert(self.text)
-            .unwrap()
-            .to_string()
+    pub(crate) fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+}
+
+impl Component for Text {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let width = self.width();
+        for (dest, src) in buffer.iter_mut().zip(self.lines.iter()) {
+            dest.push_str(src);
+            dest.extend(repeat_n(' ', width - text_width(src)));
+        }
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(self.lines.len())..]
+    }
+
+    fn width(&self) -> usize {
+        self.lines.iter()
+            .map(|line| text_width(line))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// A bar showing how far along something is, with a percentage beside it. When there's no telling how far along it is,
+/// a block slides back and forth along the bar instead, with a spinner beside it, to show it's still going.
+pub(crate) struct ProgressBar {
+    /// From 0 to 1, or None if there's no telling.
+    fraction: Option<f64>,
+    /// How many frames the animation is into, for the sliding block and the spinner.
+    frame: usize,
+    /// How long the bar is, not counting what's beside it.
+    width: usize,
+}
+
+impl ProgressBar {
+    /// How long the sliding block is.
+    const BLOCK: usize = 3;
+
+    pub(crate) fn determinate(fraction: f64, width: usize) -> Self {
+        Self { fraction: Some(fraction), frame: 0, width }
+    }
+
+    pub(crate) fn indeterminate(frame: usize, width: usize) -> Self {
+        Self { fraction: None, frame, width }
+    }
+
+    /// The bar as a line of text.
+    pub(crate) fn line(&self) -> String {
+        let glyphs = console::glyphs();
+        let line = match self.fraction {
+            Some(fraction) => format!("{} {:.0}%", bar(fraction, self.width), fraction.clamp(0.0, 1.0) * 100.0),
+            None => {
+                let block = Self::BLOCK.min(self.width);
+                // There and back again, without stopping at either end
+                let room = self.width - block;
+                let start = match self.frame % (2 * room).max(1) {
+                    step if step <= room => step,
+                    step => 2 * room - step,
+                };
+                let spinner = glyphs.spinner.chars().cycle().nth(self.frame).unwrap_or(' ');
+                let track = repeat_n(glyphs.empty, start)
+                    .chain(repeat_n(glyphs.bars[7], block))
+                    .chain(repeat_n(glyphs.empty, room - start))
+                    .collect::<String>();
+                format!("{} {}", track, spinner)
+            },
+        };
+        pad_end(&line, self.width())
+    }
+}
+
+impl Component for ProgressBar {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        if let Some(line) = buffer.first_mut() {
+            line.push_str(&self.line());
+        }
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(1)..]
+    }
+
+    /// Wide enough for the bar and " 100%" beside it, in either mode, so switching between them doesn't move things.
+    fn width(&self) -> usize {
+        self.width + 5
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+}
+
+/// Lines of text too long to show all at once, shown a screenful at a time with a scrollbar beside them.
+pub(crate) struct Scroll {
+    text: Text,
+    /// How many lines are shown at once.
+    rows: usize,
+    /// The first line shown.
+    top: usize,
+}
+
+impl Scroll {
+    pub(crate) fn new(lines: Vec<String>, rows: usize) -> Self {
+        Self { text: Text::new(lines), rows: rows.max(1), top: 0 }
+    }
+
+    /// Swap in the given lines, showing the given number at once from now on, as when the terminal is resized and the
+    /// text is wrapped again. It stays scrolled as far down as it can.
+    pub(crate) fn reflow(&mut self, lines: Vec<String>, rows: usize) {
+        self.text = Text::new(lines);
+        self.rows = rows.max(1);
+        self.top = self.top.min(self.last_top());
+    }
+
+    /// Show the given number of lines at once from now on (but no more than there are), as when the terminal is
+    /// resized.
+    pub(crate) fn resize(&mut self, rows: usize) {
+        self.rows = rows.min(self.text.height()).max(1);
+        self.top = self.top.min(self.last_top());
+    }
+
+    fn last_top(&self) -> usize {
+        self.text.height().saturating_sub(self.rows)
+    }
+
+    /// The scrollbar's thumb, as the first row it's on and one past the last. It's as much of the bar as the text shown
+    /// is of all of it, but never less than a row.
+    fn thumb(&self) -> (usize, usize) {
+        let size = (self.rows * self.rows / self.text.height().max(1)).clamp(1, self.rows);
+        let start = (self.top * (self.rows - size)).checked_div(self.last_top()).unwrap_or(0);
+        (start, start + size)
+    }
+}
+
+impl Component for Scroll {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let width = self.text.width();
+        let (start, end) = self.thumb();
+        let [thumb, track] = console::glyphs().scrollbar;
+        for (row, dest) in buffer.iter_mut().take(self.rows).enumerate() {
+            let line = self.text.lines.get(self.top + row).map_or("", String::as_str);
+            dest.push_str(&pad_end(line, width));
+            // Text that all fits has no need of a scrollbar, but still keeps its column, so it's the same width
+            dest.push(match self.text.height() > self.rows {
+                true if (start..end).contains(&row) => thumb,
+                true => track,
+                false => ' ',
+            });
+        }
+        let buffer_len = buffer.len();
+        &mut buffer[buffer_len.min(self.rows)..]
+    }
+
+    fn width(&self) -> usize {
+        self.text.width() + 1
+    }
+
+    fn height(&self) -> usize {
+        self.rows
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        let Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event else {
+            return Handled::Ignored;
+        };
+        self.top = match code {
+            KeyCode::Up => self.top.saturating_sub(1),
+            KeyCode::Down => self.top + 1,
+            KeyCode::PageUp => self.top.saturating_sub(self.rows),
+            KeyCode::PageDown => self.top + self.rows,
+            KeyCode::Home => 0,
+            KeyCode::End => self.last_top(),
+            KeyCode::Esc | KeyCode::F(1) | KeyCode::Char('q') => return Handled::Closed,
+            _ => return Handled::Consumed,
+        }.min(self.last_top());
+        Handled::Consumed
+    }
+}
+
+/// Break the text into lines no wider than the given width, between words where it can.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && text_width(&line) + 1 + text_width(word) > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        // A word too long for a line of its own is split wherever it has to be
+        for c in word.chars() {
+            if !line.is_empty() && text_width(&line) + text_width(c.encode_utf8(&mut [0; 4])) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// How to play, then every control there is (including the ones that only work in some games), for the help screen.
+/// The text is wrapped to the given width.
+pub(crate) fn help_lines(keymap: &Keymap, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let ids = [
+        "help-header", "", "help-goal", "help-numbers", "help-flags", "help-chords", "help-bookmarks", "help-tabs",
+        "help-commands", "help-presentation", "",
+    ];
+    for id in ids {
+        match id {
+            "" => lines.push(String::new()),
+            id => lines.extend(wrap(locale::tr(id), width)),
+        }
+    }
+    lines.extend(Controls::new(keymap, true, true, true).0.lines);
+    lines
+}
+
+
+/// A summary of the stats history: the current rating, and results per preset.
+pub(crate) fn stats_panel(stats: &Stats) -> Text {
+    let rating = format!("{:.0}", rating::rating(stats.ranked()));
+    let headers = ["stats-played", "stats-won", "stats-win-rate", "stats-best"].map(locale::tr);
+    // Each column is as wide as its header, or the widest value that can go in it
+    let widths = headers.iter().zip([6, 5, 6, 8]).map(|(header, min)| text_width(header).max(min)).collect::<Vec<_>>();
+    let name_width = Preset::ALL.into_iter().map(Some).chain([None])
+        .map(|preset| text_width(preset.map_or(locale::tr("preset-custom"), Preset::label)))
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    // The name on the left, then each of the columns on the right
+    let row = |cells: &[String; 5]| {
+        let columns = cells[1..].iter().zip(&widths).map(|(cell, width)| pad_start(cell, *width));
+        [pad_end(&cells[0], name_width)].into_iter().chain(columns).collect::<Vec<_>>().join(" ")
+    };
+    let mut lines = vec![
+        locale::tr_with("stats-rating", &[("rating", &rating), ("games", &stats.ranked().count())]),
+        String::new(),
+        row(&[String::new(), headers[0].to_string(), headers[1].to_string(), headers[2].to_string(), headers[3].to_string()]),
+    ];
+    if stats.games.iter().any(|game| game.adaptive_level.is_some()) {
+        let level = format!("{:.0}", adaptive::next_level(&stats.games) * 100.0);
+        lines.insert(1, locale::tr_with("stats-adaptive", &[("level", &level)]));
+    }
+
+    let presets = Preset::ALL.into_iter().map(Some).chain([None]);
+    for preset in presets {
+        let games = stats.games.iter()
+            .filter(|game| game.preset == preset)
+            .collect::<Vec<_>>();
+        let won = games.iter().filter(|game| game.won).count();
+        let win_rate = if games.is_empty() { 0.0 } else { won as f64 * 100.0 / games.len() as f64 };
+        let best = games.iter()
+            .filter(|game| game.won)
+            .map(|game| game.time_ms)
+            .min()
+            .map_or("-".to_string(), |time| format!("{:.1}s", time as f64 / 1000.0));
+        lines.push(row(&[
+            preset.map_or(locale::tr("preset-custom"), Preset::label).to_string(),
+            games.len().to_string(),
+            won.to_string(),
+            format!("{:.0}%", win_rate),
+            best,
+        ]));
+    }
+    if stats.damaged > 0 {
+        lines.insert(1, locale::tr_with("stats-damaged", &[("count", &stats.damaged)]));
+    }
+    if let Some(best) = stats.ladders.iter().map(LadderRecord::total_ms).min() {
+        let best = format_time(Duration::from_millis(best));
+        lines.push(String::new());
+        lines.push(locale::tr_with("stats-ladder", &[("runs", &stats.ladders.len()), ("best", &best)]));
+    }
+    lines.extend(stats_charts(stats, name_width));
+
+    Text::new(lines)
+}
+
+/// Charts of the stats history, to go below the table: the win rate on each kind of board that's been played, the
+/// times of recent wins on each preset, and how the winning times on the most won preset are spread out.
+fn stats_charts(stats: &Stats, name_width: usize) -> Vec<String> {
+    const WIDTH: usize = 30;
+    let presets = Preset::ALL.into_iter().map(Some).chain([None]);
+    let games = |preset: Option<Preset>| stats.games.iter().filter(move |game| game.preset == preset);
+    // Times only compare within a preset, since custom boards can be any size
+    let times = |preset: Preset| games(Some(preset))
+        .filter(|game| game.won)
+        .map(|game| game.time_ms as f64 / 1000.0)
+        .collect::<Vec<_>>();
+    let label = |preset: Option<Preset>| pad_end(preset.map_or(locale::tr("preset-custom"), Preset::label), name_width);
+
+    let mut lines = Vec::new();
+    let played = presets.filter(|&preset| games(preset).next().is_some()).collect::<Vec<_>>();
+    if !played.is_empty() {
+        lines.extend([String::new(), locale::tr("stats-win-rates").to_string()]);
+    }
+    for &preset in &played {
+        let win_rate = games(preset).filter(|game| game.won).count() as f64 / games(preset).count() as f64;
+        lines.push(format!("{} {} {:.0}%", label(preset), bar(win_rate, WIDTH), win_rate * 100.0));
+    }
+
+    let won = Preset::ALL.into_iter().filter(|&preset| !times(preset).is_empty()).collect::<Vec<_>>();
+    if !won.is_empty() {
+        lines.extend([String::new(), locale::tr("stats-recent-times").to_string()]);
+    }
+    for &preset in &won {
+        let times = times(preset);
+        lines.push(format!("{} {}", label(Some(preset)), sparkline(&times[times.len().saturating_sub(WIDTH)..])));
+    }
+
+    if let Some(preset) = won.into_iter().max_by_key(|&preset| (times(preset).len(), std::cmp::Reverse(preset as u8))) {
+        let times = times(preset);
+        lines.extend([String::new(), locale::tr_with("stats-times", &[("preset", &preset.label())])]);
+        lines.extend(histogram(&times, WIDTH, 4));
+        let fastest = format!("{:.1}s", times.iter().copied().fold(f64::INFINITY, f64::min));
+        let slowest = format!("{:.1}s", times.iter().copied().fold(0.0, f64::max));
+        lines.push(format!("{}{}", pad_end(&fastest, WIDTH - text_width(&slowest)), slowest));
+    }
+
+    // A hand-edited (or damaged) record might have been lost somewhere off its own board, which can't be mapped
+    let lost = stats.games.iter()
+        .filter(|game| game.death.is_some_and(|(row, col)| row < game.size.0 && col < game.size.1))
+        .collect::<Vec<_>>();
+    if !lost.is_empty() {
+        lines.extend([String::new(), locale::tr("stats-deaths").to_string()]);
+        lines.extend(death_heatmap(&lost, (WIDTH / 3, WIDTH)));
+        let share = |spot: Spot| {
+            let count = lost.iter().filter(|game| game.death.map(|pos| Spot::of(pos, game.size)) == Some(spot)).count();
+            format!("{:.0}", count as f64 * 100.0 / lost.len() as f64)
+        };
+        lines.push(locale::tr_with("stats-death-spots", &[
+            ("corners", &share(Spot::Corner)),
+            ("edges", &share(Spot::Edge)),
+            ("inside", &share(Spot::Inside)),
+        ]));
+    }
+    lines
+}
+
+/// Where on its board a cell is.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Spot {
+    Corner,
+    Edge,
+    Inside,
+}
+
+impl Spot {
+    fn of((row, col): (usize, usize), (rows, cols): (usize, usize)) -> Self {
+        match (row == 0 || row + 1 == rows, col == 0 || col + 1 == cols) {
+            (true, true) => Spot::Corner,
+            (true, false) | (false, true) => Spot::Edge,
+            (false, false) => Spot::Inside,
+        }
+    }
+}
+
+/// How often each part of the board was where the games were lost, with every board stretched to the same `size`, as
+/// (rows, columns). Parts where none were lost are shown as empty, and the rest are shaded darker the more were.
+fn death_heatmap(games: &[&GameRecord], (rows, cols): (usize, usize)) -> Vec<String> {
+    let mut counts = vec![vec![0; cols]; rows];
+    for game in games {
+        let Some((row, col)) = game.death else { continue };
+        let (board_rows, board_cols) = game.size;
+        // The part of the map the cell stretches over, at least one line and column of it
+        let span = |at: usize, of: usize, to: usize| at * to / of..((at + 1) * to).div_ceil(of).max(at * to / of + 1);
+        for line in &mut counts[span(row, board_rows, rows)] {
+            for count in &mut line[span(col, board_cols, cols)] {
+                *count += 1;
+            }
+        }
+    }
+    let most = counts.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let glyphs = console::glyphs();
+    counts.iter()
+        .map(|line| line.iter()
+            .map(|&count| match count {
+                0 => glyphs.safe,
+                count => glyphs.shades[(count * glyphs.shades.len()).div_ceil(most) - 1],
+            })
+            .collect()
         )
+        .collect()
+}
+
+/// A bar `width` columns long, filled in for the given fraction of it.
+fn bar(fraction: f64, width: usize) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * width as f64).round() as usize;
+    let glyphs = console::glyphs();
+    repeat_n(glyphs.bars[7], filled).chain(repeat_n(glyphs.empty, width - filled)).collect()
+}
+
+/// A bar for each value, as high as it is compared to the others: the lowest gets the shortest bar, and the highest
+/// the tallest.
+fn sparkline(values: &[f64]) -> String {
+    let bars = console::glyphs().bars;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values.iter()
+        .map(|value| if max > min { ((value - min) / (max - min) * 7.0).round() as usize } else { 3 })
+        .map(|level| bars[level])
+        .collect()
+}
+
+/// How many of the values fall in each of `buckets` equal ranges, from the lowest value to the highest, as columns of
+/// bars `height` lines high, top line first.
+fn histogram(values: &[f64], buckets: usize, height: usize) -> Vec<String> {
+    let bars = console::glyphs().bars;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mut counts = vec![0; buckets];
+    for value in values {
+        let bucket = if max > min { ((value - min) / (max - min) * buckets as f64) as usize } else { 0 };
+        counts[bucket.min(buckets - 1)] += 1;
     }
+    // Each column's height, in eighths of a line
+    let most = counts.iter().copied().max().unwrap_or(0).max(1);
+    let eighths = counts.iter().map(|&count| count * height * 8 / most).collect::<Vec<_>>();
+    (0..height).rev()
+        .map(|line| eighths.iter()
+            .map(|&eighths| match eighths.saturating_sub(line * 8).min(8) {
+                0 => ' ',
+                filled => bars[filled - 1],
+            })
+            .collect()
+        )
+        .collect()
+}
+
+/// Every achievement, marking the ones that have been unlocked.
+pub(crate) fn achievements_panel(stats: &Stats) -> Text {
+    let unlocked = achievements::unlocked(&stats.games);
+    let name_width = Achievement::ALL.into_iter().map(|achievement| text_width(achievement.name())).max().unwrap_or(0);
+    let lines = Achievement::ALL.into_iter()
+        .map(|achievement| {
+            let [earned, unearned] = console::glyphs().earned;
+            let marker = if unlocked.iter().any(|(earned, _)| *earned == achievement) { earned } else { unearned };
+            format!("{} {} {}", marker, pad_end(achievement.name(), name_width), achievement.description())
+        })
+        .collect();
+    Text::new(lines)
 }
 
-impl<'a> Component for Title<'a> {
+
+impl Component for Settings {
     fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let width = self.width();
+        let lines = [locale::tr("settings-hint").to_string()].into_iter()
+            .chain(ControlPreset::ALL.iter().enumerate().map(|(i, preset)| format!(
+                "{} {} {}",
+                if i == self.selected { console::glyphs().pointer } else { ' ' },
+                if *preset == self.current { console::glyphs().chosen[0] } else { console::glyphs().chosen[1] },
+                preset.name(),
+            )));
         let mut count = 0;
-        for (dest, src) in buffer.iter_mut().zip(self.text.lines()) {
+        for (dest, src) in buffer.iter_mut().zip(lines) {
             count += 1;
-            dest.push_str(src);
+            dest.push_str(&pad_end(&src, width));
         }
         let buffer_len = buffer.len();
         &mut buffer[buffer_len.min(count)..]
     }
 
     fn width(&self) -> usize {
-        self.lazy_title()
-            .lines()
-            .map(|line| line.len())
-            .max()
-            .unwrap_or(0)
+        // Room for the hint, or the longest preset after its markers
+        let presets = ControlPreset::ALL.iter().map(|preset| text_width(preset.name()) + 4);
+        presets.chain([text_width(locale::tr("settings-hint"))]).max().unwrap_or(0)
     }
 
     fn height(&self) -> usize {
-        self.lazy_title()
-            .bytes()
-            .filter(|c| *c == b'\n')
-            .count()
+        ControlPreset::ALL.len() + 1
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        match event {
+            Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) => self.handle_key(*code),
+            _ => Handled::Ignored,
+        }
+    }
+}
+
+impl EndMenu {
+    fn lines(&self) -> Vec<String> {
+        let actions = self.actions.iter().enumerate().map(|(i, action)| format!(
+            "{} {}",
+            if i == self.selected { console::glyphs().pointer } else { ' ' },
+            action.label(),
+        ));
+        let gap = (!self.summary.is_empty()).then(String::new);
+        self.summary.iter().cloned().chain(gap).chain(actions).collect()
+    }
+}
+
+impl Component for EndMenu {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        let width = self.width();
+        let lines = self.lines();
+        let count = lines.len().min(buffer.len());
+        for (dest, src) in buffer.iter_mut().zip(lines) {
+            dest.push_str(&pad_end(&src, width));
+        }
+        &mut buffer[count..]
+    }
+
+    fn width(&self) -> usize {
+        self.lines().iter().map(|line| text_width(line)).max().unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines().len()
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        match event {
+            Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) => self.handle_key(*code),
+            _ => Handled::Ignored,
+        }
+    }
+}
+
+/// A yes or no question about an action, like a move the solver says is a mistake, asked before it's taken. Any answer
+/// but yes is no.
+pub(crate) struct Confirm<T> {
+    pub(crate) action: T,
+    /// Whether the answer was yes, once there is one.
+    pub(crate) accepted: bool,
+    question: Text,
+}
+
+impl<T> Confirm<T> {
+    pub(crate) fn new(action: T, question: String) -> Self {
+        Self { action, accepted: false, question: Text::new(vec![question]) }
+    }
+}
+
+impl<T> Component for Confirm<T> {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        self.question.render_at(buffer)
+    }
+
+    fn width(&self) -> usize {
+        self.question.width()
+    }
+
+    fn height(&self) -> usize {
+        self.question.height()
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        match event {
+            Event::Key(KeyEvent { code, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) => {
+                self.accepted = *code == KeyCode::Char('y');
+                Handled::Closed
+            },
+            _ => Handled::Ignored,
+        }
+    }
+}
+
+
+/// A line typed in after a prefix, like the game's `:` commands. Enter submits it, and escape (or backspacing past the
+/// start of it) takes it back.
+pub(crate) struct Prompt {
+    prefix: char,
+    pub(crate) input: String,
+    /// Whether the line was submitted, once the prompt is closed.
+    pub(crate) submitted: bool,
+}
+
+impl Prompt {
+    /// How wide the box is drawn until the line outgrows it, so it doesn't grow with every character typed.
+    const MIN_WIDTH: usize = 30;
+
+    pub(crate) fn new(prefix: char) -> Self {
+        Self { prefix, input: String::new(), submitted: false }
+    }
+
+    fn line(&self) -> String {
+        // The terminal's own cursor is parked below the boards, so the prompt draws one of its own
+        format!("{}{}_", self.prefix, self.input)
+    }
+}
+
+impl Component for Prompt {
+    fn render_at<'buf>(&self, buffer: &'buf mut[String]) -> &'buf mut[String] {
+        Text::new(vec![pad_end(&self.line(), self.width())]).render_at(buffer)
+    }
+
+    fn width(&self) -> usize {
+        text_width(&self.line()).max(Self::MIN_WIDTH)
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Handled {
+        let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press | KeyEventKind::Repeat, .. }) = event
+        else {
+            return Handled::Ignored;
+        };
+        match code {
+            KeyCode::Enter => {
+                self.submitted = true;
+                return Handled::Closed;
+            },
+            KeyCode::Esc => return Handled::Closed,
+            KeyCode::Backspace if self.input.pop().is_none() => return Handled::Closed,
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => self.input.push(*c),
+            _ => {},
+        }
+        Handled::Consumed
     }
 }
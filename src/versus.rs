@@ -0,0 +1,150 @@
+use crossterm::style::Color;
+
+use crate::ai::Difficulty;
+use crate::locale;
+
+/// The scoring rules for versus mode.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Rules {
+    /// Score a point for each safe cell revealed, and pay a penalty for revealing a mine.
+    Reveal(MinePenalty),
+    /// Minesweeper Flags: revealing a mine claims it for a point and earns another turn, revealing a safe cell passes
+    /// the turn. The first player to claim a majority of the mines wins.
+    Flags,
+}
+
+/// What happens to a player who reveals a mine under [`Rules::Reveal`].
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum MinePenalty {
+    /// Lose this many points; the mine stays exploded and play passes to the next player.
+    Points(u32),
+    /// The player is knocked out of the game.
+    Eliminated,
+}
+
+#[derive(Debug)]
+pub(crate) struct Player {
+    pub(crate) name: String,
+    pub(crate) color: Color,
+    pub(crate) score: i64,
+    pub(crate) eliminated: bool,
+    /// Set if this player is played by the computer.
+    pub(crate) computer: Option<Difficulty>,
+}
+
+/// Turn and score tracking for hotseat versus, where players alternate turns on the same board.
+#[derive(Debug)]
+pub(crate) struct Versus {
+    pub(crate) players: Vec<Player>,
+    pub(crate) turn: usize,
+    rules: Rules,
+    mines: usize,
+    /// Mines claimed under [`Rules::Flags`], as (position, player index).
+    pub(crate) claims: Vec<((u16, u16), usize)>,
+}
+
+impl Versus {
+    const COLORS: [Color; 4] = [Color::Cyan, Color::Magenta, Color::Yellow, Color::Green];
+
+    /// `mines` is the number of mines on the board being played. Returns None if there are fewer than 2 or more than 4
+    /// players.
+    pub(crate) fn new(players: usize, rules: Rules, mines: usize) -> Option<Self> {
+        if !(2..=Self::COLORS.len()).contains(&players) {
+            return None;
+        }
+
+        Some(Self {
+            players: Self::COLORS.iter()
+                .take(players)
+                .enumerate()
+                .map(|(i, color)| Player {
+                    name: locale::tr_with("versus-player", &[("number", &(i + 1))]),
+                    color: *color,
+                    score: 0,
+                    eliminated: false,
+                    computer: None,
+                })
+                .collect(),
+            turn: 0,
+            rules,
+            mines,
+            claims: Vec::new(),
+        })
+    }
+
+    /// Hand the given player (by index) over to a computer opponent of the given difficulty.
+    pub(crate) fn with_computer(mut self, player: usize, difficulty: Difficulty) -> Self {
+        if let Some(player) = self.players.get_mut(player) {
+            player.name = locale::tr("versus-computer").to_string();
+            player.computer = Some(difficulty);
+        }
+        self
+    }
+
+    pub(crate) fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub(crate) fn current(&self) -> &Player {
+        &self.players[self.turn]
+    }
+
+    /// Record the result of the current player's reveal at `pos` and pass the turn if the rules say so. `revealed` is
+    /// the number of safe cells the reveal uncovered.
+    pub(crate) fn record_reveal(&mut self, pos: (u16, u16), revealed: usize, exploded: bool) {
+        let player = &mut self.players[self.turn];
+        match self.rules {
+            Rules::Reveal(penalty) => {
+                player.score += revealed as i64;
+                if exploded {
+                    match penalty {
+                        MinePenalty::Points(points) => player.score -= points as i64,
+                        MinePenalty::Eliminated => player.eliminated = true,
+                    }
+                }
+                self.next_turn();
+            },
+            Rules::Flags if exploded => {
+                player.score += 1;
+                self.claims.push((pos, self.turn));
+            },
+            Rules::Flags => self.next_turn(),
+        }
+    }
+
+    fn next_turn(&mut self) {
+        if self.remaining() == 0 {
+            return;
+        }
+        loop {
+            self.turn = (self.turn + 1) % self.players.len();
+            if !self.players[self.turn].eliminated {
+                break;
+            }
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.players.iter().filter(|player| !player.eliminated).count()
+    }
+
+    /// Returns true once the result can no longer change: at most one player is left standing, or (under
+    /// [`Rules::Flags`]) someone has claimed a majority of the mines.
+    pub(crate) fn decided(&self) -> bool {
+        match self.rules {
+            Rules::Reveal(_) => self.remaining() <= 1,
+            Rules::Flags => self.players.iter().any(|player| player.score as usize > self.mines / 2),
+        }
+    }
+
+    /// The player with the highest score among those not eliminated, or None on a tie.
+    pub(crate) fn winner(&self) -> Option<&Player> {
+        let best = self.players.iter()
+            .filter(|player| !player.eliminated)
+            .map(|player| player.score)
+            .max()?;
+        let mut leaders = self.players.iter().filter(|player| !player.eliminated && player.score == best);
+        let winner = leaders.next();
+        if leaders.next().is_some() { None } else { winner }
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::mpsc::{self, Receiver};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::paths;
+
+/// Watches the config directory, for reloading the config (and any profile's overrides) as soon as it's edited.
+pub(crate) struct ConfigWatcher {
+    /// Stops watching when dropped.
+    _watcher: RecommendedWatcher,
+    changes: Receiver<notify::Result<Event>>,
+}
+
+impl ConfigWatcher {
+    /// Start watching. Returns None if there's no config directory yet, or it can't be watched.
+    pub(crate) fn new() -> Option<Self> {
+        let (sender, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).ok()?;
+        watcher.watch(&paths::config_dir()?, RecursiveMode::Recursive).ok()?;
+        Some(Self { _watcher: watcher, changes })
+    }
+
+    /// Returns true if a config file was written, created or removed since the last time this was called.
+    pub(crate) fn changed(&self) -> bool {
+        let mut changed = false;
+        // Editors often save in several steps, each with its own events, so they're all taken at once
+        for event in self.changes.try_iter().flatten() {
+            // Reading the files (as reloading does) is an event too, on some platforms
+            let written = event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove();
+            changed |= written && event.paths.iter().any(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        }
+        changed
+    }
+}
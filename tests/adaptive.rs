@@ -0,0 +1,159 @@
+// The data directory only comes from XDG_DATA_HOME on these platforms
+#![cfg(all(unix, not(target_os = "macos")))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use termsweeper::adaptive::{self, Suggestion};
+use termsweeper::config::Config;
+use termsweeper::preset::Preset;
+use termsweeper::stats::Stats;
+
+/// A data and config directory of its own for each test, with the given stats file.
+fn dir(name: &str, history: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-adaptive-{}", std::process::id())).join(name);
+    std::fs::create_dir_all(dir.join("termsweeper")).unwrap();
+    std::fs::write(dir.join("termsweeper/stats.jsonl"), history).unwrap();
+    dir
+}
+
+/// Run termsweeper with the given arguments and data directory. Returns what it wrote.
+fn run(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .args(args)
+        .args(["--lang", "en"])
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// The board the next adaptive game would be played on, as termsweeper's script mode reports it.
+fn next_board(dir: &Path) -> String {
+    run(dir, &["--adaptive", "--script"]).lines().next().unwrap().to_string()
+}
+
+/// The level of the next adaptive game, in percent, as `termsweeper stats` shows it.
+fn next_level(dir: &Path) -> u32 {
+    let output = run(dir, &["stats"]);
+    let line = output.lines().find_map(|line| line.split("Adaptive level: ").nth(1)).unwrap();
+    line.split('%').next().unwrap().parse().unwrap()
+}
+
+/// An adaptive game played at the given level, in percent.
+fn record(level: u32, won: bool) -> String {
+    format!(
+        concat!(
+            r#"{{"timestamp":1700000000,"preset":null,"size":[9,9],"mines":8,"won":{},"time_ms":10000,"bbbv":10,"#,
+            r#""clicks":12,"adaptive_level":{}}}"#, "\n",
+        ),
+        won, level as f64 / 100.0,
+    )
+}
+
+/// Play the given results one after another, each at the level the games before it lead to. Returns the level after
+/// each one.
+fn play(dir: &Path, results: &[bool]) -> Vec<u32> {
+    let path = dir.join("termsweeper/stats.jsonl");
+    let mut level = match std::fs::read_to_string(&path).unwrap().is_empty() {
+        true => 0,
+        false => next_level(dir),
+    };
+    results.iter().map(|&won| {
+        let mut history = std::fs::read_to_string(&path).unwrap();
+        history.push_str(&record(level, won));
+        std::fs::write(&path, history).unwrap();
+        level = next_level(dir);
+        level
+    }).collect()
+}
+
+#[test]
+fn starts_on_a_small_sparse_board() {
+    assert_eq!(next_board(&dir("start", "")), "size 9 9 8");
+}
+
+#[test]
+fn promotes_after_wins_and_demotes_after_losses() {
+    let dir = dir("sequence", "");
+    let mut results = vec![true; 5];
+    results.extend([false; 10]);
+    // Losses only bring the level down once they outnumber the wins among the last ten games
+    assert_eq!(play(&dir, &results), [20, 40, 60, 80, 100, 100, 100, 100, 100, 100, 96, 88, 76, 60, 40]);
+    assert_eq!(play(&dir, &[false; 3]), [20, 0, 0]);
+    // Nor do wins bring it up again until they outnumber the losses
+    assert_eq!(play(&dir, &[true; 3]), [0, 0, 0]);
+}
+
+#[test]
+fn tops_out_at_expert() {
+    let history = (0..10).map(|_| record(100, true)).collect::<String>();
+    let dir = dir("top", &history);
+    assert_eq!(next_level(&dir), 100);
+    assert_eq!(next_board(&dir), "size 16 30 99");
+}
+
+#[test]
+fn bottoms_out_at_the_start() {
+    let history = (0..10).map(|_| record(0, false)).collect::<String>();
+    let dir = dir("bottom", &history);
+    assert_eq!(next_level(&dir), 0);
+    assert_eq!(next_board(&dir), "size 9 9 8");
+}
+
+/// Stats with the given numbers of games won and lost on each preset.
+fn stats(results: &[(Preset, usize, usize)]) -> Stats {
+    let mut history = String::new();
+    for &(preset, won, lost) in results {
+        let (size, mines) = (preset.size(), preset.mines());
+        for won in (0..won + lost).map(|i| i < won) {
+            history.push_str(&format!(
+                concat!(
+                    r#"{{"timestamp":1700000000,"preset":"{}","size":[{},{}],"mines":{},"won":{},"time_ms":10000,"#,
+                    r#""bbbv":10,"clicks":12}}"#, "\n",
+                ),
+                preset.name().to_lowercase(), size.0, size.1, mines, won,
+            ));
+        }
+    }
+    Stats::parse(history.as_bytes()).unwrap()
+}
+
+#[test]
+fn suggests_the_next_preset_from_the_hardest_played() {
+    let suggestion = |played, win_rate, next| Some(Suggestion { played, win_rate, next });
+    // Not until there have been enough games to go by
+    assert_eq!(adaptive::suggest(&stats(&[(Preset::Beginner, 9, 0)])), None);
+    assert_eq!(
+        adaptive::suggest(&stats(&[(Preset::Beginner, 8, 2)])),
+        suggestion(Preset::Beginner, 0.8, Preset::Intermediate),
+    );
+    // Going by the hardest preset with enough games, however the easier ones went
+    let results = [(Preset::Beginner, 20, 0), (Preset::Intermediate, 1, 9), (Preset::Expert, 9, 0)];
+    assert_eq!(adaptive::suggest(&stats(&results)), suggestion(Preset::Intermediate, 0.1, Preset::Beginner));
+    // Nothing in between, nor past either end
+    assert_eq!(adaptive::suggest(&stats(&[(Preset::Intermediate, 5, 5)])), None);
+    assert_eq!(adaptive::suggest(&stats(&[(Preset::Expert, 10, 0)])), None);
+    assert_eq!(adaptive::suggest(&stats(&[(Preset::Beginner, 0, 10)])), None);
+}
+
+#[test]
+fn dismissed_suggestions_stay_dismissed() {
+    let dir = dir("dismissed", "");
+    // The only test here that reads the data directory itself, rather than through termsweeper
+    std::env::set_var("XDG_DATA_HOME", &dir);
+    let config = Config::default();
+    let beginner = stats(&[(Preset::Beginner, 10, 0)]);
+    let suggestion = adaptive::pending(&beginner, &config).unwrap();
+    assert_eq!(suggestion.next, Preset::Intermediate);
+    assert_eq!(adaptive::pending(&beginner, &Config::parse("suggestions = false\n").unwrap()), None);
+
+    adaptive::dismiss(suggestion).unwrap();
+    assert!(dir.join("termsweeper/suggestions.toml").exists());
+    assert_eq!(adaptive::pending(&beginner, &config), None);
+    // Another suggestion is still made, once how the player does changes
+    let intermediate = stats(&[(Preset::Beginner, 10, 0), (Preset::Intermediate, 10, 0)]);
+    assert_eq!(adaptive::pending(&intermediate, &config).map(|suggestion| suggestion.next), Some(Preset::Expert));
+}
@@ -0,0 +1,104 @@
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::board::{BitBoard, Board};
+use termsweeper::board_file;
+use termsweeper::game::Field;
+use termsweeper::generator::{BoardGenerator, Uniform};
+use termsweeper::solver;
+use termsweeper::topology::Topology;
+
+/// A seeded field on any topology, with a random size and density.
+fn field() -> impl Strategy<Value = Field> {
+    let layers = prop_oneof![Just(1), Just(1), 2usize..=4];
+    (layers, any::<bool>(), 1usize..=16, 1usize..=16, 0.0f64..=0.3, any::<u64>())
+        .prop_map(|(layers, triangles, rows, cols, density, seed)| {
+            let topology = match (layers, triangles) {
+                (1, false) => Topology::Square,
+                (1, true) => Topology::Triangular,
+                (layers, _) => Topology::Layered { layers },
+            };
+            let size = (rows, cols * layers);
+            let mines = ((size.0 * size.1) as f64 * density) as usize;
+            let mines = Uniform.place_mines(size, mines, &mut StdRng::seed_from_u64(seed)).unwrap();
+            Field::with_topology(topology, size, &mines).unwrap()
+        })
+}
+
+/// Check that every cell of the two boards looks the same.
+fn assert_same(field: &Field, board: &BitBoard) {
+    assert_eq!(Board::size(field), board.size());
+    let (rows, cols) = board.size();
+    for pos in (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))) {
+        assert_eq!(Board::is_mine(field, pos), board.is_mine(pos), "mine at {:?}", pos);
+        assert_eq!(Board::neighbor_count(field, pos), board.neighbor_count(pos), "count at {:?}", pos);
+        assert_eq!(Board::is_hidden(field, pos), board.is_hidden(pos), "hidden at {:?}", pos);
+        assert_eq!(Board::is_flagged(field, pos), board.is_flagged(pos), "flag at {:?}", pos);
+        assert_eq!(Board::is_void(field, pos), board.is_void(pos), "void at {:?}", pos);
+        assert_eq!(Board::is_exploded(field, pos), board.is_exploded(pos), "explosion at {:?}", pos);
+        assert_eq!(Board::shown_number(field, pos), board.shown_number(pos), "number at {:?}", pos);
+    }
+    assert_eq!(Board::is_cleared(field), board.is_cleared());
+}
+
+proptest! {
+    /// A bitboard built from the same mines fits together the same way as the field, on any topology. How they play is
+    /// checked against the reference in the differential test.
+    #[test]
+    fn bitboards_fit_together_like_fields(mut field in field()) {
+        let mines = (0..field.size().0)
+            .flat_map(|row| (0..field.size().1).map(move |col| (row, col)))
+            .filter(|&pos| field.cell(pos).unwrap().is_mine())
+            .collect::<Vec<_>>();
+        let mut board = BitBoard::with_topology(field.topology(), field.size(), &mines).unwrap();
+        assert_same(&field, &board);
+        let (rows, cols) = field.size();
+        for pos in (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))) {
+            let mut expected = Board::neighbors(&field, pos).collect::<Vec<_>>();
+            let mut found = board.neighbors(pos).collect::<Vec<_>>();
+            expected.sort();
+            found.sort();
+            prop_assert_eq!(expected, found, "neighbors of {:?}", pos);
+        }
+
+        // A flood fill from the first empty cell goes the same way through the neighbors
+        let empty = (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .find(|&pos| !Board::is_mine(&field, pos) && Board::neighbor_count(&field, pos) == 0);
+        if let Some(pos) = empty {
+            field.clear_cell(pos);
+            board.clear_cell(pos);
+            assert_same(&field, &board);
+        }
+        let (expected, found) = (solver::analyze(&field), solver::analyze(&board));
+        prop_assert_eq!(expected.safe(), found.safe());
+        prop_assert_eq!(expected.mines(), found.mines());
+        assert_same(&field, &BitBoard::from(&field));
+    }
+}
+
+#[test]
+fn bitboards_keep_voids() {
+    let mut field = board_file::parse("..*..\n._.*.\n__...\n").unwrap();
+    field.clear_cell((0, 0));
+    field.toggle_flag((0, 2));
+    let mut board = BitBoard::from(&field);
+    assert_same(&field, &board);
+    // Voids can't be revealed or flagged, and don't need clearing
+    assert!(board.clear_cell((1, 1)).is_none());
+    assert_eq!(board.toggle_flag((2, 0)), Some(false));
+    for pos in [(0, 3), (0, 4), (1, 2), (1, 4), (2, 2), (2, 3)] {
+        assert!(board.clear_cell(pos).is_some_and(|reveal| !reveal.exploded));
+    }
+    assert!(!board.is_cleared());
+    board.clear_cell((2, 4));
+    assert!(board.is_cleared());
+}
+
+#[test]
+fn bitboards_refuse_bad_mines() {
+    assert!(BitBoard::from_mines((0, 5), &[]).is_none());
+    assert!(BitBoard::from_mines((3, 3), &[(3, 0)]).is_none());
+    assert!(BitBoard::from_mines((3, 3), &[(1, 1), (1, 1)]).is_none());
+    assert!(BitBoard::with_topology(Topology::Layered { layers: 2 }, (3, 3), &[]).is_none());
+}
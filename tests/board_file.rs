@@ -0,0 +1,38 @@
+use termsweeper::board_file;
+
+const BOARD: &str = "\
+..*..
+._.*.
+__...
+";
+
+#[test]
+fn round_trips() {
+    let field = board_file::parse(BOARD).unwrap();
+    assert_eq!(field.size(), (3, 5));
+    assert_eq!(field.mine_count(), 2);
+    assert_eq!(board_file::write(&field), BOARD);
+}
+
+#[test]
+fn voids_are_holes() {
+    let mut field = board_file::parse(BOARD).unwrap();
+    let void = *field.cell((1, 1)).unwrap();
+    assert!(void.is_void() && !void.is_hidden());
+    assert!(field.clear_cell((1, 1)).is_none());
+    assert_eq!(field.toggle_flag((1, 1)), Some(false));
+
+    // Clearing every safe cell wins, without touching the voids
+    for pos in [(0, 0), (0, 1), (0, 3), (0, 4), (1, 0), (1, 2), (1, 4), (2, 2), (2, 3), (2, 4)] {
+        assert!(!field.is_cleared());
+        field.clear_cell(pos);
+    }
+    assert!(field.is_cleared());
+}
+
+#[test]
+fn rejects_bad_boards() {
+    for board in ["", "..\n...\n", "..x\n", "\n\n"] {
+        assert!(board_file::parse(board).is_err(), "{:?}", board);
+    }
+}
@@ -0,0 +1,86 @@
+// The data directory only comes from XDG_DATA_HOME on these platforms
+#![cfg(all(unix, not(target_os = "macos")))]
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use termsweeper::config::Config;
+use termsweeper::game::{Field, Game};
+
+const TERMINAL_SIZE: (u16, u16) = (120, 50);
+
+/// Collects everything the game writes, so it can be played back onto a virtual screen.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn press(game: &mut Game, code: KeyCode, modifiers: KeyModifiers) {
+    game.handle_event(Event::Key(KeyEvent::new(code, modifiers))).unwrap();
+}
+
+/// Render a frame and play everything written so far onto a virtual terminal. Returns the screen contents.
+fn screen(game: &mut Game, output: &Output) -> String {
+    game.render().unwrap();
+    let mut parser = vt100::Parser::new(TERMINAL_SIZE.1, TERMINAL_SIZE.0, 0);
+    parser.process(&output.0.borrow());
+    parser.screen().contents()
+}
+
+/// A headless game on a board with one mine, in the top left corner where the cursor starts.
+fn one_mine() -> (Game, Output) {
+    let field = Field::from_mines((3, 3), &[(0, 0)]).unwrap();
+    let output = Output::default();
+    let game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    (game, output)
+}
+
+#[test]
+fn finished_games_count_towards_bookmarks() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-bookmark-{}", std::process::id()));
+    let path = dir.join("termsweeper/bookmarks.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    // The only test here, so nothing else sees the data directory change
+    std::env::set_var("XDG_DATA_HOME", &dir);
+    let bookmark = || toml::from_str::<toml::Table>(&std::fs::read_to_string(&path).unwrap()).unwrap()
+        ["bookmark"].as_array().unwrap()[0].clone();
+
+    let (mut game, output) = one_mine();
+    press(&mut game, KeyCode::Char('b'), KeyModifiers::CONTROL);
+    assert!(screen(&mut game, &output).contains("Bookmarked the board"));
+    assert_eq!(bookmark()["attempts"].as_integer(), Some(0));
+
+    // Losing straight away clears none of it
+    press(&mut game, KeyCode::Char(' '), KeyModifiers::NONE);
+    assert_eq!(bookmark()["attempts"].as_integer(), Some(1));
+    assert_eq!(bookmark()["best_progress"].as_float(), Some(0.0));
+    assert!(bookmark().get("best_ms").is_none());
+
+    // Retrying, then clearing it from the opposite corner
+    press(&mut game, KeyCode::Enter, KeyModifiers::NONE);
+    for code in [KeyCode::Down, KeyCode::Down, KeyCode::Right, KeyCode::Right, KeyCode::Char(' ')] {
+        press(&mut game, code, KeyModifiers::NONE);
+    }
+    assert_eq!(bookmark()["attempts"].as_integer(), Some(2));
+    assert_eq!(bookmark()["best_progress"].as_float(), Some(1.0));
+    assert!(bookmark()["best_ms"].is_integer());
+
+    // A bookmarks file that can't be read is said so, rather than ending the game
+    std::fs::write(&path, "[[bookmark]\n").unwrap();
+    let (mut game, output) = one_mine();
+    press(&mut game, KeyCode::Char(' '), KeyModifiers::NONE);
+    let shown = screen(&mut game, &output);
+    assert!(shown.contains("Couldn't update the board's bookmark"), "{}", shown);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "[[bookmark]\n");
+}
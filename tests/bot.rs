@@ -0,0 +1,102 @@
+// The engines here are shell scripts
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use termsweeper::board_file;
+use termsweeper::bot::Bot;
+use termsweeper::engine::Command;
+use termsweeper::game::Field;
+
+/// Where an engine's files go, with one directory per test.
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-bot-{}", std::process::id())).join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Start an engine that writes everything it's sent to `input`, and answers each `go` with the next of the given
+/// replies (as printf formats, so they can be several lines). Once it's out of replies, it quits at the next `go`.
+fn engine(name: &str, replies: &[&str], field: &Field) -> Bot {
+    let dir = dir(name);
+    let script = dir.join("engine.sh");
+    let replies = replies.iter().map(|reply| format!("'{}'", reply)).collect::<Vec<_>>().join(" ");
+    let read = format!(
+        "while read -r line; do echo \"$line\" >> {}; [ \"$line\" = go ] && break; done",
+        dir.join("input").display(),
+    );
+    let body = format!("for reply in {}; do\n  {}\n  printf \"$reply\\n\"\ndone\n{}\n", replies, read, read);
+    std::fs::write(&script, format!("#!/bin/sh\n{}", body)).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    Bot::spawn(script.to_str().unwrap(), field).unwrap()
+}
+
+/// Ask for a move, and wait for it.
+fn ask(bot: &mut Bot, field: &Field) -> Result<Command, String> {
+    bot.request(field).unwrap();
+    let start = Instant::now();
+    loop {
+        if let Some(reply) = bot.reply(field) {
+            return reply;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "the engine never replied");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn field() -> Field {
+    let mut field = board_file::parse("*..\n_..\n").unwrap();
+    field.toggle_flag((0, 0));
+    field
+}
+
+#[test]
+fn sends_the_board_and_reads_moves() {
+    let field = field();
+    let mut bot = engine("moves", &["reveal 0 1", "flag 1 1", "chord 0 2"], &field);
+    assert!(!bot.pending());
+    assert_eq!(ask(&mut bot, &field), Ok(Command::Reveal((0, 1))));
+    assert_eq!(ask(&mut bot, &field), Ok(Command::Flag((1, 1))));
+    assert_eq!(ask(&mut bot, &field), Ok(Command::Chord((0, 2))));
+    assert!(!bot.pending());
+
+    let input = std::fs::read_to_string(dir("moves").join("input")).unwrap();
+    let board = "board\nF##\n_##\ngo\n";
+    assert_eq!(input, format!("newgame 2 3 1\n{}{}{}", board, board, board));
+}
+
+#[test]
+fn skips_blank_lines() {
+    let field = field();
+    let mut bot = engine("blank", &["\\n  \\nreveal 1 2"], &field);
+    assert_eq!(ask(&mut bot, &field), Ok(Command::Reveal((1, 2))));
+}
+
+#[test]
+fn refuses_lines_that_arent_moves() {
+    let field = field();
+    let mut bot = engine("malformed", &["dig 0 1", "reveal 0", "reveal a 1", "look around"], &field);
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: unknown command dig".to_string()));
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: expected a row and a column".to_string()));
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: a isn't a row".to_string()));
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: unknown command look".to_string()));
+}
+
+#[test]
+fn refuses_moves_off_the_board() {
+    let field = field();
+    let mut bot = engine("off-board", &["reveal 2 0", "flag 0 3", "chord 99999 99999"], &field);
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: (2, 0) is off the board".to_string()));
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: (0, 3) is off the board".to_string()));
+    assert_eq!(ask(&mut bot, &field), Err("The engine sent a bad move: (99999, 99999) is off the board".to_string()));
+}
+
+#[test]
+fn notices_the_engine_quitting() {
+    let field = field();
+    let mut bot = engine("quit", &["reveal 0 1"], &field);
+    assert!(ask(&mut bot, &field).is_ok());
+    assert_eq!(ask(&mut bot, &field), Err("The engine quit".to_string()));
+}
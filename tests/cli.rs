@@ -0,0 +1,78 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run termsweeper with the given arguments. Returns what it wrote.
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper")).args(args).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn completions_cover_every_subcommand() {
+    for shell in ["bash", "zsh", "fish"] {
+        let script = run(&["completions", shell]);
+        for word in ["bookmarks", "completions", "moving-mines", "export-cast", "no-guess"] {
+            assert!(script.contains(word), "the {} completions are missing {}", shell, word);
+        }
+    }
+}
+
+#[test]
+fn bash_completions_parse() {
+    let script = run(&["completions", "bash"]);
+    // Not every system running the tests has bash
+    let Ok(mut bash) = Command::new("bash").arg("-n").stdin(Stdio::piped()).spawn() else { return };
+    bash.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    assert!(bash.wait().unwrap().success());
+}
+
+#[test]
+fn man_page() {
+    let page = run(&["man"]);
+    assert!(page.lines().any(|line| line.starts_with(".TH TERMSWEEPER 6 ")));
+    assert!(page.contains("\\fB\\-\\-json\\-events\\fR \\fI<FILE>\\fR"));
+    assert!(page.contains("termsweeper\\-edit(6)"));
+}
+
+#[test]
+fn man_page_stops_when_nothing_reads_it() {
+    // As when piped into head, which closes the pipe once it has read what it needs
+    let mut man = Command::new(env!("CARGO_BIN_EXE_termsweeper")).arg("man").stdout(Stdio::piped()).spawn().unwrap();
+    drop(man.stdout.take());
+    let output = man.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn man_pages_for_every_command() {
+    let help = run(&["--help"]);
+    let commands = help.lines()
+        .skip_while(|line| *line != "Commands:")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|command| *command != "help");
+    for command in commands {
+        let page = run(&["man", command]);
+        assert!(page.contains(&format!("\n.TH TERMSWEEPER-{} 6 ", command.to_uppercase())), "no page for {}", command);
+        assert!(page.contains("\\fB\\-\\-profile\\fR"), "the page for {} is missing the global options", command);
+    }
+}
+
+#[test]
+fn bad_arguments_are_errors() {
+    let bad: [&[&str]; 6] = [
+        &["versus", "5"],
+        &["versus", "three"],
+        &["--boards", "2", "--engine", "true"],
+        &["--layers", "9"],
+        &["--record", "game.json", "--sprint"],
+        &["--noisy", "1", "--boards", "2"],
+    ];
+    for args in bad {
+        let output = Command::new(env!("CARGO_BIN_EXE_termsweeper")).args(args).output().unwrap();
+        assert_eq!(output.status.code(), Some(2), "{:?} should be a usage error", args);
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: "));
+    }
+}
@@ -0,0 +1,44 @@
+use termsweeper::custom::{self, CustomGame};
+
+/// A custom game of the given size with the given mines, and the rules left as they are by default.
+fn game(size: (usize, usize), mines: usize) -> CustomGame {
+    CustomGame { size, mines, ..CustomGame::default() }
+}
+
+#[test]
+fn resizing_keeps_the_density() {
+    let mut custom = game((10, 10), 20);
+    custom.resize((10, -5), 0.2);
+    assert_eq!(custom, game((20, 5), 20));
+    custom.resize((0, 1), 0.2);
+    assert_eq!(custom, game((20, 6), 24));
+    // Rounded to the nearest mine
+    custom.resize((-17, 0), 0.2);
+    assert_eq!(custom, game((3, 6), 4));
+}
+
+#[test]
+fn resizing_stays_in_bounds() {
+    let mut custom = game((10, 10), 20);
+    custom.resize((1000, 1000), 0.2);
+    assert_eq!(custom.size, (*CustomGame::ROWS.end(), *CustomGame::COLUMNS.end()));
+    assert_eq!(custom.mines, 990);
+    custom.resize((-1000, -1000), 0.2);
+    assert_eq!(custom.size, (*CustomGame::ROWS.start(), *CustomGame::COLUMNS.start()));
+    // Always at least one mine, and never more than the board can take
+    assert_eq!(custom.mines, 1);
+    custom.resize((2, 2), 0.9);
+    assert_eq!(custom, game((4, 4), 8));
+    custom.no_guess = true;
+    custom.resize((0, 0), 0.9);
+    assert_eq!(custom.mines, 7);
+}
+
+#[test]
+fn previews_fit_the_room() {
+    assert_eq!(custom::preview((2, 3), 10, 10), ["███", "██◎"]);
+    // Too big for the room, so each character stands for a square of cells
+    assert_eq!(custom::preview((5, 8), 4, 10), ["████", "████", "███◎"]);
+    assert_eq!(custom::preview((50, 99), 5, 5), ["█████", "█████", "████◎"]);
+    assert_eq!(custom::preview((2, 2), 1, 1), ["◎"]);
+}
@@ -0,0 +1,151 @@
+// The data directory only comes from XDG_DATA_HOME on these platforms
+#![cfg(all(unix, not(target_os = "macos")))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use termsweeper::board_file;
+use termsweeper::config::Config;
+use termsweeper::daily::{self, Status};
+use termsweeper::game::{Field, Game};
+
+/// The date the given number of days before today, like `2024-03-01`.
+fn days_ago(ago: u64) -> String {
+    daily::date(daily::today() - ago)
+}
+
+#[test]
+fn days_are_counted_from_the_epoch() {
+    assert_eq!(daily::date(0), "1970-01-01");
+    assert_eq!(daily::date(11016), "2000-02-29");
+    assert_eq!(daily::date(19783), "2024-03-01");
+    assert_eq!(daily::parse_day("1970-01-01"), Some(0));
+    assert_eq!(daily::parse_day("2000-02-29"), Some(11016));
+    assert_eq!(daily::parse_day("2024-03-01"), Some(19783));
+    // Days that don't exist aren't some other day
+    assert_eq!(daily::parse_day("2023-02-29"), None);
+    assert_eq!(daily::parse_day("2024-13-01"), None);
+}
+
+#[test]
+fn days_have_boards_of_their_own() {
+    assert_eq!(board_file::write(&daily::field(19783)), board_file::write(&daily::field(19783)));
+    assert_ne!(board_file::write(&daily::field(19783)), board_file::write(&daily::field(19784)));
+    // Not the board that starting over from the day's number as a seed gives
+    let (size, mines) = (daily::PRESET.size(), daily::PRESET.mines());
+    let seeded = Field::with_rng(size, mines, &mut StdRng::seed_from_u64(19783)).unwrap();
+    assert_ne!(board_file::write(&daily::field(19783)), board_file::write(&seeded));
+}
+
+#[test]
+fn wins_are_timed_like_the_clock() {
+    assert_eq!(Status::Won(Duration::from_millis(61500)).describe(), "won in 1:01");
+    assert_eq!(Status::Won(Duration::from_secs(605)).describe(), "won in 10:05");
+}
+
+/// A data and config directory of its own for each test, with the given daily challenge results file.
+fn dir(name: &str, results: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-daily-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(dir.join("termsweeper")).unwrap();
+    std::fs::write(dir.join("termsweeper/daily.toml"), results).unwrap();
+    dir
+}
+
+/// Run termsweeper with the given daily challenge results file, returning whether it succeeded and what it printed.
+fn run(name: &str, results: &str, args: &[&str]) -> (bool, String) {
+    run_in(&dir(name, results), args)
+}
+
+/// Run termsweeper with the given data and config directory, returning whether it succeeded and what it printed.
+fn run_in(dir: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .args(args)
+        .args(["--lang", "en"])
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .output()
+        .unwrap();
+    (output.status.success(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn finished_daily_isnt_played_again() {
+    let results = format!("[days.{:?}]\nwon = true\ntime_ms = 61500\n", days_ago(0));
+    let (success, output) = run("finished", &results, &["daily"]);
+    assert!(success);
+    assert!(output.contains("over: won in 1:01"), "{}", output);
+}
+
+#[test]
+fn archive_games_dont_count_towards_the_streak() {
+    let won = "won = true\ntime_ms = 30000\n";
+    let results = format!(
+        "[days.{:?}]\n{won}[days.{:?}]\n{won}[archive.{:?}]\n{won}",
+        days_ago(0),
+        days_ago(1),
+        days_ago(2),
+    );
+    let (success, output) = run("streak", &results, &["daily"]);
+    assert!(success);
+    assert!(output.contains("Days won in a row: 2."), "{}", output);
+}
+
+#[test]
+fn archive_needs_a_past_date() {
+    for date in ["2024-02-31", "tomorrow", &daily::date(daily::today() + 1)] {
+        let (success, _) = run("dates", "", &["--daily", date]);
+        assert!(!success, "{}", date);
+    }
+}
+
+/// Win a headless game of the day's challenge, by revealing every safe cell from the prompt.
+fn win(day: u64, game: impl FnOnce(Game) -> Game) {
+    let field = daily::field(day);
+    let (rows, cols) = field.size();
+    let safe = (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (row, col)))
+        .filter(|&pos| !field.cell(pos).unwrap().is_mine())
+        .collect::<Vec<_>>();
+    let mut game = game(Game::headless(field, Config::default(), (80, 40), std::io::sink()));
+    let mut press = |code| {
+        game.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE))).unwrap();
+    };
+    for (row, col) in safe {
+        format!(":reveal {} {}", row, col).chars().for_each(|c| press(KeyCode::Char(c)));
+        press(KeyCode::Enter);
+    }
+}
+
+#[test]
+fn archive_wins_arent_ranked() {
+    let dir = dir("ranked", "");
+    let best = concat!(
+        r#"{"timestamp":1700000000,"preset":"intermediate","size":[16,16],"mines":40,"won":true,"time_ms":100000,"#,
+        r#""bbbv":100,"clicks":120}"#, "\n",
+    );
+    std::fs::write(dir.join("termsweeper/stats.jsonl"), best).unwrap();
+    let stats = || run_in(&dir, &["stats"]).1;
+    let before = stats();
+    assert!(before.contains("Rating: 1215 (1 ranked games)"), "{}", before);
+    assert!(before.contains("Intermediate      1     1   100%   100.0s"), "{}", before);
+
+    // The only test here that plays a game itself, rather than through termsweeper
+    std::env::set_var("XDG_DATA_HOME", &dir);
+    // Not even if it's marked as the preset it's played on
+    let yesterday = daily::today() - 1;
+    win(yesterday, |game| game.with_preset(daily::PRESET).with_daily(yesterday, true));
+    let results = std::fs::read_to_string(dir.join("termsweeper/daily.toml")).unwrap();
+    assert!(results.contains(&format!("[archive.{}]\nwon = true", daily::date(yesterday))), "{}", results);
+    assert_eq!(stats(), before);
+
+    // Unlike today's, which is ranked like any other game on the preset
+    let today = daily::today();
+    win(today, |game| game.with_preset(daily::PRESET).with_daily(today, false));
+    assert!(stats().contains("(2 ranked games)"));
+}
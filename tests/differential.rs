@@ -0,0 +1,72 @@
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::board::BitBoard;
+use termsweeper::engine::Command;
+use termsweeper::game::{Direction, Field};
+use termsweeper::solver;
+
+mod reference;
+
+/// A seeded field with a random size and density.
+fn field() -> impl Strategy<Value = Field> {
+    (1usize..=16, 1usize..=16, 0.0f64..=0.4, any::<u64>()).prop_map(|(rows, cols, density, seed)| {
+        let mines = ((rows * cols) as f64 * density) as usize;
+        Field::with_rng((rows, cols), mines, &mut StdRng::seed_from_u64(seed)).unwrap()
+    })
+}
+
+/// A field, plus commands to play on it. Positions are fractions of its size, so they always land in bounds, and
+/// reveals come up most often so that games get somewhere.
+fn field_and_commands() -> impl Strategy<Value = (Field, Vec<Command>)> {
+    let directions = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+        Direction::PrevLayer,
+        Direction::NextLayer,
+    ];
+    let command = (0..6u8, 0.0f64..1.0, 0.0f64..1.0, prop::sample::select(directions.to_vec()));
+    (field(), prop::collection::vec(command, 0..80)).prop_map(|(field, commands)| {
+        let (rows, cols) = field.size();
+        let commands = commands.into_iter()
+            .map(|(kind, row, col, direction)| {
+                let pos = ((row * rows as f64) as usize, (col * cols as f64) as usize);
+                match kind {
+                    0 | 1 => Command::Reveal(pos),
+                    2 => Command::Flag(pos),
+                    3 | 4 => Command::Chord(pos),
+                    _ => Command::Move(direction),
+                }
+            })
+            .collect();
+        (field, commands)
+    })
+}
+
+proptest! {
+    /// Random games play out the same on the engine as on the reference.
+    #[test]
+    fn engine_plays_like_the_reference((field, commands) in field_and_commands()) {
+        reference::play(field, commands);
+    }
+
+    /// The reveals and flags of random games play out the same on a bitboard, and on the field itself, as on the
+    /// reference, and leave the solver seeing the same on both.
+    #[test]
+    fn bitboards_play_like_the_reference((mut field, commands) in field_and_commands()) {
+        let mut board = BitBoard::from(&field);
+        reference::play_board(&mut board, commands.clone());
+        reference::play_board(&mut field, commands);
+        let (expected, found) = (solver::analyze(&field), solver::analyze(&board));
+        prop_assert_eq!(expected.safe(), found.safe());
+        prop_assert_eq!(expected.mines(), found.mines());
+        prop_assert_eq!(solver::frontier(&field), solver::frontier(&board));
+    }
+}
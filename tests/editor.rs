@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use termsweeper::board_file;
+use termsweeper::config::Config;
+use termsweeper::editor::Editor;
+
+/// Where the board file for a test goes, with nothing there yet.
+fn path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-editor-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.txt", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Press each key in turn, with the default controls: arrows to move, space for a mine and f for a void.
+fn press(editor: &mut Editor, keys: &[KeyCode]) {
+    for &code in keys {
+        assert!(editor.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE))).unwrap());
+    }
+}
+
+fn save(editor: &mut Editor) {
+    assert!(editor.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL))).unwrap());
+}
+
+#[test]
+fn saves_the_board_as_laid_out() {
+    let path = path("new");
+    let mut editor = Editor::open(path.clone(), (2, 3), &Config::default()).unwrap();
+    use KeyCode::*;
+    press(&mut editor, &[Char(' '), Right, Char('f'), Down, Char(' ')]);
+    // The cursor stops at the edges
+    press(&mut editor, &[Right, Right, Right, Char(' '), Char(' ')]);
+    save(&mut editor);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "*_.\n.*.\n");
+
+    // Each key undoes itself, and a void made a mine is no longer a void
+    press(&mut editor, &[Up, Left, Char(' ')]);
+    save(&mut editor);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "**.\n.*.\n");
+}
+
+#[test]
+fn edits_boards_already_saved() {
+    let path = path("existing");
+    std::fs::write(&path, "..*\n_..\n").unwrap();
+    // The size is only for new boards
+    let mut editor = Editor::open(path.clone(), (9, 9), &Config::default()).unwrap();
+    press(&mut editor, &[KeyCode::Down, KeyCode::Char('f')]);
+    save(&mut editor);
+    let field = board_file::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(field.size(), (2, 3));
+    assert_eq!(board_file::write(&field), "..*\n...\n");
+}
+
+#[test]
+fn only_saves_playable_boards() {
+    let path = path("unplayable");
+    let mut editor = Editor::open(path.clone(), (1, 2), &Config::default()).unwrap();
+    save(&mut editor);
+    assert!(!path.exists());
+    // Nor a board that's all mines
+    press(&mut editor, &[KeyCode::Char(' '), KeyCode::Right, KeyCode::Char(' ')]);
+    save(&mut editor);
+    assert!(!path.exists());
+    press(&mut editor, &[KeyCode::Char(' ')]);
+    save(&mut editor);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "*.\n");
+}
+
+#[test]
+fn quits() {
+    let mut editor = Editor::open(path("quit"), (3, 3), &Config::default()).unwrap();
+    assert!(!editor.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))).unwrap());
+}
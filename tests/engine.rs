@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::engine::{Challenge, Command, Constraints, GameEngine, GameEvent};
+use termsweeper::game::{Direction, Field};
+
+/// A seeded Beginner-sized board:
+///
+/// ```text
+/// 0012*2*2*
+/// 001*33121
+/// 0012*1000
+/// 000111011
+/// 00000001*
+/// 011100022
+/// 12*10001*
+/// *21100022
+/// 11000001*
+/// ```
+fn engine() -> GameEngine {
+    GameEngine::new(Field::with_rng((9, 9), 10, &mut StdRng::seed_from_u64(896)).unwrap())
+}
+
+#[test]
+fn flood_fill_reveals_each_cell() {
+    let mut engine = engine();
+    let events = engine.subscribe();
+    assert!(engine.apply(Command::Reveal((0, 0))));
+
+    let revealed = events.try_iter().collect::<Vec<_>>();
+    assert!(revealed.len() > 1);
+    for event in revealed {
+        let GameEvent::CellRevealed { pos, number } = event else { panic!("unexpected {:?}", event) };
+        let cell = engine.field().cell(pos).unwrap();
+        assert!(!cell.is_hidden());
+        assert_eq!(cell.shown_number(), Some(number));
+    }
+}
+
+#[test]
+fn revealing_a_mine_loses() {
+    let mut engine = engine();
+    let events = engine.subscribe();
+    engine.apply(Command::Reveal((0, 4)));
+
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), [GameEvent::MineExploded { pos: (0, 4) }, GameEvent::GameLost]);
+    assert!(engine.ended() && !engine.won());
+    assert!(!engine.apply(Command::Reveal((0, 0))));
+}
+
+#[test]
+fn flags_are_reported_both_ways() {
+    let mut engine = engine();
+    let events = engine.subscribe();
+    engine.apply(Command::Flag((0, 4)));
+    engine.apply(Command::Flag((0, 4)));
+
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), [
+        GameEvent::CellFlagged { pos: (0, 4), flagged: true },
+        GameEvent::CellFlagged { pos: (0, 4), flagged: false },
+    ]);
+}
+
+#[test]
+fn every_subscriber_gets_every_event() {
+    let mut engine = engine();
+    let first = engine.subscribe();
+    let second = engine.subscribe();
+    drop(engine.subscribe());
+    engine.apply(Command::Flag((0, 4)));
+
+    assert_eq!(first.try_iter().count(), 1);
+    assert_eq!(second.try_iter().count(), 1);
+}
+
+#[test]
+fn chord_needs_matching_flags() {
+    let mut engine = engine();
+    engine.apply(Command::Reveal((0, 3)));
+    assert!(!engine.apply(Command::Chord((0, 3))));
+
+    engine.apply(Command::Flag((0, 4)));
+    engine.apply(Command::Flag((1, 3)));
+    let events = engine.subscribe();
+    assert!(engine.apply(Command::Chord((0, 3))));
+    assert!(!engine.field().cell((1, 4)).unwrap().is_hidden());
+    assert!(!engine.field().cell((0, 2)).unwrap().is_hidden());
+    assert!(events.try_iter().all(|event| matches!(event, GameEvent::CellRevealed { .. })));
+}
+
+#[test]
+fn wrong_flags_chord_into_a_mine() {
+    let mut engine = engine();
+    engine.apply(Command::Reveal((0, 3)));
+    engine.apply(Command::Flag((0, 4)));
+    engine.apply(Command::Flag((1, 2)));
+    engine.apply(Command::Chord((0, 3)));
+    assert!(engine.ended() && !engine.won());
+}
+
+#[test]
+fn cursor_stops_at_the_edges() {
+    let mut engine = engine();
+    assert!(!engine.apply(Command::Move(Direction::Up)));
+    assert!(engine.apply(Command::Move(Direction::DownRight)));
+    assert_eq!(engine.cursor(), (1, 1));
+    for _ in 0..10 {
+        engine.apply(Command::Move(Direction::Right));
+    }
+    assert_eq!(engine.cursor(), (1, 8));
+}
+
+#[test]
+fn wrapping_cursor_comes_round_the_edges() {
+    let mut engine = engine().with_wrapping_cursor();
+    assert!(engine.apply(Command::Move(Direction::UpLeft)));
+    assert_eq!(engine.cursor(), (8, 8));
+    assert!(engine.apply(Command::Move(Direction::Right)));
+    assert_eq!(engine.cursor(), (8, 0));
+}
+
+#[test]
+fn clock_runs_from_the_first_reveal_to_the_end() {
+    let mut engine = engine();
+    engine.advance(Duration::from_secs(5));
+    assert_eq!(engine.elapsed(), Duration::ZERO);
+
+    engine.apply(Command::Reveal((0, 0)));
+    engine.advance(Duration::from_millis(1500));
+    engine.advance(Duration::from_millis(500));
+    assert_eq!(engine.elapsed(), Duration::from_secs(2));
+
+    engine.apply(Command::Reveal((0, 4)));
+    engine.advance(Duration::from_secs(5));
+    assert_eq!(engine.elapsed(), Duration::from_secs(2));
+}
+
+#[test]
+fn progress_is_the_share_of_safe_cells_revealed() {
+    let mut engine = engine();
+    assert_eq!(engine.progress(), 0.0);
+    engine.apply(Command::Reveal((0, 0)));
+    assert_eq!(engine.progress(), engine.cleared() as f64 / 71.0);
+
+    for row in 0..9 {
+        for col in 0..9 {
+            if !engine.field().cell((row, col)).unwrap().is_mine() {
+                engine.apply(Command::Reveal((row, col)));
+            }
+        }
+    }
+    assert!(engine.won());
+    assert_eq!(engine.progress(), 1.0);
+}
+
+#[test]
+fn sprint_ends_when_time_runs_out() {
+    let mut engine = engine().with_challenge(Challenge::Sprint(Duration::from_secs(60)));
+    let events = engine.subscribe();
+    engine.apply(Command::Reveal((0, 0)));
+    let cleared = engine.cleared();
+    engine.advance(Duration::from_secs(59));
+    assert_eq!(engine.remaining(), Some(Duration::from_secs(1)));
+
+    engine.advance(Duration::from_secs(5));
+    assert!(engine.ended() && engine.time_up() && !engine.won());
+    assert_eq!(events.try_iter().last(), Some(GameEvent::TimeUp));
+    assert_eq!(engine.elapsed(), Duration::from_secs(60));
+    assert!(cleared > 1);
+    assert_eq!(engine.cleared(), cleared);
+}
+
+#[test]
+fn countdown_is_lost_when_time_runs_out() {
+    let mut engine = engine().with_challenge(Challenge::Countdown(Duration::from_secs(60)));
+    let events = engine.subscribe();
+    engine.apply(Command::Reveal((0, 0)));
+    engine.advance(Duration::from_secs(60));
+
+    let events = events.try_iter().collect::<Vec<_>>();
+    assert_eq!(events[events.len() - 2..], [GameEvent::TimeUp, GameEvent::GameLost]);
+    assert!(!engine.apply(Command::Reveal((8, 0))));
+}
+
+#[test]
+fn hardcore_penalizes_flags_on_safe_cells() {
+    let mut engine = engine().with_flag_penalty(Duration::from_secs(10));
+    let events = engine.subscribe();
+    // Before the first reveal, the clock hasn't started, so there's nothing to add the time to
+    engine.apply(Command::Flag((8, 3)));
+    engine.apply(Command::Flag((8, 3)));
+    assert_eq!(engine.penalties(), Duration::ZERO);
+    engine.apply(Command::Reveal((0, 0)));
+    engine.apply(Command::Flag((0, 4)));
+    assert_eq!(engine.penalties(), Duration::ZERO);
+
+    engine.apply(Command::Flag((0, 5)));
+    assert_eq!(events.try_iter().last(), Some(GameEvent::Penalty { pos: (0, 5), time: Duration::from_secs(10) }));
+    // Taking the flag back off doesn't refund it
+    engine.apply(Command::Flag((0, 5)));
+    assert_eq!(engine.penalties(), Duration::from_secs(10));
+    assert_eq!(engine.elapsed(), Duration::from_secs(10));
+}
+
+#[test]
+fn no_flags_rejects_flagging() {
+    let mut constraints = Constraints::default();
+    constraints.no_flags = true;
+    let mut engine = engine().with_constraints(constraints);
+    engine.apply(Command::Reveal((0, 0)));
+    assert!(!engine.apply(Command::Flag((0, 4))));
+    assert!(!engine.field().cell((0, 4)).unwrap().is_flagged());
+}
+
+#[test]
+fn running_out_of_clicks_loses() {
+    let mut constraints = Constraints::default();
+    constraints.max_clicks = Some(2);
+    let mut engine = engine().with_constraints(constraints);
+    let events = engine.subscribe();
+    engine.apply(Command::Reveal((0, 0)));
+    // Clicks that reveal nothing don't count
+    assert!(!engine.apply(Command::Reveal((0, 0))));
+    assert_eq!(engine.clicks(), 1);
+    assert!(!engine.ended());
+
+    engine.apply(Command::Reveal((8, 0)));
+    assert_eq!(engine.clicks(), 2);
+    assert!(engine.ended() && !engine.won());
+    assert_eq!(events.try_iter().last(), Some(GameEvent::GameLost));
+}
+
+#[test]
+fn moving_mines_keep_the_numbers_right() {
+    let mut engine = engine().with_moving_mines(Duration::from_secs(10), 910);
+    let events = engine.subscribe();
+    engine.apply(Command::Reveal((0, 0)));
+    events.try_iter().for_each(drop);
+    engine.advance(Duration::from_secs(35));
+
+    let events = events.try_iter().collect::<Vec<_>>();
+    assert_eq!(events.iter().filter(|event| **event == GameEvent::MineMoved).count(), 3);
+    // A cell can change more than once, so only its last change has to match the board
+    let changes = events.into_iter()
+        .filter_map(|event| match event {
+            GameEvent::NumberChanged { pos, number } => Some((pos, number)),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    for (pos, number) in changes {
+        assert_eq!(engine.field().cell(pos).unwrap().shown_number(), Some(number));
+    }
+
+    let field = engine.field();
+    assert_eq!(field.mine_count(), 10);
+    let (rows, cols) = field.size();
+    for row in 0..rows {
+        for col in 0..cols {
+            let mines = field.neighbors((row, col)).filter(|&pos| field.cell(pos).unwrap().is_mine()).count();
+            assert_eq!(field.cell((row, col)).unwrap().neighbor_count() as usize, mines);
+        }
+    }
+}
+
+#[test]
+fn noisy_numbers_hold_the_real_number() {
+    let ranges = || {
+        let mut engine = engine().with_noisy_numbers(2, 979);
+        engine.apply(Command::Reveal((0, 0)));
+        engine.apply(Command::Reveal((8, 8)));
+        let field = engine.field();
+        let (rows, cols) = field.size();
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .filter_map(|pos| Some((pos, field.shown_range(pos)?, field.cell(pos).unwrap().neighbor_count())))
+            .collect::<Vec<_>>()
+    };
+    let shown = ranges();
+    assert!(!shown.is_empty());
+    for &(pos, (low, high), number) in &shown {
+        assert!(low >= 1 && low <= number && number <= high && high - low <= 2, "{:?}", pos);
+    }
+    // The same seed picks the same ranges
+    assert_eq!(ranges(), shown);
+}
@@ -0,0 +1,80 @@
+use termsweeper::board_file;
+use termsweeper::export;
+use termsweeper::game::Field;
+use termsweeper::topology::Topology;
+
+/// A small board partway through a game: a number, an empty cell, a flag, a mine set off, and a void.
+fn field() -> Field {
+    let mut field = board_file::parse("*.\n_.\n*.\n").unwrap();
+    field.toggle_flag((0, 0));
+    field.clear_cell((0, 1));
+    field.clear_cell((2, 0));
+    field
+}
+
+/// The size an SVG says it is, as (width, height).
+fn svg_size(svg: &str) -> (usize, usize) {
+    let attribute = |name: &str| {
+        let start = svg.find(&format!("{}=\"", name)).unwrap() + name.len() + 2;
+        svg[start..].split('"').next().unwrap().parse().unwrap()
+    };
+    (attribute("width"), attribute("height"))
+}
+
+#[test]
+fn square_board_svg() {
+    let svg = export::svg(&field());
+    assert_eq!(svg_size(&svg), (2 * 24, 3 * 24));
+    insta::assert_snapshot!(svg);
+}
+
+#[test]
+fn triangular_board_svg() {
+    let mut field = Field::with_topology(Topology::Triangular, (2, 3), &[(1, 1)]).unwrap();
+    field.clear_cell((0, 0));
+    let svg = export::svg(&field);
+    // Every cell is a triangle, not a square
+    assert_eq!(svg.matches("<polygon").count(), 6);
+    insta::assert_snapshot!(svg);
+}
+
+#[test]
+fn layers_are_drawn_apart() {
+    let field = Field::with_topology(Topology::Layered { layers: 2 }, (2, 4), &[(0, 0)]).unwrap();
+    // With a cell's worth of room between the two layers, as in the terminal
+    assert_eq!(svg_size(&export::svg(&field)), (5 * 24, 2 * 24));
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png_is_the_size_of_the_board() {
+    let png = export::png(&field()).unwrap();
+    assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+    // The header chunk comes first: its length and type, then the width and height
+    assert_eq!(png[12..16], *b"IHDR");
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    assert_eq!((width, height), (2 * 24, 3 * 24));
+}
+
+#[cfg(any(feature = "png", feature = "graphics"))]
+#[test]
+fn tiny_tiles_are_drawn_bigger() {
+    let mut triangles = Field::with_topology(Topology::Triangular, (2, 3), &[(1, 1)]).unwrap();
+    triangles.clear_cell((0, 0));
+    triangles.clear_cell((1, 1));
+    for field in [field(), triangles] {
+        let (width, height) = svg_size(&export::svg(&field));
+        let cells = (width / 24, height / 24);
+        // Too small to draw a number in, so they're drawn as small as there's room for one
+        for tile in [(0, 0), (1, 1), (2, 3), (5, 2)] {
+            let (width, height, pixels) = export::pixels(&field, tile, Some((0, 0)));
+            assert_eq!((width, height), (cells.0 * tile.0.max(5), cells.1 * tile.1.max(7)));
+            assert_eq!(pixels.len(), width * height * 3);
+        }
+        for tile in (5..30).flat_map(|width| (7..30).map(move |height| (width, height))) {
+            let (width, height, _) = export::pixels(&field, tile, Some((1, 1)));
+            assert_eq!((width, height), (cells.0 * tile.0, cells.1 * tile.1));
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::game::Field;
+use termsweeper::solver;
+use termsweeper::topology::Topology;
+
+/// A seeded field with a random size and density, along with its mine count.
+fn field() -> impl Strategy<Value = (Field, usize)> {
+    (1usize..=30, 1usize..=30, 0.0f64..=0.5, any::<u64>()).prop_map(|(rows, cols, density, seed)| {
+        let mines = ((rows * cols) as f64 * density) as usize;
+        let field = Field::with_rng((rows, cols), mines, &mut StdRng::seed_from_u64(seed)).unwrap();
+        (field, mines)
+    })
+}
+
+/// A field, plus a list of positions on it (as fractions of its size, so they always land in bounds).
+fn field_and_clicks() -> impl Strategy<Value = (Field, usize, Vec<(usize, usize)>)> {
+    (field(), prop::collection::vec((0.0f64..1.0, 0.0f64..1.0), 0..20)).prop_map(|((field, mines), clicks)| {
+        let (rows, cols) = field.size();
+        let clicks = clicks.into_iter()
+            .map(|(row, col)| ((row * rows as f64) as usize, (col * cols as f64) as usize))
+            .collect();
+        (field, mines, clicks)
+    })
+}
+
+/// A field on one of the other topologies, with a random size and mines.
+fn shaped_field() -> impl Strategy<Value = Field> {
+    let topology = prop_oneof![
+        (2usize..=4).prop_map(|layers| Topology::Layered { layers }),
+        Just(Topology::Triangular),
+    ];
+    (topology, 1usize..=8, 1usize..=8, prop::collection::vec((0.0f64..1.0, 0.0f64..1.0), 0..40))
+        .prop_map(|(topology, rows, cols, mines)| {
+            let size = match topology {
+                Topology::Layered { layers } => (rows, cols * layers),
+                _ => (rows, cols),
+            };
+            let mut mines = mines.into_iter()
+                .map(|(row, col)| ((row * size.0 as f64) as usize, (col * size.1 as f64) as usize))
+                .collect::<Vec<_>>();
+            mines.sort();
+            mines.dedup();
+            Field::with_topology(topology, size, &mines).unwrap()
+        })
+}
+
+fn count_adjacent_mines(field: &Field, pos: (usize, usize)) -> u8 {
+    field.neighbors(pos)
+        .filter(|&neighbor| field.cell(neighbor).unwrap().is_mine())
+        .count() as u8
+}
+
+proptest! {
+    #[test]
+    fn neighbor_counts_match_adjacent_mines((field, _) in field()) {
+        let (rows, cols) = field.size();
+        for row in 0..rows {
+            for col in 0..cols {
+                prop_assert_eq!(field.cell((row, col)).unwrap().neighbor_count(), count_adjacent_mines(&field, (row, col)));
+            }
+        }
+    }
+
+    #[test]
+    fn mine_count_is_conserved((mut field, mines, clicks) in field_and_clicks()) {
+        prop_assert_eq!(field.mine_count(), mines);
+        for (i, pos) in clicks.into_iter().enumerate() {
+            if i % 2 == 0 {
+                field.clear_cell(pos);
+            } else {
+                field.toggle_flag(pos);
+            }
+            prop_assert_eq!(field.mine_count(), mines);
+        }
+    }
+
+    #[test]
+    fn flood_fill_never_reveals_a_mine((mut field, _, clicks) in field_and_clicks()) {
+        for pos in clicks {
+            let was_mine = field.cell(pos).unwrap().is_mine();
+            let Some(reveal) = field.clear_cell(pos) else { continue };
+            prop_assert_eq!(reveal.exploded, was_mine);
+            if reveal.exploded {
+                prop_assert_eq!(reveal.cells, vec![pos]);
+            } else {
+                for cell in reveal.cells {
+                    prop_assert!(!field.cell(cell).unwrap().is_mine());
+                    prop_assert!(!field.cell(cell).unwrap().is_hidden());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn toggling_a_flag_twice_changes_nothing((mut field, _, clicks) in field_and_clicks()) {
+        for (i, pos) in clicks.into_iter().enumerate() {
+            if i % 3 == 0 {
+                field.clear_cell(pos);
+                continue;
+            }
+            let before = *field.cell(pos).unwrap();
+            let first = field.toggle_flag(pos);
+            let second = field.toggle_flag(pos);
+            prop_assert_eq!(first, second);
+            prop_assert_eq!(*field.cell(pos).unwrap(), before);
+        }
+    }
+
+    #[test]
+    fn frontier_borders_the_revealed_numbers((mut field, _, clicks) in field_and_clicks()) {
+        for pos in clicks {
+            field.clear_cell(pos);
+        }
+        for (row, cells) in solver::frontier(&field).into_iter().enumerate() {
+            for (col, on_frontier) in cells.into_iter().enumerate() {
+                let next_to_number = field.neighbors((row, col))
+                    .any(|pos| field.cell(pos).unwrap().shown_number().is_some_and(|number| number > 0));
+                prop_assert_eq!(on_frontier, field.cell((row, col)).unwrap().is_hidden() && next_to_number);
+            }
+        }
+    }
+
+    #[test]
+    fn flag_report_accounts_for_every_flag_and_mine((mut field, mines, clicks) in field_and_clicks()) {
+        for pos in clicks {
+            field.toggle_flag(pos);
+        }
+        let flags = (0..field.size().0)
+            .flat_map(|row| (0..field.size().1).map(move |col| (row, col)))
+            .filter(|&pos| field.cell(pos).unwrap().is_flagged())
+            .count();
+        let report = field.flag_report();
+        prop_assert_eq!(report.correct + report.wrong, flags);
+        prop_assert_eq!(report.correct + report.unflagged, mines);
+    }
+
+    #[test]
+    fn shaped_neighbor_counts_match_adjacent_mines(field in shaped_field()) {
+        let (rows, cols) = field.size();
+        for row in 0..rows {
+            for col in 0..cols {
+                prop_assert_eq!(field.cell((row, col)).unwrap().neighbor_count(), count_adjacent_mines(&field, (row, col)));
+                prop_assert!(field.neighbors((row, col)).count() <= 26);
+            }
+        }
+    }
+
+    #[test]
+    fn adding_and_removing_mines_keeps_the_counts((mut field, _, clicks) in field_and_clicks()) {
+        let (rows, cols) = field.size();
+        let (opening, edits) = clicks.split_at(clicks.len() / 2);
+        for &pos in opening {
+            field.clear_cell(pos);
+        }
+        for &pos in edits {
+            let number = |field: &Field, pos| field.cell(pos).unwrap().shown_number();
+            let before = (0..rows)
+                .flat_map(|row| (0..cols).map(move |col| (row, col)))
+                .map(|pos| (pos, number(&field, pos)))
+                .collect::<Vec<_>>();
+            let hidden = field.cell(pos).unwrap().is_hidden();
+            let mines = field.mine_count();
+            let changed = if field.cell(pos).unwrap().is_mine() {
+                field.remove_mine(pos).map(|changed| (changed, mines - 1))
+            } else {
+                field.add_mine(pos).map(|changed| (changed, mines + 1))
+            };
+            let Some((changed, mines)) = changed else {
+                prop_assert!(!hidden);
+                continue;
+            };
+            prop_assert_eq!(field.mine_count(), mines);
+            for (pos, before) in before {
+                let cell = field.cell(pos).unwrap();
+                prop_assert_eq!(cell.neighbor_count(), count_adjacent_mines(&field, pos));
+                let shown = number(&field, pos);
+                prop_assert!(shown.is_none() || shown == Some(cell.neighbor_count()));
+                prop_assert_eq!(changed.contains(&pos), shown.is_some() && before != shown);
+            }
+        }
+    }
+
+    #[test]
+    fn recomputing_a_consistent_board_changes_nothing((mut field, _, clicks) in field_and_clicks()) {
+        for &pos in &clicks {
+            field.clear_cell(pos);
+        }
+        let (rows, cols) = field.size();
+        let everywhere = (0..rows + 1).flat_map(|row| (0..cols + 1).map(move |col| (row, col)));
+        prop_assert!(field.recompute_neighbors(everywhere).is_empty());
+        for row in 0..rows {
+            for col in 0..cols {
+                prop_assert_eq!(field.cell((row, col)).unwrap().neighbor_count(), count_adjacent_mines(&field, (row, col)));
+            }
+        }
+    }
+
+    #[test]
+    fn cached_analysis_matches_a_fresh_one((mut field, _, clicks) in field_and_clicks()) {
+        for (i, pos) in clicks.into_iter().enumerate() {
+            match i % 4 {
+                3 => _ = field.toggle_flag(pos),
+                // Moving mines around means starting the deductions over
+                2 if field.cell(pos).unwrap().is_mine() => _ = field.remove_mine(pos),
+                _ => _ = field.clear_cell(pos),
+            }
+            let (cached, fresh) = (solver::analyze_cached(&field), solver::analyze(&field));
+            prop_assert_eq!(cached.safe(), fresh.safe());
+            prop_assert_eq!(cached.mines(), fresh.mines());
+        }
+    }
+
+    #[test]
+    fn shaped_neighbors_are_mutual(field in shaped_field()) {
+        let (rows, cols) = field.size();
+        for row in 0..rows {
+            for col in 0..cols {
+                for neighbor in field.neighbors((row, col)) {
+                    prop_assert!(field.neighbors(neighbor).any(|pos| pos == (row, col)));
+                }
+            }
+        }
+    }
+}
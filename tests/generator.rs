@@ -0,0 +1,137 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::board_file;
+use termsweeper::game::Field;
+use termsweeper::generator::{BoardGenerator, Clustered, NoGuess, Pattern, Patterned, Symmetric, Symmetry, Uniform};
+use termsweeper::solver;
+
+/// Beginner, Intermediate and Expert, as ((rows, columns), mines).
+const PRESETS: [((usize, usize), usize); 3] = [((9, 9), 10), ((16, 16), 40), ((16, 30), 99)];
+
+fn mines(field: &Field) -> Vec<(usize, usize)> {
+    let (rows, cols) = field.size();
+    (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (row, col)))
+        .filter(|&pos| field.cell(pos).unwrap().is_mine())
+        .collect()
+}
+
+#[test]
+fn generators_place_every_mine() {
+    let generators: [&dyn BoardGenerator; 6] = [
+        &Uniform,
+        &NoGuess,
+        &Clustered,
+        &Symmetric { symmetry: Symmetry::Rotational },
+        &Patterned { pattern: Pattern::Rings },
+        &Patterned { pattern: Pattern::Stripes },
+    ];
+    for generator in generators {
+        for (size, count) in PRESETS {
+            let field = generator.generate(size, count, &mut StdRng::seed_from_u64(903)).unwrap();
+            assert_eq!(field.size(), size);
+            assert_eq!(field.mine_count(), count);
+        }
+    }
+}
+
+#[test]
+fn too_many_mines_are_rejected() {
+    let generators: [&dyn BoardGenerator; 6] = [
+        &Uniform,
+        &NoGuess,
+        &Clustered,
+        &Symmetric { symmetry: Symmetry::Rotational },
+        &Patterned { pattern: Pattern::Rings },
+        &Patterned { pattern: Pattern::Stripes },
+    ];
+    for generator in generators {
+        assert!(generator.generate((4, 4), 9, &mut StdRng::seed_from_u64(903)).is_none());
+        assert!(generator.generate((0, 4), 0, &mut StdRng::seed_from_u64(903)).is_none());
+    }
+}
+
+#[test]
+fn no_guess_boards_solve_from_the_opening() {
+    for (size, count) in PRESETS {
+        let mut field = NoGuess.generate(size, count, &mut StdRng::seed_from_u64(903)).unwrap();
+        assert!(!field.cell((size.0 / 2, size.1 / 2)).unwrap().is_hidden());
+        loop {
+            let analysis = solver::analyze(&field);
+            if analysis.safe().is_empty() {
+                break;
+            }
+            for &pos in analysis.safe() {
+                // Earlier reveals may have flood filled this cell already
+                if let Some(reveal) = field.clear_cell(pos) {
+                    assert!(!reveal.exploded);
+                }
+            }
+        }
+        assert!(field.is_cleared());
+    }
+}
+
+#[test]
+fn no_guess_gives_up_on_dense_boards() {
+    // A board this big gets fewer tries than Expert, so it's given up on in about the same time
+    assert!(NoGuess.generate((60, 60), 1440, &mut StdRng::seed_from_u64(903)).is_none());
+}
+
+#[test]
+fn no_guess_boards_are_the_same_for_the_same_seed() {
+    // With the parallel feature, layouts are tried on several threads at once; the first that works must still win
+    for (size, count) in PRESETS {
+        let generate = || NoGuess.generate(size, count, &mut StdRng::seed_from_u64(905)).unwrap();
+        assert_eq!(mines(&generate()), mines(&generate()));
+    }
+}
+
+#[test]
+fn no_guess_boards_are_the_same_with_or_without_threads() {
+    // The fourth layout tried is the first the solver can clear. Trying them all at once, with the parallel feature,
+    // has to find the same one as trying them in order, so this board is checked for both ways
+    let field = NoGuess.generate((16, 16), 40, &mut StdRng::seed_from_u64(950)).unwrap();
+    let expected = concat!(
+        "..*.........*...\n",
+        ".....*....*.*...\n",
+        "...**.**..*.....\n",
+        "...*..*.........\n",
+        ".........*.*..*.\n",
+        ".............*..\n",
+        "**...........*..\n",
+        ".....*.......*..\n",
+        "................\n",
+        "...*..*.........\n",
+        ".......*...*..*.\n",
+        "*.*.......***...\n",
+        ".......*....*...\n",
+        ".....*........*.\n",
+        "....*.......*.*.\n",
+        "..*.*...........\n",
+    );
+    assert_eq!(board_file::write(&field), expected);
+}
+
+/// Where a cell lands under a symmetry, given the board size.
+type Image = fn((usize, usize), (usize, usize)) -> (usize, usize);
+
+#[test]
+fn symmetric_boards_match_their_image() {
+    let images: [(Symmetry, Image); 3] = [
+        (Symmetry::LeftRight, |size, (row, col)| (row, size.1 - 1 - col)),
+        (Symmetry::TopBottom, |size, (row, col)| (size.0 - 1 - row, col)),
+        (Symmetry::Rotational, |size, (row, col)| (size.0 - 1 - row, size.1 - 1 - col)),
+    ];
+    for (symmetry, image) in images {
+        for (size, count) in PRESETS {
+            let field = Symmetric { symmetry }.generate(size, count, &mut StdRng::seed_from_u64(904)).unwrap();
+            let unmatched = mines(&field).into_iter()
+                .filter(|&pos| !field.cell(image(size, pos)).unwrap().is_mine())
+                .count();
+            // Only an odd count on a board with no cell in the middle to put it should need an unmatched mine
+            assert!(unmatched <= (count % 2), "{:?} on {:?}", symmetry, size);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "graphics")]
+
+use termsweeper::graphics::{self, Graphics, Protocol};
+
+/// What was written, with the escape character shown as `^`.
+fn written(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+    let mut out = Vec::new();
+    write(&mut out).unwrap();
+    String::from_utf8(out).unwrap().replace('\x1b', "^")
+}
+
+#[test]
+fn base64_pads_to_whole_groups() {
+    assert_eq!(graphics::base64(b""), "");
+    assert_eq!(graphics::base64(b"f"), "Zg==");
+    assert_eq!(graphics::base64(b"fo"), "Zm8=");
+    assert_eq!(graphics::base64(b"foo"), "Zm9v");
+    assert_eq!(graphics::base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(graphics::base64(&[0xff, 0xff, 0xfe, 0x00]), "///+AA==");
+}
+
+#[test]
+fn kitty_pictures() {
+    let red = written(|out| graphics::kitty(out, 1, (1, 1), (1, 1), &[0xff, 0, 0]));
+    assert_eq!(red, "^_Ga=d,d=I,i=1,q=2^\\^_Gf=24,s=1,v=1,a=T,i=1,c=1,r=1,C=1,q=2,m=0;/wAA^\\");
+
+    // A picture too big for one escape code is sent in chunks, with all but the last saying there's more
+    let pixels = vec![0; 1025 * 3];
+    let big = written(|out| graphics::kitty(out, 2, (103, 1), (1025, 1), &pixels));
+    let codes = big.split("^\\").filter(|code| !code.is_empty()).collect::<Vec<_>>();
+    assert_eq!(codes.len(), 3);
+    assert_eq!(codes[0], "^_Ga=d,d=I,i=2,q=2");
+    let (first, data) = codes[1].split_once(';').unwrap();
+    assert_eq!(first, "^_Gf=24,s=1025,v=1,a=T,i=2,c=103,r=1,C=1,q=2,m=1");
+    assert_eq!(data, "A".repeat(4096));
+    assert_eq!(codes[2], "^_Gm=0;AAAA");
+
+    assert_eq!(written(|out| Protocol::Kitty.clear(out)), "^_Ga=d,d=A,q=2^\\");
+}
+
+#[test]
+fn sixel_pictures() {
+    // Red in the top left of a white square: red first in the palette, as it's seen first, then white in the other
+    // three pixels of the one band
+    let pixels = [[0xff, 0, 0], [0xff; 3], [0xff; 3], [0xff; 3]].concat();
+    let square = written(|out| graphics::sixel(out, (2, 2), &pixels));
+    assert_eq!(square, "^Pq\"1;1;2;2#0;2;100;0;0#1;2;100;100;100#0@?$#1AB$-^\\");
+
+    // Runs of more than three of the same sixel are sent as one, and colors are in percent
+    let row = written(|out| graphics::sixel(out, (5, 1), &[0x80; 15]));
+    assert_eq!(row, "^Pq\"1;1;5;1#0;2;50;50;50#0!5@$-^\\");
+    let row = written(|out| graphics::sixel(out, (3, 1), &[0; 9]));
+    assert_eq!(row, "^Pq\"1;1;3;1#0;2;0;0;0#0@@@$-^\\");
+
+    // Seven rows take two bands, the second with only its top row
+    let column = written(|out| graphics::sixel(out, (1, 7), &[0; 21]));
+    assert_eq!(column, "^Pq\"1;1;1;7#0;2;0;0;0#0~$-#0@$-^\\");
+
+    // Sixel pictures are taken down by drawing over them
+    assert_eq!(written(|out| Protocol::Sixel.clear(out)), "");
+}
+
+#[test]
+fn protocol_is_picked_from_the_setting_and_terminal() {
+    // Everything that reads the environment is in this one test, so the others can't see it change
+    for name in ["TMUX", "KITTY_WINDOW_ID", "TERM_PROGRAM"] {
+        std::env::remove_var(name);
+    }
+    std::env::set_var("TERM", "xterm-256color");
+    assert_eq!(Protocol::pick(Graphics::Off), None);
+    assert_eq!(Protocol::pick(Graphics::Kitty), Some(Protocol::Kitty));
+    assert_eq!(Protocol::pick(Graphics::Auto), None);
+
+    std::env::set_var("TERM", "xterm-kitty");
+    assert_eq!(Protocol::pick(Graphics::Auto), Some(Protocol::Kitty));
+    std::env::set_var("TERM", "xterm-256color");
+    std::env::set_var("TERM_PROGRAM", "WezTerm");
+    assert_eq!(Protocol::pick(Graphics::Auto), Some(Protocol::Kitty));
+    // Not through a multiplexer, unless asked for by name
+    std::env::set_var("TMUX", "/tmp/tmux-1000/default,1,0");
+    assert_eq!(Protocol::pick(Graphics::Auto), None);
+    assert_eq!(Protocol::pick(Graphics::Kitty), Some(Protocol::Kitty));
+    std::env::remove_var("TMUX");
+    std::env::remove_var("TERM_PROGRAM");
+
+    // Sixel pictures also need the size of a cell, which only a terminal can say, so a foot terminal gets them just
+    // when asking for them by name would
+    std::env::set_var("TERM", "foot");
+    assert_eq!(Protocol::pick(Graphics::Auto), Protocol::pick(Graphics::Sixel));
+    assert!(matches!(Protocol::pick(Graphics::Sixel), None | Some(Protocol::Sixel)));
+}
@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// The messages in a catalog, by ID, with the names of the arguments each one takes.
+fn catalog(lang: &str) -> BTreeMap<String, Vec<String>> {
+    let path = format!("{}/locales/{}.ftl", env!("CARGO_MANIFEST_DIR"), lang);
+    std::fs::read_to_string(path).unwrap()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (id, text) = line.split_once('=').unwrap();
+            let mut args = text.split("{ $").skip(1).map(|arg| arg.split(' ').next().unwrap().to_string()).collect::<Vec<_>>();
+            args.sort();
+            (id.trim().to_string(), args)
+        })
+        .collect()
+}
+
+#[test]
+fn translations_have_every_message_with_the_same_arguments() {
+    let english = catalog("en");
+    for lang in ["de", "es"] {
+        assert_eq!(catalog(lang), english, "the {} catalog doesn't match the English one", lang);
+    }
+}
+
+#[test]
+fn lang_flag_translates_the_panels() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-locales-{}", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .args(["stats", "--lang", "de"])
+        .env("XDG_DATA_HOME", &dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let panel = String::from_utf8(output.stdout).unwrap();
+    assert!(panel.contains("Anfänger"), "{}", panel);
+    assert!(panel.contains("Gespielt"), "{}", panel);
+}
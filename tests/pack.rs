@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A pack directory of its own for each test, with the given manifest and a board file that's in it.
+fn pack(name: &str, manifest: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-pack-{}", std::process::id())).join(name);
+    std::fs::create_dir_all(dir.join("pack")).unwrap();
+    std::fs::write(dir.join("pack/pack.toml"), manifest).unwrap();
+    std::fs::write(dir.join("pack/first.txt"), ".*\n..\n").unwrap();
+    // Next to the pack, not in it
+    std::fs::write(dir.join("secret.txt"), ".*\n..\n").unwrap();
+    dir
+}
+
+/// Try to load the pack in `dir`. Returns what was written to stderr, having checked that it failed.
+fn refuse(dir: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .arg("pack")
+        .arg(dir.join("pack"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn refuses_puzzles_up_and_out_of_the_pack() {
+    let dir = pack("parent", "[[puzzle]]\nfile = \"first.txt\"\n\n[[puzzle]]\nfile = \"../secret.txt\"\n");
+    let stderr = refuse(&dir);
+    assert!(stderr.contains("secret.txt is outside the pack"), "{}", stderr);
+}
+
+#[test]
+fn refuses_puzzles_anywhere_else() {
+    let dir = pack("absolute", "");
+    std::fs::write(dir.join("pack/pack.toml"), format!("[[puzzle]]\nfile = {:?}\n", dir.join("secret.txt"))).unwrap();
+    let stderr = refuse(&dir);
+    assert!(stderr.contains("secret.txt is outside the pack"), "{}", stderr);
+}
+
+#[cfg(unix)]
+#[test]
+fn refuses_links_out_of_the_pack() {
+    let dir = pack("link", "");
+    std::fs::remove_file(dir.join("pack/pack.toml")).unwrap();
+    let _ = std::fs::remove_file(dir.join("pack/second.txt"));
+    std::os::unix::fs::symlink(dir.join("secret.txt"), dir.join("pack/second.txt")).unwrap();
+    let stderr = refuse(&dir);
+    assert!(stderr.contains("secret.txt is outside the pack"), "{}", stderr);
+}
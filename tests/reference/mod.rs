@@ -0,0 +1,245 @@
+//! A reference implementation of the rules, written as plainly as they can be, for checking the engine against. The
+//! board is due to be reworked for speed (a flat grid, bitsets, incremental updates), and playing the same games on
+//! both is what catches a change in how it plays along the way. Shared by the differential test and fuzz target.
+
+use termsweeper::board::Board;
+use termsweeper::engine::{Command, GameEngine, GameEvent};
+use termsweeper::game::{Direction, Field};
+
+/// What the player sees on a cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Shown {
+    Hidden,
+    Flagged,
+    Number(u8),
+    Exploded,
+}
+
+/// A single-player game on a square board, kept as a grid of mines and what's shown on each cell.
+struct Reference {
+    mines: Vec<Vec<bool>>,
+    shown: Vec<Vec<Shown>>,
+    cursor: (usize, usize),
+    ended: bool,
+    won: bool,
+}
+
+impl Reference {
+    /// A game on the same mines as the board.
+    fn new(board: &impl Board) -> Self {
+        let (rows, cols) = board.size();
+        let mines = (0..rows)
+            .map(|row| (0..cols).map(|col| board.is_mine((row, col))).collect())
+            .collect();
+        Self { mines, shown: vec![vec![Shown::Hidden; cols]; rows], cursor: (0, 0), ended: false, won: false }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.mines.len(), self.mines[0].len())
+    }
+
+    /// The cells around one, as (row, col).
+    fn neighbors(&self, (row, col): (usize, usize)) -> Vec<(usize, usize)> {
+        let (rows, cols) = self.size();
+        let mut neighbors = Vec::new();
+        for r in row.saturating_sub(1)..(row + 2).min(rows) {
+            for c in col.saturating_sub(1)..(col + 2).min(cols) {
+                if (r, c) != (row, col) {
+                    neighbors.push((r, c));
+                }
+            }
+        }
+        neighbors
+    }
+
+    fn count(&self, pos: (usize, usize)) -> u8 {
+        self.neighbors(pos).into_iter().filter(|&(row, col)| self.mines[row][col]).count() as u8
+    }
+
+    /// Returns true if every safe cell has been revealed.
+    fn cleared(&self) -> bool {
+        let mut cells = self.mines.iter().flatten().zip(self.shown.iter().flatten());
+        cells.all(|(&mine, &shown)| mine || matches!(shown, Shown::Number(_)))
+    }
+
+    /// Carry out a command, returning whether it did anything and the events it should have sent.
+    fn apply(&mut self, command: Command) -> (bool, Vec<GameEvent>) {
+        let mut events = Vec::new();
+        let changed = match command {
+            Command::Move(direction) => self.step(direction),
+            Command::Reveal(_) | Command::Flag(_) | Command::Chord(_) if self.ended => false,
+            Command::Reveal(pos) => self.reveal(&[pos], &mut events),
+            Command::Flag((row, col)) => {
+                let flagged = match self.shown[row][col] {
+                    Shown::Hidden => true,
+                    Shown::Flagged => false,
+                    _ => return (false, events),
+                };
+                self.shown[row][col] = if flagged { Shown::Flagged } else { Shown::Hidden };
+                events.push(GameEvent::CellFlagged { pos: (row, col), flagged });
+                true
+            },
+            Command::Chord(pos) => {
+                let Shown::Number(number) = self.shown[pos.0][pos.1] else { return (false, events) };
+                let around = self.neighbors(pos);
+                let flags = around.iter().filter(|&&(row, col)| self.shown[row][col] == Shown::Flagged).count();
+                let targets = around.into_iter().filter(|&(row, col)| self.shown[row][col] == Shown::Hidden);
+                let targets = targets.collect::<Vec<_>>();
+                flags == number as usize && self.reveal(&targets, &mut events)
+            },
+            // Commands can be added to the engine without breaking other crates, so the reference only finds out here
+            _ => panic!("the reference doesn't know how to play {:?}: teach it, then the strategies", command),
+        };
+        (changed, events)
+    }
+
+    fn step(&mut self, direction: Direction) -> bool {
+        let (dx, dy) = match direction {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+            // The reference's boards are flat, with only the one layer
+            Direction::PrevLayer | Direction::NextLayer => return false,
+        };
+        let (rows, cols) = self.size();
+        let (row, col) = (self.cursor.0 as isize + dy, self.cursor.1 as isize + dx);
+        if row < 0 || col < 0 || row >= rows as isize || col >= cols as isize {
+            return false;
+        }
+        self.cursor = (row as usize, col as usize);
+        true
+    }
+
+    /// Reveal the cells as one move, flood filling from any with no mines around them.
+    fn reveal(&mut self, targets: &[(usize, usize)], events: &mut Vec<GameEvent>) -> bool {
+        let (mut safe, mut exploded) = (0, false);
+        for &pos in targets {
+            if self.shown[pos.0][pos.1] != Shown::Hidden {
+                continue;
+            }
+            if self.mines[pos.0][pos.1] {
+                self.shown[pos.0][pos.1] = Shown::Exploded;
+                exploded = true;
+                events.push(GameEvent::MineExploded { pos });
+                continue;
+            }
+            let mut check = vec![pos];
+            while let Some((row, col)) = check.pop() {
+                if self.shown[row][col] != Shown::Hidden {
+                    continue;
+                }
+                let number = self.count((row, col));
+                self.shown[row][col] = Shown::Number(number);
+                safe += 1;
+                events.push(GameEvent::CellRevealed { pos: (row, col), number });
+                if number == 0 {
+                    check.extend(self.neighbors((row, col)));
+                }
+            }
+        }
+        if safe == 0 && !exploded {
+            return false;
+        }
+        self.won = !exploded && self.cleared();
+        self.ended = exploded || self.won;
+        if self.won {
+            events.push(GameEvent::GameWon);
+        } else if self.ended {
+            events.push(GameEvent::GameLost);
+        }
+        true
+    }
+}
+
+/// What's shown on a cell of the engine's board, or any other.
+fn shown(board: &impl Board, pos: (usize, usize)) -> Shown {
+    match (board.is_flagged(pos), board.is_exploded(pos), board.shown_number(pos)) {
+        (true, _, _) => Shown::Flagged,
+        (_, true, _) => Shown::Exploded,
+        (_, _, Some(number)) => Shown::Number(number),
+        _ => Shown::Hidden,
+    }
+}
+
+/// Events in an order that doesn't depend on which way a flood fill or chord went round the cells.
+fn sorted(events: impl IntoIterator<Item = GameEvent>) -> Vec<String> {
+    let mut events = events.into_iter().map(|event| format!("{:?}", event)).collect::<Vec<_>>();
+    events.sort();
+    events
+}
+
+/// Play the commands on the field with both the engine and the reference, checking after each one that everything a
+/// player could see is the same: whether it did anything, the events it sent, every cell, the cursor and how the game
+/// stands. Panics on the first difference.
+pub fn play(field: Field, commands: impl IntoIterator<Item = Command>) {
+    let mut reference = Reference::new(&field);
+    let mut engine = GameEngine::new(field);
+    let events = engine.subscribe();
+    let (rows, cols) = reference.size();
+    for command in commands {
+        let (changed, expected) = reference.apply(command);
+        assert_eq!(engine.apply(command), changed, "{:?}", command);
+        assert_eq!(sorted(events.try_iter()), sorted(expected), "{:?}", command);
+
+        let field = engine.field();
+        for row in 0..rows {
+            for col in 0..cols {
+                assert_eq!(shown(field, (row, col)), reference.shown[row][col], "{:?} at {:?}", command, (row, col));
+                assert_eq!(field.cell((row, col)).unwrap().neighbor_count(), reference.count((row, col)));
+            }
+        }
+        assert_eq!(engine.cursor(), reference.cursor, "{:?}", command);
+        assert_eq!(engine.ended(), reference.ended, "{:?}", command);
+        assert_eq!(engine.won(), reference.won, "{:?}", command);
+        assert_eq!(field.is_cleared(), reference.cleared(), "{:?}", command);
+    }
+}
+
+/// Play the reveals and flags among the commands straight on the board, without an engine, checking after each one
+/// that it did the same as the reference and that every cell looks the same. Boards don't know when a game is over, so
+/// this stops once the reference's is. Panics on the first difference.
+pub fn play_board(board: &mut impl Board, commands: impl IntoIterator<Item = Command>) {
+    let mut reference = Reference::new(board);
+    let (rows, cols) = reference.size();
+    for command in commands {
+        if reference.ended {
+            break;
+        }
+        match command {
+            Command::Reveal(pos) => {
+                let (changed, events) = reference.apply(command);
+                let reveal = board.clear_cell(pos);
+                assert_eq!(reveal.is_some(), changed, "{:?}", command);
+                let mut cells = reveal.map(|reveal| reveal.cells).unwrap_or_default();
+                cells.sort();
+                let mut expected = events.into_iter()
+                    .filter_map(|event| match event {
+                        GameEvent::CellRevealed { pos, .. } | GameEvent::MineExploded { pos } => Some(pos),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                expected.sort();
+                assert_eq!(cells, expected, "{:?}", command);
+            },
+            Command::Flag(pos) => {
+                let (changed, _) = reference.apply(command);
+                assert_eq!(board.toggle_flag(pos), Some(changed), "{:?}", command);
+            },
+            // Chords and the cursor are the engine's, not the board's
+            Command::Chord(_) | Command::Move(_) => continue,
+            _ => panic!("the reference doesn't know how to play {:?} on a board", command),
+        }
+        for row in 0..rows {
+            for col in 0..cols {
+                assert_eq!(shown(board, (row, col)), reference.shown[row][col], "{:?} at {:?}", command, (row, col));
+                assert_eq!(board.neighbor_count((row, col)), reference.count((row, col)));
+            }
+        }
+        assert_eq!(board.is_cleared(), reference.cleared(), "{:?}", command);
+    }
+}
@@ -0,0 +1,683 @@
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::config::Config;
+use termsweeper::game::{Field, Game};
+use termsweeper::topology::Topology;
+
+const TERMINAL_SIZE: (u16, u16) = (40, 30);
+/// Tall enough for the whole end panel, below the controls.
+const TALL_TERMINAL: (u16, u16) = (40, 40);
+
+/// Collects everything the game writes, so it can be played back onto a virtual screen.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A headless game on a seeded Beginner-sized board, and a handle on what it has written so far.
+fn game() -> (Game, Output) {
+    let field = Field::with_rng((9, 9), 10, &mut StdRng::seed_from_u64(896)).unwrap();
+    let output = Output::default();
+    let game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    (game, output)
+}
+
+fn press(game: &mut Game, code: KeyCode) {
+    game.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE))).unwrap();
+}
+
+/// Type each character of the line, then enter.
+fn type_line(game: &mut Game, line: &str) {
+    line.chars().for_each(|c| press(game, KeyCode::Char(c)));
+    press(game, KeyCode::Enter);
+}
+
+/// Render a frame and play everything written so far onto a virtual terminal of the given size.
+fn terminal(game: &mut Game, output: &Output, size: (u16, u16)) -> vt100::Parser {
+    game.render().unwrap();
+    let mut parser = vt100::Parser::new(size.1, size.0, 0);
+    parser.process(&output.0.borrow());
+    parser
+}
+
+/// Render a frame and play everything written so far onto a virtual terminal. Returns the screen contents, with the
+/// background colors of any highlighted cells listed below it.
+fn screen(game: &mut Game, output: &Output) -> String {
+    sized_screen(game, output, TERMINAL_SIZE)
+}
+
+/// Like [`screen`], for a game on a terminal of the given size.
+fn sized_screen(game: &mut Game, output: &Output, size: (u16, u16)) -> String {
+    let parser = terminal(game, output, size);
+    let screen = parser.screen();
+
+    let mut snapshot = screen.contents();
+    for row in 0..size.1 {
+        for col in 0..size.0 {
+            let cell = screen.cell(row, col).unwrap();
+            if cell.bgcolor() != vt100::Color::Default {
+                write!(snapshot, "\nhighlight at ({}, {}): {:?}", col, row, cell.bgcolor()).unwrap();
+            }
+        }
+    }
+    snapshot
+}
+
+#[test]
+fn frames_are_written_at_once() {
+    /// Counts the writes made to it.
+    #[derive(Clone, Default)]
+    struct Writes(Rc<RefCell<usize>>);
+
+    impl Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            *self.0.borrow_mut() += 1;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let field = Field::with_rng((9, 9), 10, &mut StdRng::seed_from_u64(896)).unwrap();
+    let writes = Writes::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, writes.clone());
+    game.render().unwrap();
+    press(&mut game, KeyCode::Char(' '));
+    game.render().unwrap();
+    assert_eq!(*writes.0.borrow(), 2);
+}
+
+#[test]
+fn fresh_board() {
+    let (mut game, output) = game();
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn opened_board() {
+    let (mut game, output) = game();
+    // The top left corner of this board is an opening, so this floods the left side
+    for code in [KeyCode::Char(' '), KeyCode::Right] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn flagged_cell() {
+    let (mut game, output) = game();
+    for code in [KeyCode::Down, KeyCode::Char('f'), KeyCode::Right] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn notes() {
+    let (mut game, output) = game();
+    for code in [KeyCode::Char('n'), KeyCode::Right, KeyCode::Char('n'), KeyCode::Char('n'), KeyCode::Down] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn ping_overlay() {
+    let (mut game, output) = game();
+    for code in [KeyCode::Right, KeyCode::Down, KeyCode::Char('p'), KeyCode::Down] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn settings_panel() {
+    let (mut game, output) = game();
+    // Draw a frame first, so the snapshot also covers redrawing over it
+    screen(&mut game, &output);
+    for code in [KeyCode::Esc, KeyCode::Down] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn help_screen() {
+    let (mut game, output) = game();
+    press(&mut game, KeyCode::F(1));
+    // Too short a terminal for all of it, so it scrolls
+    game.handle_event(Event::Resize(TERMINAL_SIZE.0, 12)).unwrap();
+    insta::assert_snapshot!(screen(&mut game, &output));
+    press(&mut game, KeyCode::End);
+    let end = screen(&mut game, &output);
+    assert!(end.contains("help   f1"));
+    press(&mut game, KeyCode::PageDown);
+    assert_eq!(screen(&mut game, &output), end);
+    press(&mut game, KeyCode::Esc);
+    assert!(screen(&mut game, &output).starts_with("╭─────────╮"));
+}
+
+#[test]
+fn prompt_starts_over() {
+    let (mut game, output) = game();
+    let fresh = screen(&mut game, &output);
+    press(&mut game, KeyCode::Char(' '));
+    // The same seed as the board was laid out from gives the same board back
+    type_line(&mut game, ":seed 896");
+    assert_eq!(screen(&mut game, &output), fresh);
+    type_line(&mut game, ":new 5x5 2");
+    assert!(screen(&mut game, &output).starts_with("╭─────╮"));
+    // Sizes past what the custom game menu allows are brought back into range
+    type_line(&mut game, ":new 1x3 1");
+    let shown = screen(&mut game, &output);
+    assert!(shown.starts_with("╭───╮\n│◎██│\n│███│\n╰───╯"), "{}", shown);
+    assert!(shown.contains("Made the board 2x3"));
+    // On a terminal big enough to show the biggest board
+    let big = (120, 60);
+    let field = Field::from_mines((3, 3), &[(0, 0)]).unwrap();
+    let big_output = Output::default();
+    let mut big_game = Game::headless(field, Config::default(), big, big_output.clone());
+    type_line(&mut big_game, ":new 100000x100000 1");
+    let shown = terminal(&mut big_game, &big_output, big).screen().contents();
+    let lines = shown.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], format!("╭{}╮", "─".repeat(99)));
+    assert_eq!(lines[51], format!("╰{}╯", "─".repeat(99)));
+    // Boards are only saved in the current directory
+    type_line(&mut game, ":save ../board");
+    assert!(screen(&mut game, &output).contains("../board isn't a file name"));
+}
+
+#[test]
+fn settings_dim_the_game_behind_them() {
+    let (mut game, output) = game();
+    press(&mut game, KeyCode::Esc);
+    let parser = terminal(&mut game, &output, TERMINAL_SIZE);
+    let screen = parser.screen();
+    // The board is in the top left corner, and the settings in the middle
+    assert!(screen.cell(1, 1).unwrap().dim());
+    assert!(!screen.cell(TERMINAL_SIZE.1 / 2, TERMINAL_SIZE.0 / 2).unwrap().dim());
+}
+
+#[test]
+fn narrow_terminals_collapse_the_panels() {
+    let (mut game, output) = game();
+    press(&mut game, KeyCode::Char(' '));
+    game.handle_event(Event::Resize(27, TERMINAL_SIZE.1)).unwrap();
+    insta::assert_snapshot!("collapsed_to_status_bar", screen(&mut game, &output));
+    game.handle_event(Event::Resize(20, TERMINAL_SIZE.1)).unwrap();
+    insta::assert_snapshot!("collapsed_to_board", screen(&mut game, &output));
+    // Everything comes back once there's room for it
+    game.handle_event(Event::Resize(TERMINAL_SIZE.0, TERMINAL_SIZE.1)).unwrap();
+    assert!(screen(&mut game, &output).contains("Controls"));
+}
+
+#[test]
+fn paused() {
+    let (mut game, output) = game();
+    press(&mut game, KeyCode::Char(' '));
+    game.handle_event(Event::FocusLost).unwrap();
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn boss_key() {
+    let (mut game, output) = game();
+    let board = screen(&mut game, &output);
+    press(&mut game, KeyCode::F(12));
+    // Every line is from the log, starting with the time it was written
+    let boss = screen(&mut game, &output);
+    assert!(boss.lines().all(|line| line.get(2..3) == Some(":")));
+    // Nothing else gets through until the boss key brings the game back
+    press(&mut game, KeyCode::Char(' '));
+    press(&mut game, KeyCode::F(12));
+    assert_eq!(screen(&mut game, &output), board);
+}
+
+#[test]
+fn multiple_boards() {
+    let (game, output) = game();
+    let field = Field::with_rng((9, 9), 10, &mut StdRng::seed_from_u64(4)).unwrap();
+    let mut game = game.with_boards([field]);
+    // Open up the top left corner of the second board, leaving the first untouched
+    for code in [KeyCode::Tab, KeyCode::Char(' '), KeyCode::Right] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn layered_board() {
+    let mines = [(0, 3), (2, 7), (3, 1), (4, 8)];
+    let field = Field::with_topology(Topology::Layered { layers: 2 }, (5, 10), &mines).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    // Open up the top layer, then move the cursor down to the layer below it
+    for code in [KeyCode::Char(' '), KeyCode::Char('>')] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn triangular_board() {
+    let mines = [(0, 5), (2, 1), (3, 6), (4, 3)];
+    let field = Field::with_topology(Topology::Triangular, (5, 8), &mines).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    for code in [KeyCode::Char(' '), KeyCode::Down] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn json_events_stream_each_event() {
+    let field = Field::from_mines((3, 3), &[(0, 1)]).unwrap();
+    let events = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, io::sink()).with_json_events(events.clone());
+    for code in [KeyCode::Char(' '), KeyCode::Down, KeyCode::Char('f')] {
+        press(&mut game, code);
+    }
+
+    let stream = String::from_utf8(events.0.borrow().clone()).unwrap();
+    let lines = stream.lines().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<serde_json::Value>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event"], "cell-revealed");
+    assert_eq!(lines[0]["pos"], serde_json::json!([0, 0]));
+    assert_eq!(lines[0]["number"], 1);
+    assert_eq!(lines[1]["event"], "cell-flagged");
+    assert_eq!(lines[1]["pos"], serde_json::json!([1, 0]));
+    assert_eq!(lines[1]["board"], 0);
+}
+
+#[test]
+fn assists_flag_and_reveal_forced_cells() {
+    let field = Field::from_mines((3, 3), &[(2, 1), (2, 2)]).unwrap();
+    let config = Config::parse("[assist]\nflag = true\nreveal = true\n").unwrap();
+    let events = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, io::sink()).with_json_events(events.clone());
+    // Opening the top left corner shows the bottom row's mines, and that the cell beside them is safe
+    press(&mut game, KeyCode::Char(' '));
+
+    let stream = String::from_utf8(events.0.borrow().clone()).unwrap();
+    let lines = stream.lines().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<serde_json::Value>>();
+    let flagged = lines.iter().filter(|line| line["event"] == "cell-flagged").map(|line| &line["pos"]);
+    assert_eq!(flagged.collect::<Vec<_>>(), [&serde_json::json!([2, 1]), &serde_json::json!([2, 2])]);
+    assert_eq!(lines.last().unwrap()["event"], "game-won");
+}
+
+#[test]
+fn mistake_warning() {
+    let field = Field::from_mines((3, 3), &[(2, 1), (2, 2)]).unwrap();
+    let config = Config::parse("warn_mistakes = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    // Opening the top left corner shows that the bottom row's mines are all but certain
+    for code in [KeyCode::Char(' '), KeyCode::Down, KeyCode::Down, KeyCode::Right, KeyCode::Char(' ')] {
+        press(&mut game, code);
+    }
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn high_counts_are_colored() {
+    // Every cell but one is a mine, so it counts all 17 of its neighbors across both layers
+    let mines = (0..3).flat_map(|row| (0..6).map(move |col| (row, col)))
+        .filter(|&pos| pos != (1, 1))
+        .collect::<Vec<_>>();
+    let field = Field::with_topology(Topology::Layered { layers: 2 }, (3, 6), &mines).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    // Revealing it wins, so the end panel has to be closed before the cursor can be moved off it
+    for code in [KeyCode::Down, KeyCode::Right, KeyCode::Char(' '), KeyCode::Esc, KeyCode::Up] {
+        press(&mut game, code);
+    }
+
+    let parser = terminal(&mut game, &output, TERMINAL_SIZE);
+    let cell = parser.screen().cell(2, 2).unwrap();
+    assert_eq!(cell.contents(), "H");
+    assert_ne!(cell.fgcolor(), vt100::Color::Default);
+}
+
+#[test]
+fn end_screen() {
+    let field = Field::from_mines((3, 3), &[(0, 1), (2, 2)]).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TALL_TERMINAL, output.clone());
+    // Flag one mine and one safe cell, then step on the other mine
+    for code in [KeyCode::Right, KeyCode::Char('f'), KeyCode::Down, KeyCode::Char('f'), KeyCode::Down, KeyCode::Right] {
+        press(&mut game, code);
+    }
+    press(&mut game, KeyCode::Char(' '));
+    insta::assert_snapshot!(sized_screen(&mut game, &output, TALL_TERMINAL));
+}
+
+#[test]
+fn end_panel_actions() {
+    let field = Field::from_mines((3, 3), &[(0, 0)]).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    press(&mut game, KeyCode::Char(' '));
+    assert!(screen(&mut game, &output).contains("Game Over"));
+    // Retrying starts over on the same board, so the mine is still in the corner
+    press(&mut game, KeyCode::Enter);
+    assert!(!screen(&mut game, &output).contains("Game Over"));
+    press(&mut game, KeyCode::Char(' '));
+    let screen = screen(&mut game, &output);
+    assert!(screen.contains("Game Over"), "{}", screen);
+    // Going back is the last action, and ends the game
+    for _ in 0..4 {
+        press(&mut game, KeyCode::Down);
+    }
+    assert!(!game.handle_event(Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))).unwrap());
+}
+
+#[test]
+fn starting_over_keeps_the_kind_of_game() {
+    let layered = || Field::with_topology(Topology::Layered { layers: 2 }, (3, 6), &[(0, 0)]).unwrap();
+    let output = Output::default();
+    let game = Game::headless(layered(), Config::default(), TERMINAL_SIZE, output.clone());
+    let mut game = game.with_boards([layered()]);
+    let fresh = screen(&mut game, &output);
+    // Retrying puts both layered boards back as they were
+    press(&mut game, KeyCode::Char(' '));
+    press(&mut game, KeyCode::Enter);
+    assert_eq!(screen(&mut game, &output), fresh);
+    // A new board is as many new boards again, and the same shape
+    press(&mut game, KeyCode::Char(' '));
+    press(&mut game, KeyCode::Down);
+    press(&mut game, KeyCode::Enter);
+    let new = screen(&mut game, &output);
+    assert!(!new.contains("Game Over"));
+    assert_eq!(new.lines().next(), fresh.lines().next());
+}
+
+/// The lines of the end screen of a lost game on a terminal of the given size, with the given config.
+fn lost_screen(config: Config, size: (u16, u16)) -> Vec<String> {
+    let field = Field::from_mines((3, 3), &[(0, 0)]).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, size, output.clone());
+    press(&mut game, KeyCode::Char(' '));
+    terminal(&mut game, &output, size).screen().contents().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn titles_fall_back_to_plain_text() {
+    // Wide enough for the art, which is several lines tall
+    let art = lost_screen(Config::default(), (100, 40));
+    assert!(!art.iter().any(|line| line.contains("Game Over")));
+    assert!(art.iter().filter(|line| line.contains('|')).count() >= 3);
+
+    let plain = Config::parse("title_font = \"plain\"\n").unwrap();
+    assert!(lost_screen(plain, (100, 40)).iter().any(|line| line.trim() == "Game Over"));
+    let missing = Config::parse("title_font = \"/nonexistent/font.flf\"\n").unwrap();
+    assert!(lost_screen(missing, (100, 40)).iter().any(|line| line.trim() == "Game Over"));
+}
+
+#[test]
+fn custom_banners() {
+    // A FIGlet font two lines tall, drawing each character in brackets over a line
+    let mut font = String::from("flf2a$ 2 1 10 0 0\n");
+    let codes = (32..127).chain([196, 214, 220, 228, 246, 252, 223]);
+    for c in codes.map(|code| char::from_u32(code).unwrap()) {
+        font.push_str(&format!("[{}]@\n---@@\n", c));
+    }
+    let path = std::env::temp_dir().join(format!("termsweeper-banner-{}.flf", std::process::id()));
+    std::fs::write(&path, font).unwrap();
+
+    let config = format!("[banners.lost]\ntext = \"BOOM\"\nfont = {:?}\n", path.to_str().unwrap());
+    let screen = lost_screen(Config::parse(&config).unwrap(), (100, 40));
+    std::fs::remove_file(&path).unwrap();
+    assert!(screen.iter().any(|line| line == "[B][O][O][M]"), "{:?}", screen);
+    assert!(screen.iter().any(|line| line == "------------"));
+    // The text can be changed without changing the font
+    let config = Config::parse("title_font = \"plain\"\n[banners.lost]\ntext = \"BOOM\"\n").unwrap();
+    let screen = lost_screen(config, (100, 40));
+    assert!(screen.iter().any(|line| line.trim() == "BOOM"));
+}
+
+#[test]
+fn hex_note_color() {
+    let field = Field::from_mines((3, 3), &[(2, 2)]).unwrap();
+    let config = Config::parse("note_color = \"#ff8000\"\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    for code in [KeyCode::Char('n'), KeyCode::Right] {
+        press(&mut game, code);
+    }
+
+    let parser = terminal(&mut game, &output, TERMINAL_SIZE);
+    assert_eq!(parser.screen().cell(1, 1).unwrap().fgcolor(), vt100::Color::Rgb(255, 128, 0));
+}
+
+#[test]
+fn highlights_the_last_move() {
+    let field = Field::from_mines((3, 3), &[(0, 2), (2, 2)]).unwrap();
+    let config = Config::parse("highlight_changes = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    let highlighted = |game: &mut Game| screen(game, &output).matches("highlight at").count();
+    // Opening the corner floods the left two columns, though the cursor is drawn over the highlight on one of them
+    press(&mut game, KeyCode::Char(' '));
+    assert_eq!(highlighted(&mut game), 5);
+    // Flagging a mine replaces their highlight with its own
+    for code in [KeyCode::Down, KeyCode::Down, KeyCode::Right, KeyCode::Right, KeyCode::Char('f'), KeyCode::Up] {
+        press(&mut game, code);
+    }
+    assert_eq!(highlighted(&mut game), 1);
+}
+
+#[test]
+fn reduced_motion_keeps_highlights_still() {
+    let field = Field::from_mines((3, 3), &[(0, 2), (2, 2)]).unwrap();
+    let config = Config::parse("highlight_changes = true\nreduced_motion = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    press(&mut game, KeyCode::Char(' '));
+    // Long enough for the highlight to have faded a step, if it did
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let screen = screen(&mut game, &output);
+    assert_eq!(screen.matches("highlight at").count(), 5);
+    assert_eq!(screen.matches("Rgb(90, 90, 150)").count(), 5);
+}
+
+#[test]
+fn low_power_only_ticks_when_the_screen_changes() {
+    let headless = |config: &str| {
+        let field = Field::from_mines((3, 3), &[(0, 0)]).unwrap();
+        Game::headless(field, Config::parse(config).unwrap(), TERMINAL_SIZE, io::sink())
+    };
+    let (tick, slow_tick) = (Duration::from_millis(100), Duration::from_millis(500));
+    assert_eq!(headless("").tick_rate(), Some(tick));
+    assert_eq!(headless("slow_link = true\n").tick_rate(), Some(slow_tick));
+
+    // Nothing changes by itself before the game starts, and after that only the clock, once a second
+    let mut game = headless("low_power = true\n");
+    assert_eq!(game.tick_rate(), None);
+    press(&mut game, KeyCode::Down);
+    press(&mut game, KeyCode::Char(' '));
+    let rate = game.tick_rate().unwrap();
+    assert!(rate > Duration::ZERO && rate <= Duration::from_secs(1), "{:?}", rate);
+    // Nor once it's over
+    press(&mut game, KeyCode::Up);
+    press(&mut game, KeyCode::Char(' '));
+    assert_eq!(game.tick_rate(), None);
+
+    // Highlights fade a tick at a time, or go all at once with reduced motion
+    let mut game = headless("low_power = true\nhighlight_changes = true\n");
+    press(&mut game, KeyCode::Down);
+    press(&mut game, KeyCode::Char(' '));
+    assert_eq!(game.tick_rate(), Some(tick));
+    let mut game = headless("low_power = true\nhighlight_changes = true\nreduced_motion = true\n");
+    press(&mut game, KeyCode::Down);
+    press(&mut game, KeyCode::Char(' '));
+    let rate = game.tick_rate().unwrap();
+    assert!(rate > tick && rate <= Duration::from_secs(1), "{:?}", rate);
+
+    // A toast goes by itself too
+    let mut game = headless("low_power = true\n");
+    type_line(&mut game, ":nonsense");
+    let rate = game.tick_rate().unwrap();
+    assert!(rate > slow_tick && rate <= Duration::from_secs(5), "{:?}", rate);
+}
+
+#[test]
+fn chord_preview() {
+    let field = Field::from_mines((3, 3), &[(0, 2), (2, 2)]).unwrap();
+    let config = Config::parse("chord_preview = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    let highlights = |game: &mut Game| screen(game, &output).lines()
+        .filter(|line| line.starts_with("highlight at"))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    // The 2 in the middle has no flags around it yet, so all three of its hidden neighbors are shown as not ready
+    for code in [KeyCode::Char(' '), KeyCode::Down, KeyCode::Right] {
+        press(&mut game, code);
+    }
+    assert_eq!(highlights(&mut game), [
+        "highlight at (3, 1): Idx(3)",
+        "highlight at (3, 2): Idx(3)",
+        "highlight at (3, 3): Idx(3)",
+    ]);
+    // With both mines flagged, it's ready to chord the one safe cell left
+    let flag_both = [KeyCode::Up, KeyCode::Right, KeyCode::Char('f'), KeyCode::Down, KeyCode::Down, KeyCode::Char('f')];
+    for code in flag_both.into_iter().chain([KeyCode::Left, KeyCode::Up]) {
+        press(&mut game, code);
+    }
+    assert_eq!(highlights(&mut game), ["highlight at (3, 2): Idx(2)"]);
+}
+
+#[test]
+fn presentation_mode() {
+    let field = Field::from_mines((3, 3), &[(2, 2)]).unwrap();
+    let config = Config::parse("presentation = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    press(&mut game, KeyCode::Down);
+    let screen = screen(&mut game, &output);
+    // The cursor's row and column, with the cursor itself in its own colors
+    let highlights = screen.lines().filter(|line| line.starts_with("highlight at")).collect::<Vec<_>>();
+    assert_eq!(highlights.len(), 5, "{}", screen);
+    assert!(highlights.contains(&"highlight at (1, 2): Idx(11)"), "{}", screen);
+    assert!(!screen.contains("Controls"));
+    // Ctrl+P switches back
+    game.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL))).unwrap();
+    let screen = self::screen(&mut game, &output);
+    assert!(screen.contains("Controls"));
+    assert!(!screen.contains("highlight at"));
+}
+
+#[test]
+fn rulers() {
+    let field = Field::from_mines((3, 3), &[(2, 1)]).unwrap();
+    let config = Config::parse("rulers = true\nhighlight_changes = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TERMINAL_SIZE, output.clone());
+    // Cells can be named as on the rulers, so this flags the mine in the bottom middle, highlighted past the rulers
+    press(&mut game, KeyCode::Char(':'));
+    type_line(&mut game, "goto B3");
+    press(&mut game, KeyCode::Char('f'));
+    press(&mut game, KeyCode::Up);
+    insta::assert_snapshot!(screen(&mut game, &output));
+}
+
+#[test]
+fn rulers_name_columns_past_z() {
+    let field = Field::from_mines((2, 30), &[(1, 27)]).unwrap();
+    let config = Config::parse("rulers = true\n").unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, config, TALL_TERMINAL, output.clone());
+    // Z is followed by AA, with the names typed in either case
+    press(&mut game, KeyCode::Char(':'));
+    type_line(&mut game, "goto ab2");
+    press(&mut game, KeyCode::Char('f'));
+    press(&mut game, KeyCode::Up);
+    let shown = sized_screen(&mut game, &output, TALL_TERMINAL);
+    let lines = shown.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], format!("{}AAAA", " ".repeat(29)));
+    assert_eq!(lines[1], "   ABCDEFGHIJKLMNOPQRSTUVWXYZABCD");
+    assert_eq!(lines[4], format!("2 │{}⚑██│", "█".repeat(27)));
+
+    // Names that aren't a cell on the board are refused, rather than taken as some other cell
+    press(&mut game, KeyCode::Char(':'));
+    type_line(&mut game, "goto AE1");
+    assert!(sized_screen(&mut game, &output, TALL_TERMINAL).contains("That cell is off the board"));
+    for name in ["B", "A0", "3B"] {
+        press(&mut game, KeyCode::Char(':'));
+        type_line(&mut game, &format!("goto {}", name));
+        let shown = sized_screen(&mut game, &output, TALL_TERMINAL);
+        assert!(shown.contains(&format!("{} isn't a cell like C7", name)), "{}", shown);
+    }
+    // And the cursor stays where it was
+    let shown = sized_screen(&mut game, &output, TALL_TERMINAL);
+    assert_eq!(shown.lines().nth(3), Some(format!("1 │{}◎██│", "█".repeat(27)).as_str()));
+}
+
+#[test]
+fn noisy_numbers() {
+    let field = Field::from_mines((3, 3), &[(2, 2)]).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TALL_TERMINAL, output.clone()).with_noisy_numbers(1, 3);
+    // Each cell takes three columns, with a space after it, the ones next to the mine showing the range 1 to 2
+    press(&mut game, KeyCode::Char(' '));
+    insta::assert_snapshot!(sized_screen(&mut game, &output, TALL_TERMINAL));
+}
+
+#[test]
+fn separate_taps_move_one_cell_each() {
+    let field = Field::from_mines((1, 9), &[(0, 8)]).unwrap();
+    let output = Output::default();
+    let mut game = Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone());
+    // Without key releases, taps come in like a held key, but too far apart to be its autorepeat
+    for _ in 0..4 {
+        press(&mut game, KeyCode::Right);
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    // The cursor is drawn over the flag, so it's moved off again
+    press(&mut game, KeyCode::Char('f'));
+    press(&mut game, KeyCode::Left);
+    assert!(screen(&mut game, &output).starts_with("╭─────────╮\n│███◎⚑████│"));
+}
+
+#[test]
+fn wide_characters_keep_boxes_lined_up() {
+    let (mut game, output) = game();
+    press(&mut game, KeyCode::Char(':'));
+    "save 地雷原の盤面をここに保存する".chars().for_each(|c| press(&mut game, KeyCode::Char(c)));
+    let parser = terminal(&mut game, &output, TERMINAL_SIZE);
+    let screen = parser.screen();
+    // Every character of the text takes two columns, so the terminal's cells show where the box really ends
+    let text_row = (0..TERMINAL_SIZE.1)
+        .find(|&row| screen.contents_between(row, 0, row, TERMINAL_SIZE.0).contains("地雷"))
+        .unwrap();
+    let right_edge = |row: u16, border: &str| (0..TERMINAL_SIZE.0).rev()
+        .find(|&col| screen.cell(row, col).unwrap().contents() == border)
+        .unwrap();
+    let edge = right_edge(text_row, "│");
+    assert_eq!(right_edge(text_row - 1, "╮"), edge);
+    assert_eq!(right_edge(text_row + 1, "╯"), edge);
+    // The box fits the text exactly as wide as it's drawn, two columns a character
+    let left_edge = (0..edge).rev().find(|&col| screen.cell(text_row, col).unwrap().contents() == "│").unwrap();
+    let text = screen.contents_between(text_row, left_edge + 1, text_row, edge);
+    assert_eq!(text, ":save 地雷原の盤面をここに保存する_");
+    assert_eq!(edge - left_edge - 1, 6 + 2 * 14 + 1);
+}
@@ -0,0 +1,90 @@
+use std::process::Command;
+
+/// Turn the given replay into a cast with `termsweeper replay`. Returns whether it worked, and what went to stderr.
+fn export_cast(name: &str, replay: &str) -> (bool, String) {
+    let dir = std::env::temp_dir().join(format!("termsweeper-replay-{}", std::process::id())).join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("game.json"), replay).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .arg("replay")
+        .arg(dir.join("game.json"))
+        .arg("--export-cast")
+        .arg(dir.join("game.cast"))
+        .output()
+        .unwrap();
+    (output.status.success(), String::from_utf8(output.stderr).unwrap())
+}
+
+/// The cast `termsweeper replay` makes of the given replay.
+fn cast(name: &str, replay: &str) -> String {
+    assert!(export_cast(name, replay).0);
+    let dir = std::env::temp_dir().join(format!("termsweeper-replay-{}", std::process::id())).join(name);
+    std::fs::read_to_string(dir.join("game.cast")).unwrap()
+}
+
+const MOVES: &str = r#""moves":[{"at":500,"command":{"reveal":[1,2]}}]"#;
+
+#[test]
+fn plays_back_a_board_matching_its_hash() {
+    let replay = format!(r#"{{"board":"*..\n...\n","board_hash":"d04add599af5f245",{}}}"#, MOVES);
+    assert!(export_cast("matching", &replay).0);
+}
+
+#[test]
+fn exports_an_asciinema_cast() {
+    let cast = cast("cast", &format!(r#"{{"board":"*..\n...\n",{}}}"#, MOVES));
+    let mut lines = cast.lines().map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap());
+    let header = lines.next().unwrap();
+    assert_eq!(header, serde_json::json!({ "version": 2, "width": 80, "height": 26 }));
+
+    // Each event is output at a time, which only goes forward: the board as it starts, then as the reveal leaves it
+    let events = lines.collect::<Vec<_>>();
+    let times = events.iter().map(|event| event[0].as_f64().unwrap()).collect::<Vec<_>>();
+    assert_eq!(times.first(), Some(&0.0));
+    assert_eq!(times.last(), Some(&0.5));
+    assert!(times.is_sorted(), "{:?}", times);
+    let mut screen = vt100::Parser::new(26, 80, 0);
+    for event in &events {
+        assert_eq!(event.as_array().unwrap().len(), 3);
+        assert_eq!(event[1], "o");
+        screen.process(event[2].as_str().unwrap().as_bytes());
+    }
+    let rows = screen.screen().contents().lines().take(4).map(str::to_string).collect::<Vec<_>>();
+    assert_eq!(rows, ["╭───╮", "│◎1░│", "│█1░│", "╰───╯"]);
+}
+
+#[test]
+fn plays_back_old_replays_without_a_hash() {
+    assert!(export_cast("old", &format!(r#"{{"board":"*..\n...\n",{}}}"#, MOVES)).0);
+}
+
+#[test]
+fn refuses_a_board_that_doesnt_match_its_hash() {
+    // The mine has moved since the replay was recorded
+    let replay = format!(r#"{{"board":".*.\n...\n","board_hash":"d04add599af5f245",{}}}"#, MOVES);
+    let (succeeded, stderr) = export_cast("edited", &replay);
+    assert!(!succeeded);
+    assert!(stderr.contains("the board isn't the one the replay was recorded on"), "{}", stderr);
+}
+
+#[test]
+fn checks_the_penalties_of_hardcore_games() {
+    // Flagging the safe cell the first reveal left next to the mine costs the penalty
+    let moves = r#""moves":[{"at":200,"command":{"reveal":[1,2]}},{"at":500,"command":{"flag":[1,0]}}]"#;
+    let penalties = r#""penalties":[{"at":500,"pos":[1,0],"time_ms":10000}]"#;
+    let replay = format!(r#"{{"board":"*..\n...\n","flag_penalty_ms":10000,{},{}}}"#, moves, penalties);
+    assert!(export_cast("hardcore", &replay).0);
+    // Without the penalty recorded, it doesn't match
+    let replay = format!(r#"{{"board":"*..\n...\n","flag_penalty_ms":10000,{}}}"#, moves);
+    let (succeeded, stderr) = export_cast("hardcore-edited", &replay);
+    assert!(!succeeded);
+    assert!(stderr.contains("doesn't add the same penalties"), "{}", stderr);
+}
+
+#[test]
+fn refuses_moves_out_of_order() {
+    let moves = r#""moves":[{"at":900,"command":{"reveal":[1,2]}},{"at":500,"command":{"flag":[0,0]}}]"#;
+    let (succeeded, stderr) = export_cast("out-of-order", &format!(r#"{{"board":"*..\n...\n",{}}}"#, moves));
+    assert!(!succeeded);
+    assert!(stderr.contains("the moves aren't in the order they were made in"), "{}", stderr);
+}
@@ -0,0 +1,107 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run termsweeper in script mode on the given board, saved under `name`, feeding it the script. Returns everything it
+/// wrote.
+fn run(name: &str, board: &str, script: &str) -> String {
+    run_with_config(name, board, "", script)
+}
+
+/// Like [`run`], but with the given config file. Each run gets a config directory of its own.
+fn run_with_config(name: &str, board: &str, config: &str, script: &str) -> String {
+    let path = dir().join(format!("{}.txt", name));
+    std::fs::write(&path, board).unwrap();
+    run_with_args(name, config, &["--board", path.to_str().unwrap()], script)
+}
+
+/// Where the runs keep their files.
+fn dir() -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("termsweeper-script-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run termsweeper in script mode with the given config file and arguments, feeding it the script.
+fn run_with_args(name: &str, config: &str, args: &[&str], script: &str) -> String {
+    let config_dir = dir().join(name);
+    std::fs::create_dir_all(config_dir.join("termsweeper")).unwrap();
+    std::fs::write(config_dir.join("termsweeper/config.toml"), config).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .arg("--script")
+        .args(args)
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn answers_each_command() {
+    let output = run("commands", ".*.\n...\n...\n", "reveal 0 0\n# a comment\n\nflag 1 0\nflag 1 0\nboard\n");
+    assert_eq!(output, "size 3 3 1\nok\nok\nok\n1██\n███\n███\n");
+}
+
+#[test]
+fn reports_the_end_of_the_game() {
+    let output = run("end", ".*\n..\n", "reveal 0 0\nreveal 0 1\nreveal 1 1\n");
+    assert_eq!(output, "size 2 2 1\nok\nok\nlost\nunchanged\n");
+}
+
+#[test]
+fn rejects_bad_commands() {
+    let output = run("bad", ".*\n..\n", "dig 0 0\nreveal 0\nreveal 5 5\nquit\nreveal 1 1\n");
+    assert_eq!(
+        output,
+        "size 2 2 1\nerror unknown command dig\nerror expected a row and a column\nerror (5, 5) is off the board\n",
+    );
+}
+
+// The config directory only comes from XDG_CONFIG_HOME on these platforms
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn draws_with_ascii_glyphs() {
+    let output = run_with_config("ascii", ".*.\n...\n...\n", "glyphs = \"ascii\"\n", "reveal 0 0\nflag 0 1\nboard\n");
+    assert_eq!(output, "size 3 3 1\nok\nok\n1F#\n###\n###\n");
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn plays_saved_presets() {
+    let config = "[presets.wide]\nsize = [4, 20]\nmines = 12\n";
+    let output = run_with_args("preset", config, &["--preset", "wide"], "");
+    assert_eq!(output, "size 4 20 12\n");
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn profiles_override_the_config() {
+    let board = dir().join("profile.txt");
+    std::fs::write(&board, ".*.\n...\n...\n").unwrap();
+    std::fs::create_dir_all(dir().join("profile/termsweeper/profiles")).unwrap();
+    std::fs::write(dir().join("profile/termsweeper/profiles/ascii.toml"), "glyphs = \"ascii\"\n").unwrap();
+    let args = ["--board", board.to_str().unwrap(), "--profile", "ascii"];
+    let output = run_with_args("profile", "glyphs = \"unicode\"\n", &args, "flag 0 1\nboard\n");
+    assert_eq!(output, "size 3 3 1\nok\n#F#\n###\n###\n");
+}
+
+#[test]
+fn verbose_runs_report_their_timings() {
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .args(["--script", "--verbose", "--preset", "beginner", "--generator", "no-guess"])
+        .env("XDG_CONFIG_HOME", dir().join("verbose"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // The timings go to stderr, so the replies on stdout stay as they were
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "size 9 9 10\n");
+    let report = String::from_utf8(output.stderr).unwrap();
+    assert!(report.lines().any(|line| line.starts_with("board generation      1 ")), "{}", report);
+    assert!(report.lines().any(|line| line.starts_with("no-guess check ")), "{}", report);
+}
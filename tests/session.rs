@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use termsweeper::config::Config;
+use termsweeper::game::{Field, Game};
+use termsweeper::session::Session;
+
+const TERMINAL_SIZE: (u16, u16) = (40, 30);
+
+/// Collects everything the games write, so it can be played back onto a virtual screen.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A session of headless games on seeded Beginner-sized boards, each new tab on the next seed, and a handle on what
+/// they have written so far.
+fn session() -> (Session, Output) {
+    let output = Output::default();
+    let game = |seed, output: &Output| {
+        let field = Field::with_rng((9, 9), 10, &mut StdRng::seed_from_u64(seed)).unwrap();
+        Game::headless(field, Config::default(), TERMINAL_SIZE, output.clone())
+    };
+    let mut seed = 0;
+    let tabs = output.clone();
+    let session = Session::new(game(seed, &output), TERMINAL_SIZE, move |_| {
+        seed += 1;
+        Ok(game(seed, &tabs))
+    });
+    (session, output)
+}
+
+fn press(session: &mut Session, code: KeyCode, modifiers: KeyModifiers) -> bool {
+    session.handle_event(Event::Key(KeyEvent::new(code, modifiers))).unwrap()
+}
+
+/// Render a frame and play everything written so far onto a virtual terminal. Returns the screen contents.
+fn screen(session: &mut Session, output: &Output) -> String {
+    session.render().unwrap();
+    let mut parser = vt100::Parser::new(TERMINAL_SIZE.1, TERMINAL_SIZE.0, 0);
+    parser.process(&output.0.borrow());
+    parser.screen().contents()
+}
+
+#[test]
+fn opens_and_switches_between_tabs() {
+    let (mut session, output) = session();
+    assert!(press(&mut session, KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert!(press(&mut session, KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert_eq!((session.tabs(), session.current()), (3, 2));
+    assert!(screen(&mut session, &output).contains("Game 3 of 3"));
+
+    // Switching wraps around, both ways
+    press(&mut session, KeyCode::Tab, KeyModifiers::CONTROL);
+    assert_eq!(session.current(), 0);
+    assert!(screen(&mut session, &output).contains("Game 1 of 3"));
+    press(&mut session, KeyCode::BackTab, KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+    assert_eq!(session.current(), 2);
+}
+
+#[test]
+fn tabs_keep_their_own_games() {
+    let (mut session, output) = session();
+    // Reveal a cell in the first tab, then look at a fresh board in the second
+    press(&mut session, KeyCode::Char(' '), KeyModifiers::NONE);
+    let flagged = screen(&mut session, &output);
+    press(&mut session, KeyCode::Char('t'), KeyModifiers::CONTROL);
+    assert_ne!(screen(&mut session, &output).lines().take(11).collect::<Vec<_>>(),
+        flagged.lines().take(11).collect::<Vec<_>>());
+    press(&mut session, KeyCode::Tab, KeyModifiers::CONTROL);
+    assert_eq!(screen(&mut session, &output).lines().take(11).collect::<Vec<_>>(),
+        flagged.lines().take(11).collect::<Vec<_>>());
+}
+
+#[test]
+fn quitting_closes_only_the_current_tab() {
+    let (mut session, output) = session();
+    press(&mut session, KeyCode::Char('t'), KeyModifiers::CONTROL);
+    press(&mut session, KeyCode::Char('t'), KeyModifiers::CONTROL);
+    press(&mut session, KeyCode::Tab, KeyModifiers::CONTROL);
+    press(&mut session, KeyCode::Tab, KeyModifiers::CONTROL);
+    assert_eq!(session.current(), 1);
+
+    assert!(press(&mut session, KeyCode::Char('q'), KeyModifiers::NONE));
+    assert_eq!((session.tabs(), session.current()), (2, 1));
+    let shown = screen(&mut session, &output);
+    assert!(shown.contains("Game 2 of 2"), "{}", shown);
+    // Closing the last tab in the row shows the one before it
+    assert!(press(&mut session, KeyCode::Char('w'), KeyModifiers::CONTROL));
+    assert_eq!((session.tabs(), session.current()), (1, 0));
+    // Only closing the last one of all ends the session
+    assert!(!press(&mut session, KeyCode::Char('q'), KeyModifiers::NONE));
+}
@@ -0,0 +1,229 @@
+// The data directory only comes from XDG_DATA_HOME on these platforms
+#![cfg(all(unix, not(target_os = "macos")))]
+
+use std::process::Command;
+
+/// Run `termsweeper stats` with the given stats file and arguments. Returns what it wrote.
+fn stats(name: &str, history: &str, args: &[&str]) -> String {
+    stats_with_config(name, history, "", args)
+}
+
+/// Like [`stats`], but with the given config file.
+fn stats_with_config(name: &str, history: &str, config: &str, args: &[&str]) -> String {
+    stats_in_terminal(name, history, config, &[], args)
+}
+
+/// Like [`stats_with_config`], but with the given environment variables set, like the ones that say what the terminal
+/// can show.
+fn stats_in_terminal(name: &str, history: &str, config: &str, env: &[(&str, &str)], args: &[&str]) -> String {
+    let dir = std::env::temp_dir().join(format!("termsweeper-stats-{}", std::process::id())).join(name);
+    std::fs::create_dir_all(dir.join("termsweeper")).unwrap();
+    std::fs::write(dir.join("termsweeper/stats.jsonl"), history).unwrap();
+    std::fs::write(dir.join("termsweeper/config.toml"), config).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_termsweeper"))
+        .arg("stats")
+        .args(args)
+        .env("XDG_DATA_HOME", &dir)
+        .env("XDG_CONFIG_HOME", &dir)
+        .envs(env.iter().copied())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const HISTORY: &str = concat!(
+    r#"{"timestamp":951782400,"preset":"beginner","size":[9,9],"mines":10,"won":true,"time_ms":12345,"bbbv":21,"#,
+    r#""clicks":30,"flags":9}"#, "\n",
+    r#"{"timestamp":1700000000,"preset":null,"size":[3,3],"mines":1,"won":false,"time_ms":2017,"bbbv":3,"clicks":3,"#,
+    r#""death":[2,2]}"#, "\n",
+);
+
+#[test]
+fn exports_csv() {
+    assert_eq!(stats("csv", HISTORY, &["--export", "csv"]), concat!(
+        "date,preset,rows,columns,mines,result,time_ms,3bv,clicks\n",
+        "2000-02-29T00:00:00Z,beginner,9,9,10,won,12345,21,30\n",
+        "2023-11-14T22:13:20Z,custom,3,3,1,lost,2017,3,3\n",
+    ));
+}
+
+#[test]
+fn exports_json() {
+    let output = stats("json", HISTORY, &["--export", "json"]);
+    let games = serde_json::from_str::<serde_json::Value>(&output).unwrap();
+    assert_eq!(games[1], serde_json::json!({
+        "date": "2023-11-14T22:13:20Z",
+        "preset": "custom",
+        "rows": 3,
+        "columns": 3,
+        "mines": 1,
+        "result": "lost",
+        "time_ms": 2017,
+        "3bv": 3,
+        "clicks": 3,
+    }));
+}
+
+#[test]
+fn charts_the_history() {
+    let output = stats("charts", HISTORY, &[]);
+    assert!(output.contains("Win rate"));
+    assert!(output.contains(&format!("Beginner     {} 100%", "█".repeat(30))));
+    assert!(output.contains(&format!("Custom       {} 0%", "░".repeat(30))));
+    assert!(output.contains("Beginner winning times"));
+}
+
+#[test]
+fn maps_where_games_were_lost() {
+    let output = stats("deaths", HISTORY, &[]);
+    assert!(output.contains("Where games were lost"));
+    assert!(output.contains(&format!("{}{}", "·".repeat(20), "█".repeat(10))));
+    assert!(output.contains("Corners 100%, edges 0%, inside 0%"));
+}
+
+#[test]
+fn leaves_out_deaths_off_the_board() {
+    let history = format!("{}{}", HISTORY, concat!(
+        r#"{"timestamp":1700000100,"preset":null,"size":[3,3],"mines":1,"won":false,"time_ms":2017,"bbbv":3,"#,
+        r#""clicks":3,"death":[5,0]}"#, "\n",
+        r#"{"timestamp":1700000200,"preset":null,"size":[0,0],"mines":0,"won":false,"time_ms":0,"bbbv":0,"#,
+        r#""clicks":0,"death":[0,0]}"#, "\n",
+    ));
+    let output = stats("off-board", &history, &[]);
+    // Only the game lost in the corner is mapped
+    assert!(output.contains(&format!("{}{}", "·".repeat(20), "█".repeat(10))), "{}", output);
+    assert!(output.contains("Corners 100%, edges 0%, inside 0%"), "{}", output);
+}
+
+#[test]
+fn boxes_are_drawn_in_the_border_style_and_color() {
+    let output = stats_with_config("borders", HISTORY, "border_style = \"double\"\nborder_color = \"red\"\n", &[]);
+    let first = output.lines().next().unwrap();
+    // Red is one of the 16 named colors, which every terminal has
+    assert!(first.starts_with("\x1b[38;5;9m╔═"), "{:?}", first);
+    assert!(first.ends_with("╗\x1b[39m"), "{:?}", first);
+}
+
+#[test]
+fn colors_are_fitted_to_the_terminal() {
+    // As the first line's border color escape, for a 24-bit color terminal, a 256-color one and a 16-color one
+    let escapes = |name: &str, color: &str| {
+        let config = format!("border_color = \"{}\"\n", color);
+        [("truecolor", "xterm"), ("", "xterm-256color"), ("", "xterm")].map(|(colorterm, term)| {
+            let env = [("COLORTERM", colorterm), ("TERM", term)];
+            let output = stats_in_terminal(&format!("{}-{}", name, term), HISTORY, &config, &env, &[]);
+            output.split('m').next().unwrap().to_string()
+        })
+    };
+    // Orange is nearest the cube's (255, 135, 0), and then dark yellow
+    assert_eq!(escapes("orange", "#ff8000"), ["\x1b[38;2;255;128;0", "\x1b[38;5;208", "\x1b[38;5;3"]);
+    // Grays fit the gray ramp rather than the cube, and then dark gray
+    assert_eq!(escapes("gray", "#808080"), ["\x1b[38;2;128;128;128", "\x1b[38;5;244", "\x1b[38;5;8"]);
+    // A palette color is left for terminals that have it, and is red for those that don't
+    assert_eq!(escapes("palette", "196"), ["\x1b[38;5;196", "\x1b[38;5;196", "\x1b[38;5;9"]);
+    // A pale blue from the cube is nearer light gray than any blue or cyan of the 16
+    assert_eq!(escapes("cube", "117"), ["\x1b[38;5;117", "\x1b[38;5;117", "\x1b[38;5;7"]);
+}
+
+#[test]
+fn shows_the_best_ladder_run() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-stats-{}", std::process::id())).join("ladder");
+    std::fs::create_dir_all(dir.join("termsweeper")).unwrap();
+    std::fs::write(dir.join("termsweeper/ladder.jsonl"), concat!(
+        r#"{"timestamp":1700000000,"times_ms":[10000,60000,200000]}"#, "\n",
+        r#"{"timestamp":1700001000,"times_ms":[8000,50500,150000]}"#, "\n",
+    )).unwrap();
+    let output = stats("ladder", HISTORY, &[]);
+    assert!(output.contains("Ladder: 2 runs, best 3:28"), "{}", output);
+}
+
+#[test]
+fn skips_damaged_records() {
+    // As a power cut partway through writing the last record might leave it
+    let history = format!("{}{}", HISTORY, r#"{"timestamp":1700000500,"preset":"begin"#);
+    let output = stats("damaged", &history, &[]);
+    assert!(output.contains("Skipped 1 damaged records"), "{}", output);
+    assert!(output.contains("Beginner          1     1   100%"), "{}", output);
+}
+
+#[test]
+fn imports_games_from_other_clients() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-stats-{}", std::process::id())).join("import");
+    std::fs::create_dir_all(&dir).unwrap();
+    let export = dir.join("arbiter.csv");
+    std::fs::write(&export, concat!(
+        "\u{feff}Date,Level,Time,3BV,Clicks\n",
+        "2024-03-01 12:00:00,Beginner,4.56,17,20\n",
+        "2024-03-02,Exp,\"101,5\",180,230\n",
+        "2024-03-03,Custom,20.00,30,40\n",
+    )).unwrap();
+    let import = stats("import", "", &["--import", export.to_str().unwrap()]);
+    assert_eq!(import, "Imported 2 games (skipped 1)\n");
+    let history = std::fs::read_to_string(dir.join("termsweeper/stats.jsonl")).unwrap();
+    assert_eq!(history, concat!(
+        r#"{"timestamp":1709294400,"preset":"beginner","size":[9,9],"mines":10,"won":true,"time_ms":4560,"bbbv":17,"#,
+        r#""clicks":20,"flags":null}"#, "\n",
+        r#"{"timestamp":1709337600,"preset":"expert","size":[16,30],"mines":99,"won":true,"time_ms":101500,"#,
+        r#""bbbv":180,"clicks":230,"flags":null}"#, "\n",
+    ));
+}
+
+#[test]
+fn imports_european_dates_and_undated_games() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-stats-{}", std::process::id())).join("dates");
+    std::fs::create_dir_all(&dir).unwrap();
+    let export = dir.join("viennasweeper.csv");
+    std::fs::write(&export, concat!(
+        "Level;Time;Date\n",
+        "Beginner;4,56;01.03.2024 12:00:00\n",
+        "Beginner;4,56;01.03.2024 12:00:00\n",
+        "Beginner;7,00;\n",
+        "Beginner;7,00;\n",
+    )).unwrap();
+    // Only the dated game that's there twice is taken for a duplicate: games with no date could be any two games
+    let import = stats("dates", "", &["--import", export.to_str().unwrap()]);
+    assert_eq!(import, "Imported 3 games (skipped 1)\n");
+    let history = std::fs::read_to_string(dir.join("termsweeper/stats.jsonl")).unwrap();
+    let timestamps = history.lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["timestamp"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(timestamps, [1709294400, 0, 0]);
+}
+
+#[test]
+fn saves_keep_backups() {
+    let dir = std::env::temp_dir().join(format!("termsweeper-stats-{}", std::process::id())).join("backup");
+    let file = |name: &str| std::fs::read_to_string(dir.join("termsweeper").join(name)).unwrap();
+    let import = |history: &str, row: &str| {
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = dir.join("arbiter.csv");
+        std::fs::write(&export, format!("Date,Level,Time\n{}\n", row)).unwrap();
+        assert_eq!(stats("backup", history, &["--import", export.to_str().unwrap()]), "Imported 1 games (skipped 0)\n");
+    };
+    let beginner = concat!(
+        r#"{"timestamp":1709294400,"preset":"beginner","size":[9,9],"mines":10,"won":true,"time_ms":4560,"bbbv":0,"#,
+        r#""clicks":0,"flags":null}"#, "\n",
+    );
+
+    // The damaged record is dropped from the file, but kept in the backup and in a copy of the file that outlasts it
+    let damaged = format!("{}{{\"timestamp\":17000\n", HISTORY);
+    import(&damaged, "2024-03-01 12:00:00,Beginner,4.56");
+    let saved = format!("{}{}", HISTORY, beginner);
+    assert_eq!(file("stats.jsonl"), saved);
+    assert_eq!(file("stats.jsonl.bak"), damaged);
+    let copies = || std::fs::read_dir(dir.join("termsweeper")).unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("stats.jsonl.damaged-"))
+        .collect::<Vec<_>>();
+    assert_eq!(copies().len(), 1);
+    assert_eq!(file(&copies()[0]), damaged);
+    assert!(!dir.join("termsweeper/stats.jsonl.tmp").exists());
+
+    // The next save backs up the one before it, leaving the copy as it was
+    import(&saved, "2024-03-02 12:00:00,Beginner,5.00");
+    assert_eq!(file("stats.jsonl.bak"), saved);
+    assert_eq!(file("stats.jsonl").lines().count(), 4);
+    assert_eq!(copies().len(), 1);
+    assert_eq!(file(&copies()[0]), damaged);
+}